@@ -0,0 +1,203 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+// Defaults of the active expire cycle of Redis, for effort 1
+const KEYS_PER_LOOP: usize = 20;
+const ACCEPTABLE_STALE: usize = 10; // percentage of expired keys
+const CYCLE_TIME_PERCENTAGE: u64 = 25; // of the period between two cycles
+
+// Period of the internal timer of the server that runs the cycle
+pub const CYCLE_PERIOD: Duration = Duration::from_millis(10);
+
+// Keys with an expiry, stored in a vector as well so
+// that random keys can be sampled in constant time
+#[derive(Debug, Default)]
+pub struct ExpiryIndex {
+    keys: Vec<Vec<u8>>,
+    deadlines: HashMap<Vec<u8>, (SystemTime, usize)>,
+}
+
+impl ExpiryIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn get(&self, key: &[u8]) -> Option<&SystemTime> {
+        self.deadlines.get(key).map(|(deadline, _)| deadline)
+    }
+
+    pub fn insert(&mut self, key: Vec<u8>, deadline: SystemTime) {
+        if let Some(entry) = self.deadlines.get_mut(&key) {
+            entry.0 = deadline;
+            return;
+        }
+        self.keys.push(key.clone());
+        self.deadlines.insert(key, (deadline, self.keys.len() - 1));
+    }
+
+    pub fn remove(&mut self, key: &[u8]) -> Option<SystemTime> {
+        let (deadline, index) = self.deadlines.remove(key)?;
+        self.swap_remove(index);
+        Some(deadline)
+    }
+
+    // Key and deadline stored at a position of the index
+    pub fn get_index(&self, index: usize) -> (&[u8], SystemTime) {
+        let key = &self.keys[index];
+        (key, self.deadlines[key].0)
+    }
+
+    // The last key takes the place of the removed one
+    fn swap_remove(&mut self, index: usize) {
+        self.keys.swap_remove(index);
+        if let Some(moved) = self.keys.get(index) {
+            if let Some(entry) = self.deadlines.get_mut(moved) {
+                entry.1 = index;
+            }
+        }
+    }
+}
+
+// Settings of the active expire cycle, derived from an effort
+// level between 1 and 10 like active-expire-effort in Redis.
+// A higher effort samples more keys, tolerates fewer expired
+// keys in memory and allows the cycle to run longer.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct ActiveExpireConfig {
+    pub keys_per_loop: usize,
+    pub acceptable_stale: usize,
+    pub time_limit: Duration,
+}
+
+impl ActiveExpireConfig {
+    pub fn with_effort(effort: u8) -> Self {
+        let effort = effort.clamp(1, 10) as usize - 1;
+        let percentage = CYCLE_TIME_PERCENTAGE + 2 * effort as u64;
+        Self {
+            keys_per_loop: KEYS_PER_LOOP + KEYS_PER_LOOP / 4 * effort,
+            acceptable_stale: ACCEPTABLE_STALE - effort,
+            time_limit: CYCLE_PERIOD * percentage as u32 / 100,
+        }
+    }
+}
+
+impl Default for ActiveExpireConfig {
+    fn default() -> Self {
+        Self::with_effort(1)
+    }
+}
+
+// xorshift64* generator, enough to pick the keys to sample
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        // the state must never be zero
+        Self(seed | 1)
+    }
+
+    pub fn from_time() -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0);
+        Self::new(nanos)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545f4914f6cdd1d)
+    }
+
+    // Random number in 0..bound, bound being greater than zero
+    pub fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(seconds: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(seconds)
+    }
+
+    #[test]
+    fn test_expiry_index_insert_get() {
+        let mut index = ExpiryIndex::new();
+        index.insert(b"a".to_vec(), time(1));
+        index.insert(b"b".to_vec(), time(2));
+        index.insert(b"a".to_vec(), time(3));
+        assert_eq!(index.len(), 2);
+        assert_eq!(index.get(b"a"), Some(&time(3)));
+        assert_eq!(index.get(b"c"), None);
+    }
+
+    #[test]
+    fn test_expiry_index_remove() {
+        let mut index = ExpiryIndex::new();
+        for (i, key) in [b"a", b"b", b"c"].iter().enumerate() {
+            index.insert(key.to_vec(), time(i as u64));
+        }
+        assert_eq!(index.remove(b"a"), Some(time(0)));
+        assert_eq!(index.remove(b"a"), None);
+        // the last key was moved in place of the removed one
+        assert_eq!(index.get_index(0), (b"c".as_slice(), time(2)));
+        assert_eq!(index.remove(b"c"), Some(time(2)));
+        assert_eq!(index.get_index(0), (b"b".as_slice(), time(1)));
+        assert_eq!(index.remove(b"b"), Some(time(1)));
+        assert!(index.is_empty());
+    }
+
+    #[test]
+    fn test_config_with_effort() {
+        assert_eq!(
+            ActiveExpireConfig::with_effort(1),
+            ActiveExpireConfig {
+                keys_per_loop: 20,
+                acceptable_stale: 10,
+                time_limit: Duration::from_micros(2500),
+            }
+        );
+        assert_eq!(
+            ActiveExpireConfig::with_effort(10),
+            ActiveExpireConfig {
+                keys_per_loop: 65,
+                acceptable_stale: 1,
+                time_limit: Duration::from_micros(4300),
+            }
+        );
+        assert_eq!(
+            ActiveExpireConfig::with_effort(0),
+            ActiveExpireConfig::with_effort(1)
+        );
+        assert_eq!(
+            ActiveExpireConfig::with_effort(20),
+            ActiveExpireConfig::with_effort(10)
+        );
+    }
+
+    #[test]
+    fn test_rng_below() {
+        let mut rng = Rng::new(42);
+        let mut seen = [false; 10];
+        for _ in 0..1000 {
+            seen[rng.below(10)] = true;
+        }
+        assert!(seen.iter().all(|&s| s));
+    }
+}
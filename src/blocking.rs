@@ -0,0 +1,455 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    time::{Duration, SystemTime},
+};
+
+use crate::{
+    number::parse_f64,
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{ServerError, ServerValue},
+    storage::Storage,
+    storage_result::StorageResult,
+};
+
+// Operation of a blocking command, which is attempted when the command
+// runs and again each time one of its keys receives values
+#[derive(Debug, PartialEq)]
+pub enum BlockingCommand {
+    // BLPOP and BRPOP
+    Pop {
+        keys: Vec<Vec<u8>>,
+        front: bool,
+    },
+    // BLMPOP, and LMPOP which never blocks
+    MultiPop {
+        keys: Vec<Vec<u8>>,
+        front: bool,
+        count: usize,
+    },
+    // BZPOPMIN and BZPOPMAX
+    ZPop {
+        keys: Vec<Vec<u8>>,
+        max: bool,
+    },
+    // BZMPOP, and ZMPOP which never blocks
+    ZMultiPop {
+        keys: Vec<Vec<u8>>,
+        max: bool,
+        count: usize,
+    },
+    // BLMOVE and BRPOPLPUSH
+    Move {
+        source: Vec<u8>,
+        destination: Vec<u8>,
+        from_front: bool,
+        to_front: bool,
+    },
+}
+
+impl BlockingCommand {
+    // Run the operation, returns None if there is nothing to pop yet.
+    // When a key received values only that key is popped from, as
+    // the other keys may not even hold the type the command pops.
+    pub fn attempt(
+        &self,
+        storage: &mut Storage,
+        ready: Option<&[u8]>,
+    ) -> StorageResult<Option<RESP>> {
+        match self {
+            BlockingCommand::Pop { keys, front } => {
+                for key in candidates(keys, ready) {
+                    if let Some(mut values) = storage.pop(key, 1, *front)? {
+                        if let Some(value) = values.pop() {
+                            return Ok(Some(RESP::Array(vec![
+                                RESP::BulkString(key.clone()),
+                                RESP::BulkString(value),
+                            ])));
+                        }
+                    }
+                }
+                Ok(None)
+            }
+            BlockingCommand::MultiPop { keys, front, count } => {
+                for key in candidates(keys, ready) {
+                    if let Some(values) = storage.pop(key, *count, *front)? {
+                        return Ok(Some(RESP::Array(vec![
+                            RESP::BulkString(key.clone()),
+                            RESP::Array(values.into_iter().map(RESP::BulkString).collect()),
+                        ])));
+                    }
+                }
+                Ok(None)
+            }
+            BlockingCommand::ZPop { keys, max } => {
+                for key in candidates(keys, ready) {
+                    if let Some(mut entries) = storage.zset_pop(key, 1, *max)? {
+                        if let Some((member, score)) = entries.pop() {
+                            return Ok(Some(RESP::Array(vec![
+                                RESP::BulkString(key.clone()),
+                                RESP::BulkString(member),
+                                RESP::Double(score),
+                            ])));
+                        }
+                    }
+                }
+                Ok(None)
+            }
+            BlockingCommand::ZMultiPop { keys, max, count } => {
+                for key in candidates(keys, ready) {
+                    if let Some(entries) = storage.zset_pop(key, *count, *max)? {
+                        let entries = entries
+                            .into_iter()
+                            .map(|(member, score)| {
+                                RESP::Array(vec![RESP::BulkString(member), RESP::Double(score)])
+                            })
+                            .collect();
+                        return Ok(Some(RESP::Array(vec![
+                            RESP::BulkString(key.clone()),
+                            RESP::Array(entries),
+                        ])));
+                    }
+                }
+                Ok(None)
+            }
+            BlockingCommand::Move {
+                source,
+                destination,
+                from_front,
+                to_front,
+            } => Ok(storage
+                .list_move(source, destination, *from_front, *to_front)?
+                .map(RESP::BulkString)),
+        }
+    }
+
+    // Keys whose values can unblock the command
+    pub fn keys(&self) -> &[Vec<u8>] {
+        match self {
+            BlockingCommand::Pop { keys, .. }
+            | BlockingCommand::MultiPop { keys, .. }
+            | BlockingCommand::ZPop { keys, .. }
+            | BlockingCommand::ZMultiPop { keys, .. } => keys,
+            BlockingCommand::Move { source, .. } => std::slice::from_ref(source),
+        }
+    }
+
+    // Type of the keys the command pops from, a ready key
+    // of another type leaves the client blocked, as in Redis
+    pub fn key_type(&self) -> &'static str {
+        match self {
+            BlockingCommand::ZPop { .. } | BlockingCommand::ZMultiPop { .. } => "zset",
+            _ => "list",
+        }
+    }
+
+    // Reply sent when the command times out
+    pub fn timeout_reply(&self) -> RESP {
+        match self {
+            BlockingCommand::Move { .. } => RESP::Null,
+            _ => RESP::NullArray,
+        }
+    }
+}
+
+// Keys a command pops from, only the ready one if given
+fn candidates<'a>(
+    keys: &'a [Vec<u8>],
+    ready: Option<&'a [u8]>,
+) -> impl Iterator<Item = &'a Vec<u8>> {
+    keys.iter()
+        .filter(move |key| ready.is_none_or(|ready| key.as_slice() == ready))
+}
+
+// Parse the timeout of a blocking command, in seconds with
+// decimals allowed, 0 meaning waiting forever
+pub fn parse_timeout(timeout: &[u8]) -> Result<Option<Duration>, ServerError> {
+    let timeout = parse_f64(timeout)
+        .filter(|t| t.is_finite())
+        .ok_or(ServerError::TimeoutNotAFloat)?;
+    if timeout < 0.0 {
+        return Err(ServerError::NegativeTimeout);
+    }
+    if timeout == 0.0 {
+        return Ok(None);
+    }
+    Duration::try_from_secs_f64(timeout)
+        .map(Some)
+        .map_err(|_| ServerError::TimeoutNotAFloat)
+}
+
+pub struct BlockedClient {
+    // carries the sender to reply to the client
+    pub request: Request,
+    pub command: BlockingCommand,
+    pub deadline: Option<SystemTime>,
+    // requests the client sent while blocked,
+    // processed in order once it is unblocked
+    pub queued: VecDeque<Request>,
+}
+
+// Clients waiting on blocking commands, served in the order
+// they blocked for each key, as Redis does
+#[derive(Default)]
+pub struct BlockedClients {
+    clients: HashMap<u64, BlockedClient>,
+    by_key: HashMap<Vec<u8>, VecDeque<u64>>,
+    // identifier of the blocked client of each connection
+    by_connection: HashMap<u64, u64>,
+    next_id: u64,
+}
+
+impl BlockedClients {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[cfg(test)]
+    pub fn len(&self) -> usize {
+        self.clients.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.clients.is_empty()
+    }
+
+    pub fn block(&mut self, client: BlockedClient) {
+        let id = self.next_id;
+        self.next_id += 1;
+        for key in client.command.keys() {
+            let waiting = self.by_key.entry(key.clone()).or_default();
+            // a key given twice does not make the client wait twice
+            if waiting.back() != Some(&id) {
+                waiting.push_back(id);
+            }
+        }
+        self.by_connection.insert(client.request.client_id, id);
+        self.clients.insert(id, client);
+    }
+
+    // Queue a request sent by a blocked client, returns
+    // the request back if its client is not blocked
+    pub fn queue_if_blocked(&mut self, request: Request) -> Option<Request> {
+        match self
+            .by_connection
+            .get(&request.client_id)
+            .and_then(|id| self.clients.get_mut(id))
+        {
+            Some(client) => {
+                client.queued.push_back(request);
+                None
+            }
+            None => Some(request),
+        }
+    }
+
+    // Identifiers of the clients blocked on a key, first blocked first
+    pub fn waiting_on(&self, key: &[u8]) -> Vec<u64> {
+        self.by_key
+            .get(key)
+            .map(|waiting| waiting.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn get(&self, id: u64) -> Option<&BlockedClient> {
+        self.clients.get(&id)
+    }
+
+    pub fn unblock(&mut self, id: u64) -> Option<BlockedClient> {
+        let client = self.clients.remove(&id)?;
+        self.by_connection.remove(&client.request.client_id);
+        for key in client.command.keys() {
+            if let Some(waiting) = self.by_key.get_mut(key) {
+                waiting.retain(|&waiting| waiting != id);
+                if waiting.is_empty() {
+                    self.by_key.remove(key);
+                }
+            }
+        }
+        Some(client)
+    }
+
+    // Unblock the clients whose deadline has passed
+    pub fn timed_out(&mut self, now: SystemTime) -> Vec<BlockedClient> {
+        let ids: Vec<u64> = self
+            .clients
+            .iter()
+            .filter(|(_, client)| client.deadline.is_some_and(|deadline| deadline <= now))
+            .map(|(&id, _)| id)
+            .collect();
+        ids.into_iter().filter_map(|id| self.unblock(id)).collect()
+    }
+
+    // Forget the clients which disconnected while blocked
+    pub fn remove_disconnected(&mut self) {
+        let ids: Vec<u64> = self
+            .clients
+            .iter()
+            .filter(|(_, client)| client.request.sender.is_closed())
+            .map(|(&id, _)| id)
+            .collect();
+        for id in ids {
+            self.unblock(id);
+        }
+    }
+}
+
+// Run a blocking command, replying at once if there is something
+// to pop, otherwise parking the client until there is or until the
+// timeout, if any
+pub async fn run(
+    server: &mut Server,
+    request: &Request,
+    command: BlockingCommand,
+    timeout: Option<Duration>,
+) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    match command.attempt(storage, None) {
+        Ok(Some(reply)) => request.data(ServerValue::RESP(reply)).await,
+        Ok(None) => {
+            let deadline = match timeout {
+                Some(timeout) => match storage.now().checked_add(timeout) {
+                    Some(deadline) => Some(deadline),
+                    None => {
+                        request.error(ServerError::TimeoutNotAFloat).await;
+                        return;
+                    }
+                },
+                None => None,
+            };
+            server.blocked.block(BlockedClient {
+                request: Request {
+                    value: RESP::Null,
+                    sender: request.sender.clone(),
+                    client_id: request.client_id,
+                },
+                command,
+                deadline,
+                queued: VecDeque::new(),
+            });
+        }
+        Err(e) => request.error(ServerError::from(e)).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server_result::ServerMessage;
+    use tokio::sync::mpsc;
+
+    fn blocked_client(
+        client_id: u64,
+        keys: &[&str],
+        deadline: Option<SystemTime>,
+    ) -> BlockedClient {
+        let (sender, _) = mpsc::channel::<ServerMessage>(32);
+        BlockedClient {
+            request: Request {
+                value: RESP::Null,
+                sender,
+                client_id,
+            },
+            command: BlockingCommand::Pop {
+                keys: keys.iter().map(|k| k.as_bytes().to_vec()).collect(),
+                front: true,
+            },
+            deadline,
+            queued: VecDeque::new(),
+        }
+    }
+
+    #[test]
+    fn test_parse_timeout() {
+        assert_eq!(parse_timeout(b"0"), Ok(None));
+        assert_eq!(parse_timeout(b"1.5"), Ok(Some(Duration::from_millis(1500))));
+        assert_eq!(parse_timeout(b"-1"), Err(ServerError::NegativeTimeout));
+        assert_eq!(parse_timeout(b"soon"), Err(ServerError::TimeoutNotAFloat));
+        assert_eq!(parse_timeout(b"inf"), Err(ServerError::TimeoutNotAFloat));
+    }
+
+    #[test]
+    fn test_block_and_unblock() {
+        let mut blocked = BlockedClients::new();
+        blocked.block(blocked_client(1, &["a", "b"], None));
+        blocked.block(blocked_client(2, &["b", "b"], None));
+        assert_eq!(blocked.len(), 2);
+        assert_eq!(blocked.waiting_on(b"a"), vec![0]);
+        assert_eq!(blocked.waiting_on(b"b"), vec![0, 1]);
+
+        assert!(blocked.unblock(0).is_some());
+        assert!(blocked.unblock(0).is_none());
+        assert_eq!(blocked.waiting_on(b"a"), Vec::<u64>::new());
+        assert_eq!(blocked.waiting_on(b"b"), vec![1]);
+    }
+
+    #[test]
+    fn test_timed_out() {
+        let now = SystemTime::now();
+        let mut blocked = BlockedClients::new();
+        blocked.block(blocked_client(1, &["a"], Some(now)));
+        blocked.block(blocked_client(
+            2,
+            &["a"],
+            Some(now + Duration::from_secs(1)),
+        ));
+        blocked.block(blocked_client(3, &["a"], None));
+        assert_eq!(blocked.timed_out(now).len(), 1);
+        assert_eq!(blocked.waiting_on(b"a"), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_queue_if_blocked() {
+        let mut blocked = BlockedClients::new();
+        let (sender, _receiver) = mpsc::channel::<ServerMessage>(32);
+        let mut client = blocked_client(1, &["a"], None);
+        client.request.sender = sender.clone();
+        blocked.block(client);
+
+        let request = Request {
+            value: RESP::Null,
+            sender: sender.clone(),
+            client_id: 1,
+        };
+        assert!(blocked.queue_if_blocked(request).is_none());
+        assert_eq!(blocked.get(0).unwrap().queued.len(), 1);
+
+        let (other, _) = mpsc::channel::<ServerMessage>(32);
+        let request = Request {
+            value: RESP::Null,
+            sender: other,
+            client_id: 2,
+        };
+        assert!(blocked.queue_if_blocked(request).is_some());
+
+        // once unblocked the requests of the connection run again
+        blocked.unblock(0);
+        let request = Request {
+            value: RESP::Null,
+            sender,
+            client_id: 1,
+        };
+        assert!(blocked.queue_if_blocked(request).is_some());
+    }
+
+    #[test]
+    fn test_remove_disconnected() {
+        let mut blocked = BlockedClients::new();
+        // the receiver of the client is dropped at once
+        blocked.block(blocked_client(1, &["a"], None));
+        let (sender, _receiver) = mpsc::channel::<ServerMessage>(32);
+        let mut client = blocked_client(2, &["a"], None);
+        client.request.sender = sender;
+        blocked.block(client);
+        blocked.remove_disconnected();
+        assert_eq!(blocked.waiting_on(b"a"), vec![1]);
+    }
+}
@@ -0,0 +1,99 @@
+use std::time::{Instant, SystemTime};
+
+#[cfg(test)]
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, UNIX_EPOCH},
+};
+
+// Source of the current time for the storage, which needs
+// both a time that never goes back, to compare deadlines,
+// and its unix value, for EXPIREAT or EXPIRETIME
+pub trait Clock: Send + Sync {
+    fn now(&self) -> SystemTime;
+}
+
+// Unix time read once at start, then advanced by a monotonic
+// clock, so that adjustments of the system time (NTP) do not
+// expire all the keys at once or keep them alive forever
+pub struct MonotonicClock {
+    unix_start: SystemTime,
+    start: Instant,
+}
+
+impl MonotonicClock {
+    pub fn new() -> Self {
+        Self {
+            unix_start: SystemTime::now(),
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Clock for MonotonicClock {
+    fn now(&self) -> SystemTime {
+        self.unix_start + self.start.elapsed()
+    }
+}
+
+// Clock that only moves when advanced, clones share the same time
+#[cfg(test)]
+#[derive(Clone)]
+pub struct ManualClock {
+    nanos: Arc<AtomicU64>, // since the unix epoch
+}
+
+#[cfg(test)]
+impl ManualClock {
+    pub fn new(start: SystemTime) -> Self {
+        let nanos = start
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0);
+        Self {
+            nanos: Arc::new(AtomicU64::new(nanos)),
+        }
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        self.nanos
+            .fetch_add(duration.as_nanos() as u64, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+impl Clock for ManualClock {
+    fn now(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_nanos(self.nanos.load(Ordering::SeqCst))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_monotonic_clock() {
+        let clock = MonotonicClock::new();
+        let first = clock.now();
+        let second = clock.now();
+        assert!(second >= first);
+        let drift = SystemTime::now()
+            .duration_since(first)
+            .unwrap_or(Duration::ZERO);
+        assert!(drift < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_manual_clock() {
+        let start = UNIX_EPOCH + Duration::from_secs(1700000000);
+        let clock = ManualClock::new(start);
+        let shared = clock.clone();
+        assert_eq!(clock.now(), start);
+        shared.advance(Duration::from_millis(1500));
+        assert_eq!(clock.now(), start + Duration::from_millis(1500));
+    }
+}
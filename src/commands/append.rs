@@ -0,0 +1,82 @@
+use crate::{
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{ServerError, ServerValue},
+};
+
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    if command.len() != 3 {
+        request
+            .error(ServerError::WrongNumberOfArguments(String::from("append")))
+            .await;
+        return;
+    }
+
+    match storage.append(&command[1], &command[2]) {
+        Ok(length) => {
+            request
+                .data(ServerValue::RESP(RESP::Integer(length as i64)))
+                .await
+        }
+        Err(e) => request.error(ServerError::from(e)).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server_result::ServerMessage;
+    use crate::storage::Storage;
+    use tokio::sync::mpsc;
+
+    #[tokio::test]
+    async fn test_command() {
+        let mut server = Server::with_new(Storage::new());
+        for (value, length) in [("Hello", 5), (" World", 11)] {
+            let cmd = vec![
+                b"append".to_vec(),
+                b"key".to_vec(),
+                value.as_bytes().to_vec(),
+            ];
+            let (connection_sender, mut connection_receiver) = mpsc::channel::<ServerMessage>(32);
+            let request = Request {
+                value: RESP::Null,
+                sender: connection_sender,
+                client_id: 0,
+            };
+            command(&mut server, &request, &cmd).await;
+            assert_eq!(
+                connection_receiver.try_recv().unwrap(),
+                ServerMessage::Data(ServerValue::RESP(RESP::Integer(length)))
+            );
+        }
+        let storage = server.storage.as_mut().unwrap();
+        assert_eq!(storage.get(b"key").unwrap(), Some(b"Hello World".to_vec()));
+    }
+
+    #[tokio::test]
+    async fn test_wrong_syntax() {
+        let mut server = Server::with_new(Storage::new());
+        let cmd = vec![b"append".to_vec(), b"key".to_vec()];
+        let (connection_sender, mut connection_receiver) = mpsc::channel::<ServerMessage>(32);
+        let request = Request {
+            value: RESP::Null,
+            sender: connection_sender,
+            client_id: 0,
+        };
+        command(&mut server, &request, &cmd).await;
+        assert_eq!(
+            connection_receiver.try_recv().unwrap(),
+            ServerMessage::Error(ServerError::WrongNumberOfArguments("append".to_string()))
+        );
+    }
+}
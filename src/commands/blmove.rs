@@ -0,0 +1,100 @@
+use crate::{
+    blocking::{self, parse_timeout, BlockingCommand},
+    commands::lmove::parse_side,
+    request::Request,
+    server::Server,
+    server_result::{command_to_string, ServerError},
+};
+
+// BLMOVE and BRPOPLPUSH, the latter being
+// BLMOVE source destination RIGHT LEFT timeout
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let name = String::from_utf8_lossy(&command[0]).to_lowercase();
+    let arity = if name == "blmove" { 6 } else { 4 };
+    if command.len() != arity {
+        request
+            .error(ServerError::WrongNumberOfArguments(name))
+            .await;
+        return;
+    }
+
+    let sides = if name == "blmove" {
+        parse_side(&command[3]).zip(parse_side(&command[4]))
+    } else {
+        Some((false, true))
+    };
+    let Some((from_front, to_front)) = sides else {
+        request
+            .error(ServerError::CommandSyntaxError(command_to_string(command)))
+            .await;
+        return;
+    };
+    let timeout = match parse_timeout(&command[arity - 1]) {
+        Ok(timeout) => timeout,
+        Err(e) => {
+            request.error(e).await;
+            return;
+        }
+    };
+    let operation = BlockingCommand::Move {
+        source: command[1].clone(),
+        destination: command[2].clone(),
+        from_front,
+        to_front,
+    };
+    blocking::run(server, request, operation, timeout).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::send;
+    use crate::resp::RESP;
+    use crate::server_result::{ServerMessage, ServerValue};
+    use crate::storage::Storage;
+
+    #[tokio::test]
+    async fn test_move_at_once() {
+        let mut storage = Storage::new();
+        storage
+            .push(b"source", &[b"a".to_vec(), b"b".to_vec()], false, false)
+            .unwrap();
+        let mut server = Server::with_new(storage);
+        let mut receiver = send(&mut server, "BRPOPLPUSH source destination 0").await;
+        assert_eq!(
+            receiver.try_recv().unwrap(),
+            ServerMessage::Data(ServerValue::RESP(RESP::BulkString(b"b".to_vec())))
+        );
+        let mut receiver = send(&mut server, "BLMOVE source destination LEFT RIGHT 0").await;
+        assert_eq!(
+            receiver.try_recv().unwrap(),
+            ServerMessage::Data(ServerValue::RESP(RESP::BulkString(b"a".to_vec())))
+        );
+        let storage = server.storage.as_mut().unwrap();
+        assert_eq!(
+            storage.list_range(b"destination", 0, -1).unwrap(),
+            vec![b"b".to_vec(), b"a".to_vec()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_blocks_on_source() {
+        let mut server = Server::with_new(Storage::new());
+        let mut receiver = send(&mut server, "BLMOVE source destination LEFT LEFT 0").await;
+        assert!(receiver.try_recv().is_err());
+        assert_eq!(server.blocked.waiting_on(b"source"), vec![0]);
+        assert!(server.blocked.waiting_on(b"destination").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_wrong_syntax() {
+        let mut server = Server::with_new(Storage::new());
+        let mut receiver = send(&mut server, "BLMOVE source destination UP LEFT 0").await;
+        assert_eq!(
+            receiver.try_recv().unwrap(),
+            ServerMessage::Error(ServerError::CommandSyntaxError(String::from(
+                "BLMOVE source destination UP LEFT 0"
+            )))
+        );
+    }
+}
@@ -0,0 +1,83 @@
+use crate::{
+    blocking::{self, parse_timeout, BlockingCommand},
+    request::Request,
+    server::Server,
+    server_result::ServerError,
+};
+
+// BLPOP and BRPOP, popping from the first non empty list
+// among the keys, or waiting for one to receive values
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let name = String::from_utf8_lossy(&command[0]).to_lowercase();
+    if command.len() < 3 {
+        request
+            .error(ServerError::WrongNumberOfArguments(name))
+            .await;
+        return;
+    }
+
+    let timeout = match parse_timeout(&command[command.len() - 1]) {
+        Ok(timeout) => timeout,
+        Err(e) => {
+            request.error(e).await;
+            return;
+        }
+    };
+    let operation = BlockingCommand::Pop {
+        keys: command[1..command.len() - 1].to_vec(),
+        front: name == "blpop",
+    };
+    blocking::run(server, request, operation, timeout).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::send;
+    use crate::resp::RESP;
+    use crate::server_result::{ServerMessage, ServerValue};
+    use crate::storage::Storage;
+
+    #[tokio::test]
+    async fn test_pop_at_once() {
+        let mut storage = Storage::new();
+        storage
+            .push(b"b", &[b"x".to_vec(), b"y".to_vec()], false, false)
+            .unwrap();
+        let mut server = Server::with_new(storage);
+        let mut receiver = send(&mut server, "BRPOP a b 0").await;
+        assert_eq!(
+            receiver.try_recv().unwrap(),
+            ServerMessage::Data(ServerValue::RESP(RESP::Array(vec![
+                RESP::BulkString(b"b".to_vec()),
+                RESP::BulkString(b"y".to_vec()),
+            ])))
+        );
+        assert!(server.blocked.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_blocks_on_empty_lists() {
+        let mut server = Server::with_new(Storage::new());
+        let mut receiver = send(&mut server, "BLPOP a b 1.5").await;
+        assert!(receiver.try_recv().is_err());
+        assert_eq!(server.blocked.len(), 1);
+        assert_eq!(server.blocked.waiting_on(b"b"), vec![0]);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_timeout() {
+        let mut server = Server::with_new(Storage::new());
+        let mut receiver = send(&mut server, "BLPOP a -1").await;
+        assert_eq!(
+            receiver.try_recv().unwrap(),
+            ServerMessage::Error(ServerError::NegativeTimeout)
+        );
+        let mut receiver = send(&mut server, "BLPOP a").await;
+        assert_eq!(
+            receiver.try_recv().unwrap(),
+            ServerMessage::Error(ServerError::WrongNumberOfArguments(String::from("blpop")))
+        );
+        assert!(server.blocked.is_empty());
+    }
+}
@@ -0,0 +1,93 @@
+use crate::{
+    blocking::{self, parse_timeout, BlockingCommand},
+    request::Request,
+    server::Server,
+    server_result::ServerError,
+};
+
+// BZPOPMIN and BZPOPMAX, popping from the first non empty sorted
+// set among the keys, or waiting for one to receive members
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let name = String::from_utf8_lossy(&command[0]).to_lowercase();
+    if command.len() < 3 {
+        request
+            .error(ServerError::WrongNumberOfArguments(name))
+            .await;
+        return;
+    }
+
+    let timeout = match parse_timeout(&command[command.len() - 1]) {
+        Ok(timeout) => timeout,
+        Err(e) => {
+            request.error(e).await;
+            return;
+        }
+    };
+    let operation = BlockingCommand::ZPop {
+        keys: command[1..command.len() - 1].to_vec(),
+        max: name == "bzpopmax",
+    };
+    blocking::run(server, request, operation, timeout).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::send;
+    use crate::resp::RESP;
+    use crate::server_result::{ServerMessage, ServerValue};
+    use crate::storage::Storage;
+    use crate::zset::ZAddOptions;
+
+    #[tokio::test]
+    async fn test_pop_at_once() {
+        let mut storage = Storage::new();
+        storage
+            .zset_add(
+                b"b",
+                &[(1.0, b"x".to_vec()), (2.0, b"y".to_vec())],
+                &ZAddOptions::default(),
+            )
+            .unwrap();
+        let mut server = Server::with_new(storage);
+        let mut receiver = send(&mut server, "BZPOPMAX a b 0").await;
+        assert_eq!(
+            receiver.try_recv().unwrap(),
+            ServerMessage::Data(ServerValue::RESP(RESP::Array(vec![
+                RESP::BulkString(b"b".to_vec()),
+                RESP::BulkString(b"y".to_vec()),
+                RESP::Double(2.0),
+            ])))
+        );
+        let mut receiver = send(&mut server, "BZPOPMIN b 0").await;
+        assert_eq!(
+            receiver.try_recv().unwrap(),
+            ServerMessage::Data(ServerValue::RESP(RESP::Array(vec![
+                RESP::BulkString(b"b".to_vec()),
+                RESP::BulkString(b"x".to_vec()),
+                RESP::Double(1.0),
+            ])))
+        );
+        assert!(server.blocked.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_blocks_on_empty_sorted_sets() {
+        let mut server = Server::with_new(Storage::new());
+        let mut receiver = send(&mut server, "BZPOPMIN a b 1.5").await;
+        assert!(receiver.try_recv().is_err());
+        assert_eq!(server.blocked.len(), 1);
+        assert_eq!(server.blocked.waiting_on(b"b"), vec![0]);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_timeout() {
+        let mut server = Server::with_new(Storage::new());
+        let mut receiver = send(&mut server, "BZPOPMIN a x").await;
+        assert_eq!(
+            receiver.try_recv().unwrap(),
+            ServerMessage::Error(ServerError::TimeoutNotAFloat)
+        );
+        assert!(server.blocked.is_empty());
+    }
+}
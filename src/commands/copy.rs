@@ -0,0 +1,114 @@
+use crate::{
+    number::parse_i64,
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{command_to_string, ServerError, ServerValue},
+};
+
+// COPY source destination [DB destination-db] [REPLACE],
+// there is a single database so only DB 0 is accepted
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    if command.len() < 3 {
+        request
+            .error(ServerError::WrongNumberOfArguments(String::from("copy")))
+            .await;
+        return;
+    }
+
+    let mut replace = false;
+    let mut idx: usize = 3;
+    while idx < command.len() {
+        match command[idx].to_ascii_lowercase().as_slice() {
+            b"replace" => {
+                replace = true;
+                idx += 1;
+            }
+            b"db" if idx + 1 < command.len() => {
+                match parse_i64(&command[idx + 1]) {
+                    Some(0) => {}
+                    Some(_) => {
+                        request.error(ServerError::DBIndexOutOfRange).await;
+                        return;
+                    }
+                    None => {
+                        request.error(ServerError::NotAnInteger).await;
+                        return;
+                    }
+                }
+                idx += 2;
+            }
+            _ => {
+                request
+                    .error(ServerError::CommandSyntaxError(command_to_string(command)))
+                    .await;
+                return;
+            }
+        }
+    }
+    if command[1] == command[2] {
+        request.error(ServerError::SameObject).await;
+        return;
+    }
+
+    let copied = storage.copy(&command[1], &command[2], replace);
+    request
+        .data(ServerValue::RESP(RESP::Integer(copied as i64)))
+        .await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::{add_strings, run, server_with};
+    use crate::server_result::ServerMessage;
+
+    fn integer(value: i64) -> ServerMessage {
+        ServerMessage::Data(ServerValue::RESP(RESP::Integer(value)))
+    }
+
+    fn server_with_keys() -> Server {
+        server_with(|storage| add_strings(storage, &[("a", "a"), ("b", "b")]))
+    }
+
+    #[tokio::test]
+    async fn test_command() {
+        let mut server = server_with_keys();
+        assert_eq!(run(&mut server, "COPY a c").await, integer(1));
+        assert_eq!(run(&mut server, "COPY a b").await, integer(0));
+        assert_eq!(run(&mut server, "COPY missing d").await, integer(0));
+        assert_eq!(run(&mut server, "COPY a b DB 0 REPLACE").await, integer(1));
+        let storage = server.storage.as_mut().unwrap();
+        assert_eq!(storage.get(b"b").unwrap(), Some(b"a".to_vec()));
+        assert_eq!(storage.get(b"c").unwrap(), Some(b"a".to_vec()));
+    }
+
+    #[tokio::test]
+    async fn test_errors() {
+        let mut server = server_with_keys();
+        assert_eq!(
+            run(&mut server, "COPY a a").await,
+            ServerMessage::Error(ServerError::SameObject)
+        );
+        assert_eq!(
+            run(&mut server, "COPY a c DB 1").await,
+            ServerMessage::Error(ServerError::DBIndexOutOfRange)
+        );
+        assert_eq!(
+            run(&mut server, "COPY a c DB").await,
+            ServerMessage::Error(ServerError::CommandSyntaxError(String::from("COPY a c DB")))
+        );
+        assert_eq!(
+            run(&mut server, "COPY a").await,
+            ServerMessage::Error(ServerError::WrongNumberOfArguments(String::from("copy")))
+        );
+    }
+}
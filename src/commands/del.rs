@@ -0,0 +1,83 @@
+use crate::{
+    lazyfree,
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{ServerError, ServerValue},
+};
+
+// DEL and UNLINK, the latter freeing large values in the background
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    let name = String::from_utf8_lossy(&command[0]).to_lowercase();
+    if command.len() < 2 {
+        request
+            .error(ServerError::WrongNumberOfArguments(name))
+            .await;
+        return;
+    }
+
+    let mut removed = 0;
+    for key in command[1..].iter() {
+        if let Some(data) = storage.remove(key) {
+            if name == "unlink" {
+                lazyfree::free(data);
+            }
+            removed += 1;
+        }
+    }
+    request
+        .data(ServerValue::RESP(RESP::Integer(removed)))
+        .await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::{add_strings, run, server_with};
+    use crate::server_result::ServerMessage;
+
+    fn server_with_keys() -> Server {
+        server_with(|storage| {
+            add_strings(storage, &[("a", "value"), ("b", "value"), ("c", "value")])
+        })
+    }
+
+    #[tokio::test]
+    async fn test_del() {
+        let mut server = server_with_keys();
+        assert_eq!(
+            run(&mut server, "DEL a b missing b").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(2)))
+        );
+        let storage = server.storage.as_mut().unwrap();
+        assert!(!storage.exists(b"a"));
+        assert!(storage.exists(b"c"));
+    }
+
+    #[tokio::test]
+    async fn test_unlink() {
+        let mut server = server_with_keys();
+        assert_eq!(
+            run(&mut server, "UNLINK c missing").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(1)))
+        );
+        assert!(!server.storage.as_mut().unwrap().exists(b"c"));
+    }
+
+    #[tokio::test]
+    async fn test_wrong_number_of_arguments() {
+        let mut server = server_with_keys();
+        assert_eq!(
+            run(&mut server, "UNLINK").await,
+            ServerMessage::Error(ServerError::WrongNumberOfArguments(String::from("unlink")))
+        );
+    }
+}
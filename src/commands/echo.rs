@@ -1,7 +1,13 @@
-use crate::server_result::ServerValue;
+use crate::server_result::{ServerError, ServerValue};
 use crate::{request::Request, resp::RESP, server::Server};
 
-pub async fn command(_server: &Server, request: &Request, command: &Vec<String>) {
+pub async fn command(_server: &Server, request: &Request, command: &[Vec<u8>]) {
+    if command.len() != 2 {
+        request
+            .error(ServerError::WrongNumberOfArguments(String::from("echo")))
+            .await;
+        return;
+    }
     request
         .data(ServerValue::RESP(RESP::BulkString(command[1].clone())))
         .await;
@@ -14,17 +20,35 @@ mod tests {
     use tokio::sync::mpsc;
     #[tokio::test]
     async fn test_command() {
-        let cmd = vec![String::from("echo"), String::from("hey")];
+        let cmd = vec![b"echo".to_vec(), b"hey".to_vec()];
+        let server = Server::new();
+        let (connection_sender, mut connection_receiver) = mpsc::channel::<ServerMessage>(32);
+        let request = Request {
+            value: RESP::Null,
+            sender: connection_sender,
+            client_id: 0,
+        };
+        command(&server, &request, &cmd).await;
+        assert_eq!(
+            connection_receiver.try_recv().unwrap(),
+            ServerMessage::Data(ServerValue::RESP(RESP::BulkString(b"hey".to_vec())))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_command_wrong_number_of_arguments() {
+        let cmd = vec![b"echo".to_vec()];
         let server = Server::new();
         let (connection_sender, mut connection_receiver) = mpsc::channel::<ServerMessage>(32);
         let request = Request {
             value: RESP::Null,
             sender: connection_sender,
+            client_id: 0,
         };
         command(&server, &request, &cmd).await;
         assert_eq!(
             connection_receiver.try_recv().unwrap(),
-            ServerMessage::Data(ServerValue::RESP(RESP::BulkString(String::from("hey"))))
+            ServerMessage::Error(ServerError::WrongNumberOfArguments(String::from("echo")))
         );
     }
 }
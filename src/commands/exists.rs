@@ -0,0 +1,64 @@
+use crate::{
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{ServerError, ServerValue},
+};
+
+// EXISTS and TOUCH, replying the number of existing keys,
+// a key given several times being counted as many times
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    let name = String::from_utf8_lossy(&command[0]).to_lowercase();
+    if command.len() < 2 {
+        request
+            .error(ServerError::WrongNumberOfArguments(name))
+            .await;
+        return;
+    }
+
+    let count = command[1..]
+        .iter()
+        .filter(|key| storage.exists(key))
+        .count();
+    request
+        .data(ServerValue::RESP(RESP::Integer(count as i64)))
+        .await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::run;
+    use crate::server_result::ServerMessage;
+    use crate::set::SetArgs;
+    use crate::storage::Storage;
+
+    #[tokio::test]
+    async fn test_command() {
+        let mut storage = Storage::new();
+        storage
+            .set(b"key".to_vec(), b"value".to_vec(), SetArgs::new())
+            .unwrap();
+        let mut server = Server::with_new(storage);
+        assert_eq!(
+            run(&mut server, "EXISTS key missing key").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(2)))
+        );
+        assert_eq!(
+            run(&mut server, "TOUCH key missing").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(1)))
+        );
+        assert_eq!(
+            run(&mut server, "TOUCH").await,
+            ServerMessage::Error(ServerError::WrongNumberOfArguments(String::from("touch")))
+        );
+    }
+}
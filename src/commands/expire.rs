@@ -0,0 +1,137 @@
+use crate::{
+    expire::{parse_expire_arguments, ExpireTime},
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{command_to_string, ServerError, ServerValue},
+    storage_result::StorageError,
+};
+
+// EXPIRE, PEXPIRE, EXPIREAT and PEXPIREAT
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    let name = String::from_utf8_lossy(&command[0]).to_lowercase();
+    if command.len() < 3 {
+        request
+            .error(ServerError::WrongNumberOfArguments(name))
+            .await;
+        return;
+    }
+    let unit_ms = match name.as_str() {
+        "expire" | "expireat" => 1000,
+        _ => 1,
+    };
+    let (value, conditions) = match parse_expire_arguments(&command[2..], unit_ms, &name) {
+        Ok(arguments) => arguments,
+        Err(StorageError::CommandSyntaxError(_)) => {
+            request
+                .error(ServerError::CommandSyntaxError(command_to_string(command)))
+                .await;
+            return;
+        }
+        Err(e) => {
+            request.error(ServerError::from(e)).await;
+            return;
+        }
+    };
+    let time = match name.as_str() {
+        "expireat" | "pexpireat" => ExpireTime::Absolute(value),
+        _ => ExpireTime::Relative(value),
+    };
+
+    match storage.expire(&command[1], time, &conditions, &name) {
+        Ok(updated) => {
+            request
+                .data(ServerValue::RESP(RESP::Integer(updated as i64)))
+                .await
+        }
+        Err(e) => request.error(ServerError::from(e)).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::{add_strings, run, server_with};
+    use crate::expire::KeyTTL;
+    use crate::server_result::ServerMessage;
+
+    fn integer(value: i64) -> ServerMessage {
+        ServerMessage::Data(ServerValue::RESP(RESP::Integer(value)))
+    }
+
+    fn server_with_key() -> Server {
+        server_with(|storage| add_strings(storage, &[("key", "value")]))
+    }
+
+    #[tokio::test]
+    async fn test_expire() {
+        let mut server = server_with_key();
+        assert_eq!(run(&mut server, "expire missing 10").await, integer(0));
+        assert_eq!(run(&mut server, "EXPIRE key 10 XX").await, integer(0));
+        assert_eq!(run(&mut server, "EXPIRE key 10").await, integer(1));
+        assert_eq!(run(&mut server, "EXPIRE key 100 LT").await, integer(0));
+        assert_eq!(run(&mut server, "PEXPIRE key 100 LT").await, integer(1));
+    }
+
+    #[tokio::test]
+    async fn test_expireat() {
+        let mut server = server_with_key();
+        assert_eq!(
+            run(&mut server, "EXPIREAT key 4102444800").await,
+            integer(1)
+        );
+        let storage = server.storage.as_mut().unwrap();
+        assert_eq!(storage.ttl(b"key").unwrap(), KeyTTL::Expires(4102444800000));
+        assert_eq!(
+            run(&mut server, "PEXPIREAT key 4102444800123").await,
+            integer(1)
+        );
+        let storage = server.storage.as_mut().unwrap();
+        assert_eq!(storage.ttl(b"key").unwrap(), KeyTTL::Expires(4102444800123));
+    }
+
+    #[tokio::test]
+    async fn test_expire_in_the_past() {
+        let mut server = server_with_key();
+        assert_eq!(run(&mut server, "PEXPIREAT key 1000").await, integer(1));
+        let storage = server.storage.as_mut().unwrap();
+        assert_eq!(storage.ttl(b"key").unwrap(), KeyTTL::Missing);
+    }
+
+    #[tokio::test]
+    async fn test_expire_errors() {
+        let mut server = server_with_key();
+        assert_eq!(
+            run(&mut server, "PEXPIRE key").await,
+            ServerMessage::Error(ServerError::WrongNumberOfArguments(String::from("pexpire")))
+        );
+        assert_eq!(
+            run(&mut server, "EXPIRE key ten").await,
+            ServerMessage::Error(ServerError::NotAnInteger)
+        );
+        assert_eq!(
+            run(&mut server, "EXPIRE key 10 NX GT").await,
+            ServerMessage::Error(ServerError::IncompatibleOptions(String::from(
+                "NX and XX, GT or LT"
+            )))
+        );
+        assert_eq!(
+            run(&mut server, "EXPIRE key 10 FOO").await,
+            ServerMessage::Error(ServerError::CommandSyntaxError(String::from(
+                "EXPIRE key 10 FOO"
+            )))
+        );
+        assert_eq!(
+            run(&mut server, "EXPIREAT key 9223372036854775807").await,
+            ServerMessage::Error(ServerError::InvalidExpireTime(String::from("expireat")))
+        );
+    }
+}
@@ -2,10 +2,10 @@ use crate::{
     request::Request,
     resp::RESP,
     server::Server,
-    server_result::{ServerError, ServerValue},
+    server_result::{command_to_string, ServerError, ServerValue},
 };
 
-pub async fn command(server: &mut Server, request: &Request, command: &Vec<String>) {
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
     let storage = match server.storage.as_mut() {
         Some(storage) => storage,
         None => {
@@ -16,18 +16,20 @@ pub async fn command(server: &mut Server, request: &Request, command: &Vec<Strin
 
     if command.len() != 2 {
         request
-            .error(ServerError::CommandSyntaxError(command.join(" ")))
+            .error(ServerError::WrongNumberOfArguments(String::from("get")))
             .await;
         return;
     }
-    let output = storage.get(command[1].clone());
+    let output = storage.get(&command[1]);
 
     match output {
         Ok(Some(v)) => request.data(ServerValue::RESP(RESP::BulkString(v))).await,
         Ok(None) => request.data(ServerValue::RESP(RESP::Null)).await,
         Err(_) => {
             request
-                .error(ServerError::CommandInternalError(command.join(" ")))
+                .error(ServerError::CommandInternalError(command_to_string(
+                    command,
+                )))
                 .await
         }
     }
@@ -45,30 +47,32 @@ mod tests {
     async fn test_command() {
         let mut storage = Storage::new();
         storage
-            .set("key".to_string(), "value".to_string(), SetArgs::new())
+            .set(b"key".to_vec(), b"value".to_vec(), SetArgs::new())
             .unwrap();
         let mut server = Server::with_new(storage);
-        let cmd = vec![String::from("get"), String::from("key")];
+        let cmd = vec![b"get".to_vec(), b"key".to_vec()];
         let (connection_sender, mut connection_receiver) = mpsc::channel::<ServerMessage>(32);
         let request = Request {
             value: RESP::Null,
             sender: connection_sender,
+            client_id: 0,
         };
         command(&mut server, &request, &cmd).await;
         assert_eq!(
             connection_receiver.try_recv().unwrap(),
-            ServerMessage::Data(ServerValue::RESP(RESP::BulkString(String::from("value"))))
+            ServerMessage::Data(ServerValue::RESP(RESP::BulkString(b"value".to_vec())))
         );
     }
 
     #[tokio::test]
     async fn test_storage_not_initialised() {
         let mut server = Server::new();
-        let cmd = vec![String::from("get"), String::from("key")];
+        let cmd = vec![b"get".to_vec(), b"key".to_vec()];
         let (connection_sender, mut connection_receiver) = mpsc::channel::<ServerMessage>(32);
         let request = Request {
             value: RESP::Null,
             sender: connection_sender,
+            client_id: 0,
         };
         command(&mut server, &request, &cmd).await;
         assert_eq!(
@@ -81,16 +85,17 @@ mod tests {
     async fn test_wrong_syntax() {
         let storage = Storage::new();
         let mut server = Server::with_new(storage);
-        let cmd = vec![String::from("get")];
+        let cmd = vec![b"get".to_vec()];
         let (connection_sender, mut connection_receiver) = mpsc::channel::<ServerMessage>(32);
         let request = Request {
             value: RESP::Null,
             sender: connection_sender,
+            client_id: 0,
         };
         command(&mut server, &request, &cmd).await;
         assert_eq!(
             connection_receiver.try_recv().unwrap(),
-            ServerMessage::Error(ServerError::CommandSyntaxError("get".to_string()))
+            ServerMessage::Error(ServerError::WrongNumberOfArguments("get".to_string()))
         );
     }
 }
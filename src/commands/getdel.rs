@@ -0,0 +1,78 @@
+use crate::{
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{ServerError, ServerValue},
+};
+
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    if command.len() != 2 {
+        request
+            .error(ServerError::WrongNumberOfArguments(String::from("getdel")))
+            .await;
+        return;
+    }
+
+    match storage.get_del(&command[1]) {
+        Ok(Some(v)) => request.data(ServerValue::RESP(RESP::BulkString(v))).await,
+        Ok(None) => request.data(ServerValue::RESP(RESP::Null)).await,
+        Err(e) => request.error(ServerError::from(e)).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server_result::ServerMessage;
+    use crate::set::SetArgs;
+    use crate::storage::Storage;
+    use tokio::sync::mpsc;
+
+    #[tokio::test]
+    async fn test_command() {
+        let mut storage = Storage::new();
+        storage
+            .set(b"key".to_vec(), b"value".to_vec(), SetArgs::new())
+            .unwrap();
+        let mut server = Server::with_new(storage);
+        let cmd = vec![b"getdel".to_vec(), b"key".to_vec()];
+        for expected in [RESP::BulkString(b"value".to_vec()), RESP::Null] {
+            let (connection_sender, mut connection_receiver) = mpsc::channel::<ServerMessage>(32);
+            let request = Request {
+                value: RESP::Null,
+                sender: connection_sender,
+                client_id: 0,
+            };
+            command(&mut server, &request, &cmd).await;
+            assert_eq!(
+                connection_receiver.try_recv().unwrap(),
+                ServerMessage::Data(ServerValue::RESP(expected))
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_wrong_syntax() {
+        let mut server = Server::with_new(Storage::new());
+        let cmd = vec![b"getdel".to_vec()];
+        let (connection_sender, mut connection_receiver) = mpsc::channel::<ServerMessage>(32);
+        let request = Request {
+            value: RESP::Null,
+            sender: connection_sender,
+            client_id: 0,
+        };
+        command(&mut server, &request, &cmd).await;
+        assert_eq!(
+            connection_receiver.try_recv().unwrap(),
+            ServerMessage::Error(ServerError::WrongNumberOfArguments("getdel".to_string()))
+        );
+    }
+}
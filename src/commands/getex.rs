@@ -0,0 +1,204 @@
+use crate::{
+    expire::ExpireTime,
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{command_to_string, ServerError, ServerValue},
+    set::{parse_expiry, KeyExipry},
+    storage_result::{StorageError, StorageResult},
+};
+
+#[derive(Debug, PartialEq)]
+enum GetExOption {
+    Expire(ExpireTime),
+    Persist,
+}
+
+fn expire_time(expiry: KeyExipry) -> Option<ExpireTime> {
+    match expiry {
+        KeyExipry::EX(v) => (v as i64).checked_mul(1000).map(ExpireTime::Relative),
+        KeyExipry::PX(v) => Some(ExpireTime::Relative(v as i64)),
+        KeyExipry::EXAT(v) => (v as i64).checked_mul(1000).map(ExpireTime::Absolute),
+        KeyExipry::PXAT(v) => Some(ExpireTime::Absolute(v as i64)),
+        KeyExipry::KEEPTTL => None,
+    }
+}
+
+// Parse `[EX seconds|PX milliseconds|EXAT timestamp|PXAT timestamp|PERSIST]`
+fn parse_getex_arguments(arguments: &[Vec<u8>]) -> StorageResult<Option<GetExOption>> {
+    match arguments {
+        [] => Ok(None),
+        [option] if option.eq_ignore_ascii_case(b"persist") => Ok(Some(GetExOption::Persist)),
+        [option, value] if !option.eq_ignore_ascii_case(b"keepttl") => {
+            let expiry = parse_expiry(option, value, "getex")?;
+            let time = expire_time(expiry)
+                .ok_or_else(|| StorageError::InvalidExpireTime(String::from("getex")))?;
+            Ok(Some(GetExOption::Expire(time)))
+        }
+        _ => Err(StorageError::CommandSyntaxError(command_to_string(
+            arguments,
+        ))),
+    }
+}
+
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    if command.len() < 2 {
+        request
+            .error(ServerError::WrongNumberOfArguments(String::from("getex")))
+            .await;
+        return;
+    }
+    let option = match parse_getex_arguments(&command[2..]) {
+        Ok(option) => option,
+        Err(StorageError::CommandSyntaxError(_)) => {
+            request
+                .error(ServerError::CommandSyntaxError(command_to_string(command)))
+                .await;
+            return;
+        }
+        Err(e) => {
+            request.error(ServerError::from(e)).await;
+            return;
+        }
+    };
+
+    let value = match storage.get(&command[1]) {
+        Ok(Some(value)) => value,
+        Ok(None) => {
+            request.data(ServerValue::RESP(RESP::Null)).await;
+            return;
+        }
+        Err(e) => {
+            request.error(ServerError::from(e)).await;
+            return;
+        }
+    };
+    let result = match option {
+        Some(GetExOption::Expire(time)) => {
+            storage.expire(&command[1], time, &[], "getex").map(|_| ())
+        }
+        Some(GetExOption::Persist) => storage.persist(&command[1]).map(|_| ()),
+        None => Ok(()),
+    };
+    match result {
+        Ok(()) => {
+            request
+                .data(ServerValue::RESP(RESP::BulkString(value)))
+                .await
+        }
+        Err(e) => request.error(ServerError::from(e)).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use crate::commands::test_utils::run;
+    use crate::expire::KeyTTL;
+    use crate::server_result::ServerMessage;
+    use crate::set::SetArgs;
+    use crate::storage::Storage;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn value() -> ServerMessage {
+        ServerMessage::Data(ServerValue::RESP(RESP::BulkString(b"value".to_vec())))
+    }
+
+    fn server_with_key() -> Server {
+        let clock = ManualClock::new(UNIX_EPOCH + Duration::from_secs(1700000000));
+        let mut storage = Storage::with_clock(clock);
+        storage
+            .set(b"key".to_vec(), b"value".to_vec(), SetArgs::new())
+            .unwrap();
+        Server::with_new(storage)
+    }
+
+    fn ttl(server: &mut Server) -> KeyTTL {
+        server.storage.as_mut().unwrap().ttl(b"key").unwrap()
+    }
+
+    #[test]
+    fn test_parse_getex_arguments() {
+        let parse = |arguments: &str| {
+            let arguments: Vec<Vec<u8>> = arguments
+                .split(' ')
+                .map(|a| a.as_bytes().to_vec())
+                .collect();
+            parse_getex_arguments(&arguments)
+        };
+        assert_eq!(
+            parse("EX 10").unwrap(),
+            Some(GetExOption::Expire(ExpireTime::Relative(10000)))
+        );
+        assert_eq!(
+            parse("pxat 1700000000000").unwrap(),
+            Some(GetExOption::Expire(ExpireTime::Absolute(1700000000000)))
+        );
+        assert_eq!(parse("PERSIST").unwrap(), Some(GetExOption::Persist));
+        for arguments in ["EX 10 PX 10", "PERSIST EX 10", "KEEPTTL", "EX"] {
+            assert_eq!(
+                parse(arguments).unwrap_err(),
+                StorageError::CommandSyntaxError(arguments.to_string())
+            );
+        }
+        assert_eq!(
+            parse("EX 0").unwrap_err(),
+            StorageError::InvalidExpireTime(String::from("getex"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_command() {
+        let mut server = server_with_key();
+        assert_eq!(run(&mut server, "GETEX key").await, value());
+        assert_eq!(ttl(&mut server), KeyTTL::Persistent);
+        assert_eq!(run(&mut server, "GETEX key EX 10").await, value());
+        assert_eq!(ttl(&mut server), KeyTTL::Expires(1700000010000));
+        assert_eq!(
+            run(&mut server, "GETEX key PXAT 1700000020000").await,
+            value()
+        );
+        assert_eq!(ttl(&mut server), KeyTTL::Expires(1700000020000));
+        assert_eq!(run(&mut server, "GETEX key PERSIST").await, value());
+        assert_eq!(ttl(&mut server), KeyTTL::Persistent);
+        assert_eq!(
+            run(&mut server, "GETEX missing EX 10").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Null))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_command_expiry_in_the_past() {
+        let mut server = server_with_key();
+        assert_eq!(run(&mut server, "GETEX key EXAT 1000").await, value());
+        assert_eq!(ttl(&mut server), KeyTTL::Missing);
+    }
+
+    #[tokio::test]
+    async fn test_command_errors() {
+        let mut server = server_with_key();
+        assert_eq!(
+            run(&mut server, "GETEX key EX 10 PERSIST").await,
+            ServerMessage::Error(ServerError::CommandSyntaxError(String::from(
+                "GETEX key EX 10 PERSIST"
+            )))
+        );
+        assert_eq!(
+            run(&mut server, "GETEX key PX -1").await,
+            ServerMessage::Error(ServerError::InvalidExpireTime(String::from("getex")))
+        );
+        assert_eq!(
+            run(&mut server, "GETEX key EX ten").await,
+            ServerMessage::Error(ServerError::NotAnInteger)
+        );
+    }
+}
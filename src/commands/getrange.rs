@@ -0,0 +1,148 @@
+use crate::{
+    number::parse_i64,
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{ServerError, ServerValue},
+};
+
+// Bytes between start and end included, negative
+// offsets being counted from the end of the value
+fn range(value: &[u8], start: i64, end: i64) -> &[u8] {
+    let length = value.len() as i64;
+    if length == 0 || (start < 0 && end < 0 && start > end) {
+        return &[];
+    }
+    let start = if start < 0 {
+        (length + start).max(0)
+    } else {
+        start
+    };
+    let end = if end < 0 {
+        (length + end).max(0)
+    } else {
+        end.min(length - 1)
+    };
+    if start > end {
+        return &[];
+    }
+    &value[start as usize..=end as usize]
+}
+
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    if command.len() != 4 {
+        request
+            .error(ServerError::WrongNumberOfArguments(String::from(
+                "getrange",
+            )))
+            .await;
+        return;
+    }
+    let (start, end) = match (parse_i64(&command[2]), parse_i64(&command[3])) {
+        (Some(start), Some(end)) => (start, end),
+        _ => {
+            request.error(ServerError::NotAnInteger).await;
+            return;
+        }
+    };
+
+    match storage.get(&command[1]) {
+        Ok(value) => {
+            let value = value.unwrap_or_default();
+            request
+                .data(ServerValue::RESP(RESP::BulkString(
+                    range(&value, start, end).to_vec(),
+                )))
+                .await
+        }
+        Err(e) => request.error(ServerError::from(e)).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server_result::ServerMessage;
+    use crate::set::SetArgs;
+    use crate::storage::Storage;
+    use tokio::sync::mpsc;
+
+    #[test]
+    fn test_range() {
+        let value = b"This is a string";
+        assert_eq!(range(value, 0, 3), b"This");
+        assert_eq!(range(value, -3, -1), b"ing");
+        assert_eq!(range(value, 0, -1), value);
+        assert_eq!(range(value, 10, 100), b"string");
+        assert_eq!(range(value, -100, 1), b"Th");
+        assert_eq!(range(value, 5, 3), b"");
+        assert_eq!(range(value, -1, -5), b"");
+        assert_eq!(range(b"", 0, -1), b"");
+    }
+
+    #[tokio::test]
+    async fn test_command() {
+        let mut storage = Storage::new();
+        storage
+            .set(
+                b"key".to_vec(),
+                b"This is a string".to_vec(),
+                SetArgs::new(),
+            )
+            .unwrap();
+        let mut server = Server::with_new(storage);
+        for (key, start, end, expected) in [
+            ("key", "0", "3", "This"),
+            ("key", "-3", "-1", "ing"),
+            ("missing", "0", "-1", ""),
+        ] {
+            let cmd: Vec<Vec<u8>> = ["getrange", key, start, end]
+                .iter()
+                .map(|a| a.as_bytes().to_vec())
+                .collect();
+            let (connection_sender, mut connection_receiver) = mpsc::channel::<ServerMessage>(32);
+            let request = Request {
+                value: RESP::Null,
+                sender: connection_sender,
+                client_id: 0,
+            };
+            command(&mut server, &request, &cmd).await;
+            assert_eq!(
+                connection_receiver.try_recv().unwrap(),
+                ServerMessage::Data(ServerValue::RESP(RESP::BulkString(
+                    expected.as_bytes().to_vec()
+                )))
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_not_an_integer() {
+        let mut server = Server::with_new(Storage::new());
+        let cmd = vec![
+            b"getrange".to_vec(),
+            b"key".to_vec(),
+            b"a".to_vec(),
+            b"1".to_vec(),
+        ];
+        let (connection_sender, mut connection_receiver) = mpsc::channel::<ServerMessage>(32);
+        let request = Request {
+            value: RESP::Null,
+            sender: connection_sender,
+            client_id: 0,
+        };
+        command(&mut server, &request, &cmd).await;
+        assert_eq!(
+            connection_receiver.try_recv().unwrap(),
+            ServerMessage::Error(ServerError::NotAnInteger)
+        );
+    }
+}
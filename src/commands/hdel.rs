@@ -0,0 +1,72 @@
+use crate::{
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{ServerError, ServerValue},
+};
+
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    if command.len() < 3 {
+        request
+            .error(ServerError::WrongNumberOfArguments(String::from("hdel")))
+            .await;
+        return;
+    }
+
+    match storage.hash_delete(&command[1], &command[2..]) {
+        Ok(removed) => {
+            request
+                .data(ServerValue::RESP(RESP::Integer(removed as i64)))
+                .await
+        }
+        Err(e) => request.error(ServerError::from(e)).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::run;
+    use crate::server_result::ServerMessage;
+    use crate::storage::Storage;
+
+    #[tokio::test]
+    async fn test_hdel() {
+        let mut storage = Storage::new();
+        storage
+            .hash_set(
+                b"user",
+                &[
+                    b"name".to_vec(),
+                    b"alice".to_vec(),
+                    b"age".to_vec(),
+                    b"30".to_vec(),
+                ],
+            )
+            .unwrap();
+        let mut server = Server::with_new(storage);
+        assert_eq!(
+            run(&mut server, "HDEL user age city age").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(1)))
+        );
+        assert!(server.storage.as_mut().unwrap().exists(b"user"));
+        assert_eq!(
+            run(&mut server, "HDEL user name").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(1)))
+        );
+        // the hash is deleted with its last field
+        assert!(!server.storage.as_mut().unwrap().exists(b"user"));
+        assert_eq!(
+            run(&mut server, "HDEL user name").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(0)))
+        );
+    }
+}
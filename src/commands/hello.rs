@@ -0,0 +1,189 @@
+use crate::{
+    request::Request,
+    resp::{Protocol, RESP},
+    server::Server,
+    server_result::{ServerError, ServerValue},
+};
+
+// Without ACLs the default user accepts any password
+const DEFAULT_USER: &[u8] = b"default";
+
+fn parse_protocol(value: &[u8]) -> Result<Protocol, ServerError> {
+    let version: i64 = std::str::from_utf8(value)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .ok_or(ServerError::InvalidProtocolVersion)?;
+    match version {
+        2 => Ok(Protocol::RESP2),
+        3 => Ok(Protocol::RESP3),
+        _ => Err(ServerError::UnsupportedProtocol),
+    }
+}
+
+// Same rule as CLIENT SETNAME, only printable characters without spaces
+fn validate_name(name: &[u8]) -> Result<String, ServerError> {
+    if name.iter().any(|&c| !(b'!'..=b'~').contains(&c)) {
+        return Err(ServerError::InvalidClientName);
+    }
+    Ok(String::from_utf8_lossy(name).into_owned())
+}
+
+// HELLO [protover [AUTH username password] [SETNAME clientname]]
+fn parse_hello_arguments(
+    command: &[Vec<u8>],
+) -> Result<(Option<Protocol>, Option<String>), ServerError> {
+    if command.len() == 1 {
+        return Ok((None, None));
+    }
+    let protocol = parse_protocol(&command[1])?;
+    let mut name = None;
+    let mut idx: usize = 2;
+    while idx < command.len() {
+        match command[idx].to_ascii_lowercase().as_slice() {
+            b"auth" if idx + 2 < command.len() => {
+                if command[idx + 1] != DEFAULT_USER {
+                    return Err(ServerError::WrongPass);
+                }
+                idx += 3;
+            }
+            b"setname" if idx + 1 < command.len() => {
+                name = Some(validate_name(&command[idx + 1])?);
+                idx += 2;
+            }
+            _ => {
+                return Err(ServerError::CommandSyntaxError(
+                    String::from_utf8_lossy(&command[idx]).into_owned(),
+                ))
+            }
+        }
+    }
+    Ok((Some(protocol), name))
+}
+
+pub async fn command(_server: &Server, request: &Request, command: &[Vec<u8>]) {
+    match parse_hello_arguments(command) {
+        Ok((protocol, name)) => request.data(ServerValue::Hello { protocol, name }).await,
+        Err(e) => request.error(e).await,
+    }
+}
+
+// Reply sent by the connection once it has switched protocol
+pub fn reply(id: u64, protocol: Protocol) -> RESP {
+    let field = |name: &str| RESP::BulkString(name.as_bytes().to_vec());
+    RESP::Map(vec![
+        (field("server"), field("sider")),
+        (field("version"), field(env!("CARGO_PKG_VERSION"))),
+        (field("proto"), RESP::Integer(protocol.version())),
+        (field("id"), RESP::Integer(id as i64)),
+        (field("mode"), field("standalone")),
+        (field("role"), field("master")),
+        (field("modules"), RESP::Array(vec![])),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::run_args;
+    use crate::server_result::ServerMessage;
+
+    #[tokio::test]
+    async fn test_command_no_arguments() {
+        assert_eq!(
+            run_args(&mut Server::new(), &["hello"]).await,
+            ServerMessage::Data(ServerValue::Hello {
+                protocol: None,
+                name: None
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_command_resp3() {
+        assert_eq!(
+            run_args(&mut Server::new(), &["HELLO", "3"]).await,
+            ServerMessage::Data(ServerValue::Hello {
+                protocol: Some(Protocol::RESP3),
+                name: None
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_command_auth_and_setname() {
+        assert_eq!(
+            run_args(
+                &mut Server::new(),
+                &["hello", "2", "AUTH", "default", "secret", "SETNAME", "worker-1"]
+            )
+            .await,
+            ServerMessage::Data(ServerValue::Hello {
+                protocol: Some(Protocol::RESP2),
+                name: Some(String::from("worker-1"))
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_command_unsupported_protocol() {
+        assert_eq!(
+            run_args(&mut Server::new(), &["hello", "4"]).await,
+            ServerMessage::Error(ServerError::UnsupportedProtocol)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_command_invalid_protocol() {
+        assert_eq!(
+            run_args(&mut Server::new(), &["hello", "three"]).await,
+            ServerMessage::Error(ServerError::InvalidProtocolVersion)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_command_wrong_user() {
+        assert_eq!(
+            run_args(
+                &mut Server::new(),
+                &["hello", "3", "auth", "admin", "secret"]
+            )
+            .await,
+            ServerMessage::Error(ServerError::WrongPass)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_command_invalid_name() {
+        assert_eq!(
+            run_args(&mut Server::new(), &["hello", "3", "setname", "my client"]).await,
+            ServerMessage::Error(ServerError::InvalidClientName)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_command_missing_option_value() {
+        assert_eq!(
+            run_args(&mut Server::new(), &["hello", "3", "setname"]).await,
+            ServerMessage::Error(ServerError::CommandSyntaxError(String::from("setname")))
+        );
+    }
+
+    #[test]
+    fn test_reply() {
+        let reply = reply(7, Protocol::RESP3);
+        match reply {
+            RESP::Map(fields) => {
+                assert_eq!(fields.len(), 7);
+                assert_eq!(
+                    fields[2],
+                    (RESP::BulkString(b"proto".to_vec()), RESP::Integer(3))
+                );
+                assert_eq!(
+                    fields[3],
+                    (RESP::BulkString(b"id".to_vec()), RESP::Integer(7))
+                );
+            }
+            _ => panic!(),
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use crate::{
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{ServerError, ServerValue},
+};
+
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    if command.len() != 3 {
+        request
+            .error(ServerError::WrongNumberOfArguments(String::from("hexists")))
+            .await;
+        return;
+    }
+
+    match storage.hash_exists(&command[1], &command[2]) {
+        Ok(exists) => {
+            request
+                .data(ServerValue::RESP(RESP::Integer(exists as i64)))
+                .await
+        }
+        Err(e) => request.error(ServerError::from(e)).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::run;
+    use crate::server_result::ServerMessage;
+    use crate::storage::Storage;
+
+    #[tokio::test]
+    async fn test_hexists() {
+        let mut storage = Storage::new();
+        storage
+            .hash_set(b"user", &[b"name".to_vec(), b"alice".to_vec()])
+            .unwrap();
+        let mut server = Server::with_new(storage);
+        assert_eq!(
+            run(&mut server, "HEXISTS user name").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(1)))
+        );
+        assert_eq!(
+            run(&mut server, "HEXISTS user age").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(0)))
+        );
+        assert_eq!(
+            run(&mut server, "HEXISTS missing name").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(0)))
+        );
+    }
+}
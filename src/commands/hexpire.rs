@@ -0,0 +1,216 @@
+use crate::{
+    expire::{parse_expire_arguments, ExpireTime},
+    number::parse_i64,
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{command_to_string, ServerError, ServerValue},
+    storage_result::StorageError,
+};
+
+// Parse `FIELDS numfields field [field ...]`
+pub fn parse_fields(arguments: &[Vec<u8>]) -> Result<&[Vec<u8>], ServerError> {
+    match arguments.first() {
+        Some(argument) if argument.eq_ignore_ascii_case(b"fields") => {}
+        _ => return Err(ServerError::MissingFieldsArgument),
+    }
+    let Some((numfields, fields)) = arguments[1..].split_first() else {
+        return Err(ServerError::NumFieldsMismatch);
+    };
+    let numfields = parse_i64(numfields).ok_or(ServerError::NotAnInteger)?;
+    if numfields <= 0 {
+        return Err(ServerError::NumFieldsMustBePositive);
+    }
+    if numfields as usize != fields.len() {
+        return Err(ServerError::NumFieldsMismatch);
+    }
+    Ok(fields)
+}
+
+// HEXPIRE, HPEXPIRE, HEXPIREAT and HPEXPIREAT
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    let name = String::from_utf8_lossy(&command[0]).to_lowercase();
+    if command.len() < 6 {
+        request
+            .error(ServerError::WrongNumberOfArguments(name))
+            .await;
+        return;
+    }
+    // FIELDS follows the time, or the condition if there is one
+    let position = if command[3].eq_ignore_ascii_case(b"fields") {
+        3
+    } else {
+        4
+    };
+    let unit_ms = match name.as_str() {
+        "hexpire" | "hexpireat" => 1000,
+        _ => 1,
+    };
+    let (value, conditions) = match parse_expire_arguments(&command[2..position], unit_ms, &name) {
+        Ok(arguments) => arguments,
+        Err(StorageError::CommandSyntaxError(_)) => {
+            request
+                .error(ServerError::CommandSyntaxError(command_to_string(command)))
+                .await;
+            return;
+        }
+        Err(e) => {
+            request.error(ServerError::from(e)).await;
+            return;
+        }
+    };
+    if value < 0 {
+        request.error(ServerError::NegativeExpireTime).await;
+        return;
+    }
+    let fields = match parse_fields(&command[position..]) {
+        Ok(fields) => fields,
+        Err(e) => {
+            request.error(e).await;
+            return;
+        }
+    };
+    let time = match name.as_str() {
+        "hexpireat" | "hpexpireat" => ExpireTime::Absolute(value),
+        _ => ExpireTime::Relative(value),
+    };
+
+    match storage.hash_expire(&command[1], fields, time, &conditions, &name) {
+        Ok(replies) => {
+            request
+                .data(ServerValue::RESP(RESP::Array(
+                    replies.into_iter().map(RESP::Integer).collect(),
+                )))
+                .await
+        }
+        Err(e) => request.error(ServerError::from(e)).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::{add_hash, run, server_with};
+    use crate::expire::KeyTTL;
+    use crate::server_result::ServerMessage;
+
+    fn integers(values: &[i64]) -> ServerMessage {
+        ServerMessage::Data(ServerValue::RESP(RESP::Array(
+            values.iter().map(|&value| RESP::Integer(value)).collect(),
+        )))
+    }
+
+    fn server_with_hash() -> Server {
+        server_with(|storage| add_hash(storage, "hash", &["a", "1", "b", "2", "c", "3"]))
+    }
+
+    #[tokio::test]
+    async fn test_hexpire() {
+        let mut server = server_with_hash();
+        assert_eq!(
+            run(&mut server, "HEXPIRE hash 100 FIELDS 2 a missing").await,
+            integers(&[1, -2])
+        );
+        assert_eq!(
+            run(&mut server, "HEXPIRE hash 200 nx FIELDS 2 a b").await,
+            integers(&[0, 1])
+        );
+        assert_eq!(
+            run(&mut server, "HPEXPIRE hash 50000 GT FIELDS 2 a b").await,
+            integers(&[0, 0])
+        );
+        assert_eq!(
+            run(&mut server, "HEXPIRE missing 100 FIELDS 1 a").await,
+            integers(&[-2])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_hexpireat() {
+        let mut server = server_with_hash();
+        assert_eq!(
+            run(&mut server, "HPEXPIREAT hash 4102444800123 FIELDS 1 a").await,
+            integers(&[1])
+        );
+        assert_eq!(
+            run(&mut server, "HEXPIREAT hash 4102444800 FIELDS 1 b").await,
+            integers(&[1])
+        );
+        let storage = server.storage.as_mut().unwrap();
+        assert_eq!(
+            storage
+                .hash_ttl(b"hash", &[b"a".to_vec(), b"b".to_vec(), b"c".to_vec()])
+                .unwrap(),
+            vec![
+                KeyTTL::Expires(4102444800123),
+                KeyTTL::Expires(4102444800000),
+                KeyTTL::Persistent
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_expire_in_the_past() {
+        let mut server = server_with_hash();
+        assert_eq!(
+            run(&mut server, "HEXPIRE hash 0 FIELDS 2 a b").await,
+            integers(&[2, 2])
+        );
+        assert_eq!(
+            run(&mut server, "HEXPIREAT hash 1 FIELDS 1 c").await,
+            integers(&[2])
+        );
+        // the hash is deleted along with its last field
+        let storage = server.storage.as_mut().unwrap();
+        assert!(!storage.exists(b"hash"));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_arguments() {
+        let mut server = server_with_hash();
+        for (cmd, error) in [
+            ("HEXPIRE hash 10 NX 1 a", ServerError::MissingFieldsArgument),
+            (
+                "HEXPIRE hash 10 NX XX FIELDS 1 a",
+                ServerError::MissingFieldsArgument,
+            ),
+            (
+                "HEXPIRE hash 10 FIELDS 0 a",
+                ServerError::NumFieldsMustBePositive,
+            ),
+            ("HEXPIRE hash 10 FIELDS 2 a", ServerError::NumFieldsMismatch),
+            ("HEXPIRE hash 10 FIELDS x a", ServerError::NotAnInteger),
+            (
+                "HEXPIRE hash -1 FIELDS 1 a",
+                ServerError::NegativeExpireTime,
+            ),
+            (
+                "HEXPIRE hash 10 UP FIELDS 1 a",
+                ServerError::CommandSyntaxError(String::from("HEXPIRE hash 10 UP FIELDS 1 a")),
+            ),
+            (
+                "HPEXPIREAT hash 281474976710656 FIELDS 1 a",
+                ServerError::InvalidExpireTime(String::from("hpexpireat")),
+            ),
+            (
+                "HEXPIRE hash 10 FIELDS",
+                ServerError::WrongNumberOfArguments(String::from("hexpire")),
+            ),
+        ] {
+            assert_eq!(
+                run(&mut server, cmd).await,
+                ServerMessage::Error(error),
+                "{}",
+                cmd
+            );
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use crate::{
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{ServerError, ServerValue},
+};
+
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    if command.len() != 3 {
+        request
+            .error(ServerError::WrongNumberOfArguments(String::from("hget")))
+            .await;
+        return;
+    }
+
+    match storage.hash_get(&command[1], &command[2]) {
+        Ok(Some(value)) => {
+            request
+                .data(ServerValue::RESP(RESP::BulkString(value)))
+                .await
+        }
+        Ok(None) => request.data(ServerValue::RESP(RESP::Null)).await,
+        Err(e) => request.error(ServerError::from(e)).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::run;
+    use crate::server_result::ServerMessage;
+    use crate::storage::Storage;
+
+    #[tokio::test]
+    async fn test_hget() {
+        let mut storage = Storage::new();
+        storage
+            .hash_set(b"user", &[b"name".to_vec(), b"alice".to_vec()])
+            .unwrap();
+        storage
+            .push(b"list", &[b"a".to_vec()], false, false)
+            .unwrap();
+        let mut server = Server::with_new(storage);
+        assert_eq!(
+            run(&mut server, "HGET user name").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::BulkString(b"alice".to_vec())))
+        );
+        assert_eq!(
+            run(&mut server, "HGET user age").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Null))
+        );
+        assert_eq!(
+            run(&mut server, "HGET missing name").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Null))
+        );
+        assert_eq!(
+            run(&mut server, "HGET list name").await,
+            ServerMessage::Error(ServerError::WrongType)
+        );
+    }
+}
@@ -0,0 +1,111 @@
+use crate::{
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{ServerError, ServerValue},
+};
+
+// HGETALL, HKEYS and HVALS, HGETALL replying with a map
+// which RESP2 connections receive as a flat array
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    let name = String::from_utf8_lossy(&command[0]).to_lowercase();
+    if command.len() != 2 {
+        request
+            .error(ServerError::WrongNumberOfArguments(name))
+            .await;
+        return;
+    }
+
+    let entries = match storage.hash_entries(&command[1]) {
+        Ok(entries) => entries,
+        Err(e) => {
+            request.error(ServerError::from(e)).await;
+            return;
+        }
+    };
+    let reply = match name.as_str() {
+        "hkeys" => RESP::Array(
+            entries
+                .into_iter()
+                .map(|(field, _)| RESP::BulkString(field))
+                .collect(),
+        ),
+        "hvals" => RESP::Array(
+            entries
+                .into_iter()
+                .map(|(_, value)| RESP::BulkString(value))
+                .collect(),
+        ),
+        _ => RESP::Map(
+            entries
+                .into_iter()
+                .map(|(field, value)| (RESP::BulkString(field), RESP::BulkString(value)))
+                .collect(),
+        ),
+    };
+    request.data(ServerValue::RESP(reply)).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::{add_hash, run, server_with};
+    use crate::server_result::ServerMessage;
+
+    fn server_with_hash() -> Server {
+        server_with(|storage| add_hash(storage, "user", &["name", "alice", "age", "30"]))
+    }
+
+    #[tokio::test]
+    async fn test_hgetall() {
+        let mut server = server_with_hash();
+        assert_eq!(
+            run(&mut server, "HGETALL user").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Map(vec![
+                (
+                    RESP::BulkString(b"name".to_vec()),
+                    RESP::BulkString(b"alice".to_vec())
+                ),
+                (
+                    RESP::BulkString(b"age".to_vec()),
+                    RESP::BulkString(b"30".to_vec())
+                ),
+            ])))
+        );
+        assert_eq!(
+            run(&mut server, "HGETALL missing").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Map(vec![])))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_hkeys_and_hvals() {
+        let mut server = server_with_hash();
+        assert_eq!(
+            run(&mut server, "HKEYS user").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Array(vec![
+                RESP::BulkString(b"name".to_vec()),
+                RESP::BulkString(b"age".to_vec()),
+            ])))
+        );
+        assert_eq!(
+            run(&mut server, "HVALS user").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Array(vec![
+                RESP::BulkString(b"alice".to_vec()),
+                RESP::BulkString(b"30".to_vec()),
+            ])))
+        );
+        assert_eq!(
+            run(&mut server, "HKEYS").await,
+            ServerMessage::Error(ServerError::WrongNumberOfArguments(String::from("hkeys")))
+        );
+    }
+}
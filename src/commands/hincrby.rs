@@ -0,0 +1,91 @@
+use crate::{
+    number::parse_i64,
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{ServerError, ServerValue},
+};
+
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    if command.len() != 4 {
+        request
+            .error(ServerError::WrongNumberOfArguments(String::from("hincrby")))
+            .await;
+        return;
+    }
+    let increment = match parse_i64(&command[3]) {
+        Some(increment) => increment,
+        None => {
+            request.error(ServerError::NotAnInteger).await;
+            return;
+        }
+    };
+
+    match storage.hash_incr_by(&command[1], &command[2], increment) {
+        Ok(value) => request.data(ServerValue::RESP(RESP::Integer(value))).await,
+        Err(e) => request.error(ServerError::from(e)).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::run;
+    use crate::server_result::ServerMessage;
+    use crate::storage::Storage;
+
+    #[tokio::test]
+    async fn test_hincrby() {
+        let mut server = Server::with_new(Storage::new());
+        assert_eq!(
+            run(&mut server, "HINCRBY user visits 5").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(5)))
+        );
+        assert_eq!(
+            run(&mut server, "HINCRBY user visits -7").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(-2)))
+        );
+        let storage = server.storage.as_mut().unwrap();
+        assert_eq!(
+            storage.hash_get(b"user", b"visits"),
+            Ok(Some(b"-2".to_vec()))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_hincrby_errors() {
+        let mut storage = Storage::new();
+        storage
+            .hash_set(
+                b"user",
+                &[
+                    b"name".to_vec(),
+                    b"alice".to_vec(),
+                    b"max".to_vec(),
+                    i64::MAX.to_string().into_bytes(),
+                ],
+            )
+            .unwrap();
+        let mut server = Server::with_new(storage);
+        assert_eq!(
+            run(&mut server, "HINCRBY user name 1").await,
+            ServerMessage::Error(ServerError::HashValueNotAnInteger)
+        );
+        assert_eq!(
+            run(&mut server, "HINCRBY user max 1").await,
+            ServerMessage::Error(ServerError::Overflow)
+        );
+        assert_eq!(
+            run(&mut server, "HINCRBY user visits one").await,
+            ServerMessage::Error(ServerError::NotAnInteger)
+        );
+    }
+}
@@ -0,0 +1,99 @@
+use crate::{
+    number::parse_f64,
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{ServerError, ServerValue},
+};
+
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    if command.len() != 4 {
+        request
+            .error(ServerError::WrongNumberOfArguments(String::from(
+                "hincrbyfloat",
+            )))
+            .await;
+        return;
+    }
+    let increment = match parse_f64(&command[3]) {
+        Some(increment) => increment,
+        None => {
+            request.error(ServerError::NotAFloat).await;
+            return;
+        }
+    };
+
+    match storage.hash_incr_by_float(&command[1], &command[2], increment) {
+        Ok(value) => {
+            request
+                .data(ServerValue::RESP(RESP::BulkString(value)))
+                .await
+        }
+        Err(e) => request.error(ServerError::from(e)).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::run;
+    use crate::server_result::ServerMessage;
+    use crate::storage::Storage;
+
+    #[tokio::test]
+    async fn test_hincrbyfloat() {
+        let mut server = Server::with_new(Storage::new());
+        assert_eq!(
+            run(&mut server, "HINCRBYFLOAT item price 10.5").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::BulkString(b"10.5".to_vec())))
+        );
+        assert_eq!(
+            run(&mut server, "HINCRBYFLOAT item price 0.25").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::BulkString(b"10.75".to_vec())))
+        );
+        assert_eq!(
+            run(&mut server, "HINCRBYFLOAT item price -10.75").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::BulkString(b"0".to_vec())))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_hincrbyfloat_errors() {
+        let mut storage = Storage::new();
+        storage
+            .hash_set(b"item", &[b"name".to_vec(), b"pen".to_vec()])
+            .unwrap();
+        let mut server = Server::with_new(storage);
+        assert_eq!(
+            run(&mut server, "HINCRBYFLOAT item name 1").await,
+            ServerMessage::Error(ServerError::HashValueNotAFloat)
+        );
+        assert_eq!(
+            run(&mut server, "HINCRBYFLOAT item price abc").await,
+            ServerMessage::Error(ServerError::NotAFloat)
+        );
+        assert_eq!(
+            run(&mut server, "HINCRBYFLOAT item price inf").await,
+            ServerMessage::Error(ServerError::NaNOrInfinity)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_hincrbyfloat_error_creates_no_key() {
+        let mut server = Server::with_new(Storage::new());
+        assert_eq!(
+            run(&mut server, "HINCRBYFLOAT item price inf").await,
+            ServerMessage::Error(ServerError::NaNOrInfinity)
+        );
+        let storage = server.storage.as_mut().unwrap();
+        assert!(!storage.exists(b"item"));
+    }
+}
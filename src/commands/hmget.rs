@@ -0,0 +1,85 @@
+use crate::{
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{ServerError, ServerValue},
+};
+
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    if command.len() < 3 {
+        request
+            .error(ServerError::WrongNumberOfArguments(String::from("hmget")))
+            .await;
+        return;
+    }
+
+    match storage.hash_get_many(&command[1], &command[2..]) {
+        Ok(values) => {
+            let values = values
+                .into_iter()
+                .map(|value| value.map_or(RESP::Null, RESP::BulkString))
+                .collect();
+            request.data(ServerValue::RESP(RESP::Array(values))).await
+        }
+        Err(e) => request.error(ServerError::from(e)).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::run;
+    use crate::server_result::ServerMessage;
+    use crate::set::SetArgs;
+    use crate::storage::Storage;
+
+    #[tokio::test]
+    async fn test_hmget() {
+        let mut storage = Storage::new();
+        storage
+            .hash_set(
+                b"user",
+                &[
+                    b"name".to_vec(),
+                    b"alice".to_vec(),
+                    b"age".to_vec(),
+                    b"30".to_vec(),
+                ],
+            )
+            .unwrap();
+        let mut server = Server::with_new(storage);
+        assert_eq!(
+            run(&mut server, "HMGET user age city name").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Array(vec![
+                RESP::BulkString(b"30".to_vec()),
+                RESP::Null,
+                RESP::BulkString(b"alice".to_vec()),
+            ])))
+        );
+        assert_eq!(
+            run(&mut server, "HMGET missing a b").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Array(vec![RESP::Null, RESP::Null])))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_wrong_type() {
+        let mut storage = Storage::new();
+        storage
+            .set(b"key".to_vec(), b"value".to_vec(), SetArgs::new())
+            .unwrap();
+        let mut server = Server::with_new(storage);
+        assert_eq!(
+            run(&mut server, "HMGET key field").await,
+            ServerMessage::Error(ServerError::WrongType)
+        );
+    }
+}
@@ -0,0 +1,92 @@
+use crate::{
+    commands::hexpire::parse_fields,
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{ServerError, ServerValue},
+};
+
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    if command.len() < 5 {
+        request
+            .error(ServerError::WrongNumberOfArguments(String::from(
+                "hpersist",
+            )))
+            .await;
+        return;
+    }
+    let fields = match parse_fields(&command[2..]) {
+        Ok(fields) => fields,
+        Err(e) => {
+            request.error(e).await;
+            return;
+        }
+    };
+
+    match storage.hash_persist(&command[1], fields) {
+        Ok(replies) => {
+            request
+                .data(ServerValue::RESP(RESP::Array(
+                    replies.into_iter().map(RESP::Integer).collect(),
+                )))
+                .await
+        }
+        Err(e) => request.error(ServerError::from(e)).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::run;
+    use crate::expire::{ExpireTime, KeyTTL};
+    use crate::server_result::ServerMessage;
+    use crate::storage::Storage;
+
+    #[tokio::test]
+    async fn test_hpersist() {
+        let mut storage = Storage::new();
+        storage
+            .hash_set(
+                b"hash",
+                &[b"a".to_vec(), b"1".to_vec(), b"b".to_vec(), b"2".to_vec()],
+            )
+            .unwrap();
+        storage
+            .hash_expire(
+                b"hash",
+                &[b"a".to_vec()],
+                ExpireTime::Relative(10000),
+                &[],
+                "hpexpire",
+            )
+            .unwrap();
+        let mut server = Server::with_new(storage);
+
+        assert_eq!(
+            run(&mut server, "HPERSIST hash FIELDS 3 a b c").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Array(vec![
+                RESP::Integer(1),
+                RESP::Integer(-1),
+                RESP::Integer(-2),
+            ])))
+        );
+        let storage = server.storage.as_mut().unwrap();
+        assert_eq!(
+            storage.hash_ttl(b"hash", &[b"a".to_vec()]).unwrap(),
+            vec![KeyTTL::Persistent]
+        );
+        assert_eq!(
+            run(&mut server, "HPERSIST hash FIELDS 2 a").await,
+            ServerMessage::Error(ServerError::NumFieldsMismatch)
+        );
+    }
+}
@@ -0,0 +1,175 @@
+use crate::{
+    number::parse_i64,
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{command_to_string, ServerError, ServerValue},
+};
+
+// HRANDFIELD key [count [WITHVALUES]], a negative count
+// allowing the same field to be returned several times
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    if !(2..=4).contains(&command.len()) {
+        request
+            .error(ServerError::WrongNumberOfArguments(String::from(
+                "hrandfield",
+            )))
+            .await;
+        return;
+    }
+
+    // without a count, a single field is replied
+    if command.len() == 2 {
+        match storage.hash_random_fields(&command[1], 1, true) {
+            Ok(mut fields) => {
+                let reply = fields
+                    .pop()
+                    .map_or(RESP::Null, |(field, _)| RESP::BulkString(field));
+                request.data(ServerValue::RESP(reply)).await
+            }
+            Err(e) => request.error(ServerError::from(e)).await,
+        }
+        return;
+    }
+
+    let count = match parse_i64(&command[2]) {
+        Some(count) => count,
+        None => {
+            request.error(ServerError::NotAnInteger).await;
+            return;
+        }
+    };
+    let with_values = command.len() == 4;
+    if with_values && !command[3].eq_ignore_ascii_case(b"withvalues") {
+        request
+            .error(ServerError::CommandSyntaxError(command_to_string(command)))
+            .await;
+        return;
+    }
+    // as in Redis, so that the reply length cannot overflow
+    if count == i64::MIN || (with_values && count < -i64::MAX / 2) {
+        request.error(ServerError::ValueOutOfRange).await;
+        return;
+    }
+
+    let fields =
+        match storage.hash_random_fields(&command[1], count.unsigned_abs() as usize, count >= 0) {
+            Ok(fields) => fields,
+            Err(e) => {
+                request.error(ServerError::from(e)).await;
+                return;
+            }
+        };
+    let reply = if with_values {
+        RESP::Pairs(
+            fields
+                .into_iter()
+                .map(|(field, value)| (RESP::BulkString(field), RESP::BulkString(value)))
+                .collect(),
+        )
+    } else {
+        RESP::Array(
+            fields
+                .into_iter()
+                .map(|(field, _)| RESP::BulkString(field))
+                .collect(),
+        )
+    };
+    request.data(ServerValue::RESP(reply)).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::{add_hash, add_strings, run, server_with};
+    use crate::server_result::ServerMessage;
+
+    fn server_with_hash() -> Server {
+        server_with(|storage| {
+            add_hash(storage, "user", &["name", "alice", "age", "30"]);
+            add_strings(storage, &[("string", "value")]);
+        })
+    }
+
+    fn array_len(message: ServerMessage) -> usize {
+        match message {
+            ServerMessage::Data(ServerValue::RESP(RESP::Array(elements))) => elements.len(),
+            ServerMessage::Data(ServerValue::RESP(RESP::Pairs(pairs))) => pairs.len(),
+            other => panic!("unexpected reply {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_single_field() {
+        let mut server = server_with_hash();
+        match run(&mut server, "HRANDFIELD user").await {
+            ServerMessage::Data(ServerValue::RESP(RESP::BulkString(field))) => {
+                assert!(field == b"name" || field == b"age")
+            }
+            other => panic!("unexpected reply {:?}", other),
+        }
+        assert_eq!(
+            run(&mut server, "HRANDFIELD missing").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Null))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_count() {
+        let mut server = server_with_hash();
+        assert_eq!(array_len(run(&mut server, "HRANDFIELD user 1").await), 1);
+        assert_eq!(array_len(run(&mut server, "HRANDFIELD user 5").await), 2);
+        assert_eq!(array_len(run(&mut server, "HRANDFIELD user -5").await), 5);
+        assert_eq!(array_len(run(&mut server, "HRANDFIELD user 0").await), 0);
+        assert_eq!(array_len(run(&mut server, "HRANDFIELD missing 3").await), 0);
+        match run(&mut server, "HRANDFIELD user -3 WITHVALUES").await {
+            ServerMessage::Data(ServerValue::RESP(RESP::Pairs(pairs))) => {
+                assert_eq!(pairs.len(), 3);
+                for pair in pairs {
+                    assert!(matches!(
+                        pair,
+                        (RESP::BulkString(field), RESP::BulkString(value))
+                            if (field == b"name" && value == b"alice")
+                                || (field == b"age" && value == b"30")
+                    ));
+                }
+            }
+            other => panic!("unexpected reply {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_errors() {
+        let mut server = server_with_hash();
+        assert_eq!(
+            run(&mut server, "HRANDFIELD string").await,
+            ServerMessage::Error(ServerError::WrongType)
+        );
+        assert_eq!(
+            run(&mut server, "HRANDFIELD user 1 WITHSCORES").await,
+            ServerMessage::Error(ServerError::CommandSyntaxError(String::from(
+                "HRANDFIELD user 1 WITHSCORES"
+            )))
+        );
+        assert_eq!(
+            run(
+                &mut server,
+                "HRANDFIELD user -9223372036854775807 WITHVALUES"
+            )
+            .await,
+            ServerMessage::Error(ServerError::ValueOutOfRange)
+        );
+        assert_eq!(
+            run(&mut server, "HRANDFIELD user many").await,
+            ServerMessage::Error(ServerError::NotAnInteger)
+        );
+    }
+}
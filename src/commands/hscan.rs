@@ -0,0 +1,244 @@
+use crate::{
+    glob::string_match,
+    number::parse_i64,
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{command_to_string, ServerError, ServerValue},
+};
+
+#[derive(Debug, PartialEq)]
+struct HScanArgs {
+    pattern: Option<Vec<u8>>,
+    count: usize,
+    novalues: bool,
+}
+
+fn parse_hscan_arguments(arguments: &[Vec<u8>]) -> Result<HScanArgs, ServerError> {
+    let mut args = HScanArgs {
+        pattern: None,
+        count: 10,
+        novalues: false,
+    };
+    let mut idx: usize = 0;
+    while idx < arguments.len() {
+        let option = arguments[idx].to_ascii_lowercase();
+        if option == b"novalues" {
+            args.novalues = true;
+            idx += 1;
+            continue;
+        }
+        if idx + 1 == arguments.len() {
+            return Err(ServerError::CommandSyntaxError(command_to_string(
+                arguments,
+            )));
+        }
+        let value = &arguments[idx + 1];
+        match option.as_slice() {
+            b"match" => args.pattern = Some(value.clone()),
+            b"count" => {
+                args.count = match parse_i64(value) {
+                    Some(count) if count >= 1 => count as usize,
+                    Some(_) => {
+                        return Err(ServerError::CommandSyntaxError(command_to_string(
+                            arguments,
+                        )))
+                    }
+                    None => return Err(ServerError::NotAnInteger),
+                }
+            }
+            _ => {
+                return Err(ServerError::CommandSyntaxError(command_to_string(
+                    arguments,
+                )))
+            }
+        }
+        idx += 2;
+    }
+    Ok(args)
+}
+
+// HSCAN key cursor [MATCH pattern] [COUNT count] [NOVALUES]
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    if command.len() < 3 {
+        request
+            .error(ServerError::WrongNumberOfArguments(String::from("hscan")))
+            .await;
+        return;
+    }
+    let cursor = match std::str::from_utf8(&command[2])
+        .ok()
+        .and_then(|c| c.parse::<u64>().ok())
+    {
+        Some(cursor) => cursor,
+        None => {
+            request.error(ServerError::InvalidCursor).await;
+            return;
+        }
+    };
+    let args = match parse_hscan_arguments(&command[3..]) {
+        Ok(args) => args,
+        Err(ServerError::CommandSyntaxError(_)) => {
+            request
+                .error(ServerError::CommandSyntaxError(command_to_string(command)))
+                .await;
+            return;
+        }
+        Err(e) => {
+            request.error(e).await;
+            return;
+        }
+    };
+
+    let (cursor, mut fields) = match storage.hash_scan(&command[1], cursor, args.count) {
+        Ok(scanned) => scanned,
+        Err(e) => {
+            request.error(ServerError::from(e)).await;
+            return;
+        }
+    };
+    if let Some(pattern) = &args.pattern {
+        fields.retain(|(field, _)| string_match(pattern, field, false));
+    }
+    let mut reply = Vec::with_capacity(fields.len() * 2);
+    for (field, value) in fields {
+        reply.push(RESP::BulkString(field));
+        if !args.novalues {
+            reply.push(RESP::BulkString(value));
+        }
+    }
+    request
+        .data(ServerValue::RESP(RESP::Array(vec![
+            RESP::BulkString(cursor.to_string().into_bytes()),
+            RESP::Array(reply),
+        ])))
+        .await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::run;
+    use crate::server_result::ServerMessage;
+    use crate::storage::Storage;
+
+    // Run a full scan, returning the elements found, sorted
+    async fn scan_all(server: &mut Server, key: &str, options: &str) -> Vec<Vec<u8>> {
+        let mut cursor = b"0".to_vec();
+        let mut found = Vec::new();
+        loop {
+            let cmd = format!(
+                "HSCAN {} {}{}",
+                key,
+                String::from_utf8_lossy(&cursor),
+                options
+            );
+            match run(server, &cmd).await {
+                ServerMessage::Data(ServerValue::RESP(RESP::Array(reply))) => match &reply[..] {
+                    [RESP::BulkString(next), RESP::Array(elements)] => {
+                        for element in elements {
+                            if let RESP::BulkString(element) = element {
+                                found.push(element.clone());
+                            }
+                        }
+                        cursor = next.clone();
+                    }
+                    other => panic!("unexpected reply {:?}", other),
+                },
+                other => panic!("unexpected reply {:?}", other),
+            }
+            if cursor == b"0" {
+                break;
+            }
+        }
+        found.sort();
+        found.dedup();
+        found
+    }
+
+    fn server_with_hashes() -> Server {
+        let mut storage = Storage::new();
+        let pairs = |count: usize| -> Vec<Vec<u8>> {
+            (0..count)
+                .flat_map(|i| {
+                    [
+                        format!("field:{}", i).into_bytes(),
+                        format!("value:{}", i).into_bytes(),
+                    ]
+                })
+                .collect()
+        };
+        storage.hash_set(b"small", &pairs(5)).unwrap();
+        storage.hash_set(b"large", &pairs(500)).unwrap();
+        Server::with_new(storage)
+    }
+
+    #[test]
+    fn test_parse_hscan_arguments() {
+        let parse = |arguments: &str| {
+            let arguments: Vec<Vec<u8>> = arguments
+                .split(' ')
+                .map(|a| a.as_bytes().to_vec())
+                .collect();
+            parse_hscan_arguments(&arguments)
+        };
+        assert_eq!(
+            parse("NOVALUES MATCH f* COUNT 100").unwrap(),
+            HScanArgs {
+                pattern: Some(b"f*".to_vec()),
+                count: 100,
+                novalues: true,
+            }
+        );
+        assert_eq!(parse("COUNT ten").unwrap_err(), ServerError::NotAnInteger);
+        assert_eq!(
+            parse("TYPE hash").unwrap_err(),
+            ServerError::CommandSyntaxError(String::from("TYPE hash"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_small_hash_at_once() {
+        let mut server = server_with_hashes();
+        match run(&mut server, "HSCAN small 0 COUNT 1").await {
+            ServerMessage::Data(ServerValue::RESP(RESP::Array(reply))) => {
+                assert_eq!(reply[0], RESP::BulkString(b"0".to_vec()));
+                assert!(matches!(&reply[1], RESP::Array(elements) if elements.len() == 10));
+            }
+            other => panic!("unexpected reply {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_command() {
+        let mut server = server_with_hashes();
+        assert_eq!(scan_all(&mut server, "large", " COUNT 7").await.len(), 1000);
+        let fields = scan_all(&mut server, "large", " MATCH field:1?? NOVALUES").await;
+        assert_eq!(fields.len(), 100);
+        assert!(fields.iter().all(|field| field.starts_with(b"field:1")));
+        assert!(scan_all(&mut server, "missing", "").await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_errors() {
+        let mut server = server_with_hashes();
+        assert_eq!(
+            run(&mut server, "HSCAN small abc").await,
+            ServerMessage::Error(ServerError::InvalidCursor)
+        );
+        assert_eq!(
+            run(&mut server, "HSCAN small 0 COUNT").await,
+            ServerMessage::Error(ServerError::CommandSyntaxError(String::from(
+                "HSCAN small 0 COUNT"
+            )))
+        );
+    }
+}
@@ -0,0 +1,95 @@
+use crate::{
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{ServerError, ServerValue},
+};
+
+// HSET and HMSET, the latter replying OK
+// instead of the number of fields added
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    let name = String::from_utf8_lossy(&command[0]).to_lowercase();
+    if command.len() < 4 || !command.len().is_multiple_of(2) {
+        request
+            .error(ServerError::WrongNumberOfArguments(name))
+            .await;
+        return;
+    }
+
+    match storage.hash_set(&command[1], &command[2..]) {
+        Ok(added) if name == "hset" => {
+            request
+                .data(ServerValue::RESP(RESP::Integer(added as i64)))
+                .await
+        }
+        Ok(_) => {
+            request
+                .data(ServerValue::RESP(RESP::SimpleString(String::from("OK"))))
+                .await
+        }
+        Err(e) => request.error(ServerError::from(e)).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::run;
+    use crate::server_result::ServerMessage;
+    use crate::set::SetArgs;
+    use crate::storage::Storage;
+
+    #[tokio::test]
+    async fn test_hset() {
+        let mut server = Server::with_new(Storage::new());
+        assert_eq!(
+            run(&mut server, "HSET user name alice age 30").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(2)))
+        );
+        assert_eq!(
+            run(&mut server, "HSET user age 31 city paris").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(1)))
+        );
+        assert_eq!(
+            run(&mut server, "HMSET user age 32").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::SimpleString(String::from("OK"))))
+        );
+        let storage = server.storage.as_mut().unwrap();
+        assert_eq!(storage.hash_len(b"user"), Ok(3));
+        assert_eq!(storage.hash_get(b"user", b"age"), Ok(Some(b"32".to_vec())));
+    }
+
+    #[tokio::test]
+    async fn test_wrong_type() {
+        let mut storage = Storage::new();
+        storage
+            .set(b"key".to_vec(), b"value".to_vec(), SetArgs::new())
+            .unwrap();
+        let mut server = Server::with_new(storage);
+        assert_eq!(
+            run(&mut server, "HSET key field value").await,
+            ServerMessage::Error(ServerError::WrongType)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_wrong_number_of_arguments() {
+        let mut server = Server::with_new(Storage::new());
+        assert_eq!(
+            run(&mut server, "HSET user name").await,
+            ServerMessage::Error(ServerError::WrongNumberOfArguments(String::from("hset")))
+        );
+        assert_eq!(
+            run(&mut server, "HMSET user name alice age").await,
+            ServerMessage::Error(ServerError::WrongNumberOfArguments(String::from("hmset")))
+        );
+    }
+}
@@ -0,0 +1,58 @@
+use crate::{
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{ServerError, ServerValue},
+};
+
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    if command.len() != 4 {
+        request
+            .error(ServerError::WrongNumberOfArguments(String::from("hsetnx")))
+            .await;
+        return;
+    }
+
+    match storage.hash_set_nx(&command[1], &command[2], &command[3]) {
+        Ok(set) => {
+            request
+                .data(ServerValue::RESP(RESP::Integer(set as i64)))
+                .await
+        }
+        Err(e) => request.error(ServerError::from(e)).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::run;
+    use crate::server_result::ServerMessage;
+    use crate::storage::Storage;
+
+    #[tokio::test]
+    async fn test_hsetnx() {
+        let mut server = Server::with_new(Storage::new());
+        assert_eq!(
+            run(&mut server, "HSETNX user name alice").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(1)))
+        );
+        assert_eq!(
+            run(&mut server, "HSETNX user name bob").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(0)))
+        );
+        let storage = server.storage.as_mut().unwrap();
+        assert_eq!(
+            storage.hash_get(b"user", b"name"),
+            Ok(Some(b"alice".to_vec()))
+        );
+    }
+}
@@ -0,0 +1,57 @@
+use crate::{
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{ServerError, ServerValue},
+};
+
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    if command.len() != 3 {
+        request
+            .error(ServerError::WrongNumberOfArguments(String::from("hstrlen")))
+            .await;
+        return;
+    }
+
+    match storage.hash_strlen(&command[1], &command[2]) {
+        Ok(length) => {
+            request
+                .data(ServerValue::RESP(RESP::Integer(length as i64)))
+                .await
+        }
+        Err(e) => request.error(ServerError::from(e)).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::run;
+    use crate::server_result::ServerMessage;
+    use crate::storage::Storage;
+
+    #[tokio::test]
+    async fn test_hstrlen() {
+        let mut storage = Storage::new();
+        storage
+            .hash_set(b"user", &[b"name".to_vec(), b"alice".to_vec()])
+            .unwrap();
+        let mut server = Server::with_new(storage);
+        assert_eq!(
+            run(&mut server, "HSTRLEN user name").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(5)))
+        );
+        assert_eq!(
+            run(&mut server, "HSTRLEN user age").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(0)))
+        );
+    }
+}
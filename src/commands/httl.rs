@@ -0,0 +1,153 @@
+use crate::{
+    commands::hexpire::parse_fields,
+    expire::{unix_time_ms, KeyTTL},
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{ServerError, ServerValue},
+};
+
+// HTTL, HPTTL, HEXPIRETIME and HPEXPIRETIME
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    let name = String::from_utf8_lossy(&command[0]).to_lowercase();
+    if command.len() < 5 {
+        request
+            .error(ServerError::WrongNumberOfArguments(name))
+            .await;
+        return;
+    }
+    let fields = match parse_fields(&command[2..]) {
+        Ok(fields) => fields,
+        Err(e) => {
+            request.error(e).await;
+            return;
+        }
+    };
+
+    let ttls = match storage.hash_ttl(&command[1], fields) {
+        Ok(ttls) => ttls,
+        Err(e) => {
+            request.error(ServerError::from(e)).await;
+            return;
+        }
+    };
+    let now = unix_time_ms(storage.now());
+    let expiries = ttls
+        .into_iter()
+        .map(|ttl| match ttl {
+            KeyTTL::Missing => -2,
+            KeyTTL::Persistent => -1,
+            KeyTTL::Expires(deadline) => {
+                let remaining = (deadline - now).max(0);
+                // seconds are rounded as TTL does
+                match name.as_str() {
+                    "httl" => (remaining + 500) / 1000,
+                    "hpttl" => remaining,
+                    "hexpiretime" => (deadline + 500) / 1000,
+                    _ => deadline,
+                }
+            }
+        })
+        .map(RESP::Integer)
+        .collect();
+    request.data(ServerValue::RESP(RESP::Array(expiries))).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use crate::commands::test_utils::run;
+    use crate::expire::ExpireTime;
+    use crate::server_result::ServerMessage;
+    use crate::storage::Storage;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn integers(values: &[i64]) -> ServerMessage {
+        ServerMessage::Data(ServerValue::RESP(RESP::Array(
+            values.iter().map(|&value| RESP::Integer(value)).collect(),
+        )))
+    }
+
+    #[tokio::test]
+    async fn test_field_expiries() {
+        let clock = ManualClock::new(UNIX_EPOCH + Duration::from_millis(1700000000000));
+        let mut storage = Storage::with_clock(clock.clone());
+        storage
+            .hash_set(
+                b"hash",
+                &[b"a".to_vec(), b"1".to_vec(), b"b".to_vec(), b"2".to_vec()],
+            )
+            .unwrap();
+        storage
+            .hash_expire(
+                b"hash",
+                &[b"a".to_vec()],
+                ExpireTime::Relative(10400),
+                &[],
+                "hpexpire",
+            )
+            .unwrap();
+        let mut server = Server::with_new(storage);
+
+        assert_eq!(
+            run(&mut server, "HTTL hash FIELDS 3 a b c").await,
+            integers(&[10, -1, -2])
+        );
+        assert_eq!(
+            run(&mut server, "hpttl hash FIELDS 1 a").await,
+            integers(&[10400])
+        );
+        assert_eq!(
+            run(&mut server, "HEXPIRETIME hash FIELDS 1 a").await,
+            integers(&[1700000010])
+        );
+        assert_eq!(
+            run(&mut server, "HPEXPIRETIME hash FIELDS 1 a").await,
+            integers(&[1700000010400])
+        );
+        assert_eq!(
+            run(&mut server, "HTTL missing FIELDS 1 a").await,
+            integers(&[-2])
+        );
+
+        // the field is gone once expired
+        clock.advance(Duration::from_millis(10400));
+        assert_eq!(
+            run(&mut server, "HTTL hash FIELDS 2 a b").await,
+            integers(&[-2, -1])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_invalid_arguments() {
+        let mut server = Server::with_new(Storage::new());
+        for (cmd, error) in [
+            ("HTTL hash 1 a b", ServerError::MissingFieldsArgument),
+            ("HTTL hash FIELDS 1 a b", ServerError::NumFieldsMismatch),
+            (
+                "HPTTL hash FIELDS -1 a",
+                ServerError::NumFieldsMustBePositive,
+            ),
+            (
+                "HTTL hash FIELDS 1",
+                ServerError::WrongNumberOfArguments(String::from("httl")),
+            ),
+        ] {
+            assert_eq!(
+                run(&mut server, cmd).await,
+                ServerMessage::Error(error),
+                "{}",
+                cmd
+            );
+        }
+    }
+}
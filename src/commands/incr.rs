@@ -0,0 +1,120 @@
+use crate::{
+    number::parse_i64,
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{ServerError, ServerValue},
+};
+
+// INCR, DECR, INCRBY and DECRBY
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    let name = String::from_utf8_lossy(&command[0]).to_lowercase();
+    let arity = match name.as_str() {
+        "incr" | "decr" => 2,
+        _ => 3,
+    };
+    if command.len() != arity {
+        request
+            .error(ServerError::WrongNumberOfArguments(name))
+            .await;
+        return;
+    }
+
+    let increment = match name.as_str() {
+        "incr" | "decr" => 1,
+        _ => match parse_i64(&command[2]) {
+            Some(increment) => increment,
+            None => {
+                request.error(ServerError::NotAnInteger).await;
+                return;
+            }
+        },
+    };
+    // DECR and DECRBY subtract the increment
+    let increment = if name.starts_with("decr") {
+        match increment.checked_neg() {
+            Some(increment) => increment,
+            None => {
+                request.error(ServerError::DecrementOverflow).await;
+                return;
+            }
+        }
+    } else {
+        increment
+    };
+
+    match storage.incr_by(&command[1], increment) {
+        Ok(value) => request.data(ServerValue::RESP(RESP::Integer(value))).await,
+        Err(e) => request.error(ServerError::from(e)).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::run;
+    use crate::server_result::ServerMessage;
+    use crate::set::SetArgs;
+    use crate::storage::Storage;
+
+    fn integer(value: i64) -> ServerMessage {
+        ServerMessage::Data(ServerValue::RESP(RESP::Integer(value)))
+    }
+
+    #[tokio::test]
+    async fn test_command() {
+        let mut server = Server::with_new(Storage::new());
+        assert_eq!(run(&mut server, "INCR counter").await, integer(1));
+        assert_eq!(run(&mut server, "incrby counter 10").await, integer(11));
+        assert_eq!(run(&mut server, "DECR counter").await, integer(10));
+        assert_eq!(run(&mut server, "DECRBY counter 15").await, integer(-5));
+    }
+
+    #[tokio::test]
+    async fn test_errors() {
+        let mut storage = Storage::new();
+        storage
+            .set(b"text".to_vec(), b"abc".to_vec(), SetArgs::new())
+            .unwrap();
+        storage
+            .set(
+                b"max".to_vec(),
+                b"9223372036854775807".to_vec(),
+                SetArgs::new(),
+            )
+            .unwrap();
+        let mut server = Server::with_new(storage);
+        assert_eq!(
+            run(&mut server, "INCR text").await,
+            ServerMessage::Error(ServerError::NotAnInteger)
+        );
+        assert_eq!(
+            run(&mut server, "INCRBY counter 1.5").await,
+            ServerMessage::Error(ServerError::NotAnInteger)
+        );
+        assert_eq!(
+            run(&mut server, "INCR max").await,
+            ServerMessage::Error(ServerError::Overflow)
+        );
+        assert_eq!(
+            run(&mut server, "DECRBY counter -9223372036854775808").await,
+            ServerMessage::Error(ServerError::DecrementOverflow)
+        );
+        assert_eq!(
+            run(&mut server, "INCRBY counter").await,
+            ServerMessage::Error(ServerError::WrongNumberOfArguments(String::from("incrby")))
+        );
+        assert_eq!(
+            run(&mut server, "DECR counter 1").await,
+            ServerMessage::Error(ServerError::WrongNumberOfArguments(String::from("decr")))
+        );
+    }
+}
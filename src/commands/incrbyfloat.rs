@@ -0,0 +1,88 @@
+use crate::{
+    number::parse_f64,
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{ServerError, ServerValue},
+};
+
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    if command.len() != 3 {
+        request
+            .error(ServerError::WrongNumberOfArguments(String::from(
+                "incrbyfloat",
+            )))
+            .await;
+        return;
+    }
+    let increment = match parse_f64(&command[2]) {
+        Some(increment) => increment,
+        None => {
+            request.error(ServerError::NotAFloat).await;
+            return;
+        }
+    };
+
+    match storage.incr_by_float(&command[1], increment) {
+        Ok(value) => {
+            request
+                .data(ServerValue::RESP(RESP::BulkString(value)))
+                .await
+        }
+        Err(e) => request.error(ServerError::from(e)).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::run;
+    use crate::server_result::ServerMessage;
+    use crate::storage::Storage;
+
+    fn bulk(value: &str) -> ServerMessage {
+        ServerMessage::Data(ServerValue::RESP(RESP::BulkString(
+            value.as_bytes().to_vec(),
+        )))
+    }
+
+    #[tokio::test]
+    async fn test_command() {
+        let mut server = Server::with_new(Storage::new());
+        assert_eq!(run(&mut server, "INCRBYFLOAT key 10.5").await, bulk("10.5"));
+        assert_eq!(run(&mut server, "INCRBYFLOAT key 0.1").await, bulk("10.6"));
+        assert_eq!(run(&mut server, "INCRBYFLOAT key -0.6").await, bulk("10"));
+        assert_eq!(run(&mut server, "INCRBYFLOAT key 1e2").await, bulk("110"));
+    }
+
+    #[tokio::test]
+    async fn test_errors() {
+        let mut server = Server::with_new(Storage::new());
+        assert_eq!(
+            run(&mut server, "INCRBYFLOAT key abc").await,
+            ServerMessage::Error(ServerError::NotAFloat)
+        );
+        assert_eq!(
+            run(&mut server, "INCRBYFLOAT key nan").await,
+            ServerMessage::Error(ServerError::NotAFloat)
+        );
+        assert_eq!(
+            run(&mut server, "INCRBYFLOAT key inf").await,
+            ServerMessage::Error(ServerError::NaNOrInfinity)
+        );
+        assert_eq!(
+            run(&mut server, "INCRBYFLOAT key").await,
+            ServerMessage::Error(ServerError::WrongNumberOfArguments(String::from(
+                "incrbyfloat"
+            )))
+        );
+    }
+}
@@ -0,0 +1,77 @@
+use crate::{
+    glob::string_match,
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{ServerError, ServerValue},
+};
+
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    if command.len() != 2 {
+        request
+            .error(ServerError::WrongNumberOfArguments(String::from("keys")))
+            .await;
+        return;
+    }
+
+    let pattern = &command[1];
+    let keys = if pattern.as_slice() == b"*" {
+        storage.keys(|_| true)
+    } else {
+        storage.keys(|key| string_match(pattern, key, false))
+    };
+    request
+        .data(ServerValue::RESP(RESP::Array(
+            keys.into_iter().map(RESP::BulkString).collect(),
+        )))
+        .await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server_result::ServerMessage;
+    use crate::set::SetArgs;
+    use crate::storage::Storage;
+    use tokio::sync::mpsc;
+
+    #[tokio::test]
+    async fn test_command() {
+        let mut storage = Storage::new();
+        for key in ["user:1", "user:2", "session:1"] {
+            storage
+                .set(key.as_bytes().to_vec(), b"v".to_vec(), SetArgs::new())
+                .unwrap();
+        }
+        let mut server = Server::with_new(storage);
+        let cmd = vec![b"keys".to_vec(), b"user:*".to_vec()];
+        let (connection_sender, mut connection_receiver) = mpsc::channel::<ServerMessage>(32);
+        let request = Request {
+            value: RESP::Null,
+            sender: connection_sender,
+            client_id: 0,
+        };
+        command(&mut server, &request, &cmd).await;
+        match connection_receiver.try_recv().unwrap() {
+            ServerMessage::Data(ServerValue::RESP(RESP::Array(mut keys))) => {
+                keys.sort_by_key(|key| format!("{}", key));
+                assert_eq!(
+                    keys,
+                    vec![
+                        RESP::BulkString(b"user:1".to_vec()),
+                        RESP::BulkString(b"user:2".to_vec())
+                    ]
+                );
+            }
+            other => panic!("unexpected reply {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use crate::{
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{ServerError, ServerValue},
+};
+
+// TYPE, named after the keyword
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    if command.len() != 2 {
+        request
+            .error(ServerError::WrongNumberOfArguments(String::from("type")))
+            .await;
+        return;
+    }
+
+    let name = storage.key_type(&command[1]).unwrap_or("none");
+    request
+        .data(ServerValue::RESP(RESP::SimpleString(name.to_string())))
+        .await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server_result::ServerMessage;
+    use crate::set::SetArgs;
+    use crate::storage::Storage;
+    use tokio::sync::mpsc;
+
+    #[tokio::test]
+    async fn test_command() {
+        let mut storage = Storage::new();
+        storage
+            .set(b"key".to_vec(), b"value".to_vec(), SetArgs::new())
+            .unwrap();
+        let mut server = Server::with_new(storage);
+        for (key, expected) in [("key", "string"), ("missing", "none")] {
+            let cmd = vec![b"type".to_vec(), key.as_bytes().to_vec()];
+            let (connection_sender, mut connection_receiver) = mpsc::channel::<ServerMessage>(32);
+            let request = Request {
+                value: RESP::Null,
+                sender: connection_sender,
+                client_id: 0,
+            };
+            command(&mut server, &request, &cmd).await;
+            assert_eq!(
+                connection_receiver.try_recv().unwrap(),
+                ServerMessage::Data(ServerValue::RESP(RESP::SimpleString(String::from(
+                    expected
+                ))))
+            );
+        }
+    }
+}
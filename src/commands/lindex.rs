@@ -0,0 +1,75 @@
+use crate::{
+    number::parse_i64,
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{ServerError, ServerValue},
+};
+
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    if command.len() != 3 {
+        request
+            .error(ServerError::WrongNumberOfArguments(String::from("lindex")))
+            .await;
+        return;
+    }
+    let Some(index) = parse_i64(&command[2]) else {
+        request.error(ServerError::NotAnInteger).await;
+        return;
+    };
+
+    match storage.list_index(&command[1], index) {
+        Ok(value) => {
+            request
+                .data(ServerValue::RESP(
+                    value.map_or(RESP::Null, RESP::BulkString),
+                ))
+                .await
+        }
+        Err(e) => request.error(ServerError::from(e)).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::run;
+    use crate::server_result::ServerMessage;
+    use crate::storage::Storage;
+
+    #[tokio::test]
+    async fn test_command() {
+        let mut storage = Storage::new();
+        storage
+            .push(
+                b"list",
+                &[b"a".to_vec(), b"b".to_vec(), b"c".to_vec()],
+                false,
+                false,
+            )
+            .unwrap();
+        let mut server = Server::with_new(storage);
+        for (cmd, expected) in [
+            ("LINDEX list 0", RESP::BulkString(b"a".to_vec())),
+            ("LINDEX list -1", RESP::BulkString(b"c".to_vec())),
+            ("LINDEX list 3", RESP::Null),
+            ("LINDEX list -4", RESP::Null),
+            ("LINDEX missing 0", RESP::Null),
+        ] {
+            assert_eq!(
+                run(&mut server, cmd).await,
+                ServerMessage::Data(ServerValue::RESP(expected)),
+                "{}",
+                cmd
+            );
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use crate::{
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{command_to_string, ServerError, ServerValue},
+};
+
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    if command.len() != 5 {
+        request
+            .error(ServerError::WrongNumberOfArguments(String::from("linsert")))
+            .await;
+        return;
+    }
+    let before = match command[2].to_ascii_lowercase().as_slice() {
+        b"before" => true,
+        b"after" => false,
+        _ => {
+            request
+                .error(ServerError::CommandSyntaxError(command_to_string(command)))
+                .await;
+            return;
+        }
+    };
+
+    match storage.list_insert(&command[1], &command[3], &command[4], before) {
+        Ok(length) => request.data(ServerValue::RESP(RESP::Integer(length))).await,
+        Err(e) => request.error(ServerError::from(e)).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::run;
+    use crate::server_result::ServerMessage;
+    use crate::storage::Storage;
+
+    #[tokio::test]
+    async fn test_command() {
+        let mut storage = Storage::new();
+        storage
+            .push(b"list", &[b"a".to_vec(), b"c".to_vec()], false, false)
+            .unwrap();
+        let mut server = Server::with_new(storage);
+        assert_eq!(
+            run(&mut server, "LINSERT list BEFORE c b").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(3)))
+        );
+        assert_eq!(
+            run(&mut server, "LINSERT list after c d").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(4)))
+        );
+        assert_eq!(
+            server
+                .storage
+                .as_mut()
+                .unwrap()
+                .list_range(b"list", 0, -1)
+                .unwrap(),
+            vec![b"a".to_vec(), b"b".to_vec(), b"c".to_vec(), b"d".to_vec()]
+        );
+        assert_eq!(
+            run(&mut server, "LINSERT list BEFORE z b").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(-1)))
+        );
+        assert_eq!(
+            run(&mut server, "LINSERT missing BEFORE a b").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(0)))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_wrong_syntax() {
+        let mut server = Server::with_new(Storage::new());
+        assert_eq!(
+            run(&mut server, "LINSERT list NEAR a b").await,
+            ServerMessage::Error(ServerError::CommandSyntaxError(String::from(
+                "LINSERT list NEAR a b"
+            )))
+        );
+    }
+}
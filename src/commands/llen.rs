@@ -0,0 +1,65 @@
+use crate::{
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{ServerError, ServerValue},
+};
+
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    if command.len() != 2 {
+        request
+            .error(ServerError::WrongNumberOfArguments(String::from("llen")))
+            .await;
+        return;
+    }
+
+    match storage.list_len(&command[1]) {
+        Ok(length) => {
+            request
+                .data(ServerValue::RESP(RESP::Integer(length as i64)))
+                .await
+        }
+        Err(e) => request.error(ServerError::from(e)).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::run;
+    use crate::server_result::ServerMessage;
+    use crate::set::SetArgs;
+    use crate::storage::Storage;
+
+    #[tokio::test]
+    async fn test_command() {
+        let mut storage = Storage::new();
+        storage
+            .push(b"list", &[b"a".to_vec(), b"b".to_vec()], true, false)
+            .unwrap();
+        storage
+            .set(b"string".to_vec(), b"value".to_vec(), SetArgs::new())
+            .unwrap();
+        let mut server = Server::with_new(storage);
+        assert_eq!(
+            run(&mut server, "LLEN list").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(2)))
+        );
+        assert_eq!(
+            run(&mut server, "LLEN missing").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(0)))
+        );
+        assert_eq!(
+            run(&mut server, "LLEN string").await,
+            ServerMessage::Error(ServerError::WrongType)
+        );
+    }
+}
@@ -0,0 +1,149 @@
+use crate::{
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{command_to_string, ServerError, ServerValue},
+};
+
+// Whether a LEFT or RIGHT argument designates the head of the list
+pub fn parse_side(side: &[u8]) -> Option<bool> {
+    match side.to_ascii_lowercase().as_slice() {
+        b"left" => Some(true),
+        b"right" => Some(false),
+        _ => None,
+    }
+}
+
+// LMOVE and RPOPLPUSH, the latter being LMOVE source destination RIGHT LEFT
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    let name = String::from_utf8_lossy(&command[0]).to_lowercase();
+    let arity = if name == "lmove" { 5 } else { 3 };
+    if command.len() != arity {
+        request
+            .error(ServerError::WrongNumberOfArguments(name))
+            .await;
+        return;
+    }
+
+    let sides = if name == "lmove" {
+        parse_side(&command[3]).zip(parse_side(&command[4]))
+    } else {
+        Some((false, true))
+    };
+    let Some((from_front, to_front)) = sides else {
+        request
+            .error(ServerError::CommandSyntaxError(command_to_string(command)))
+            .await;
+        return;
+    };
+
+    match storage.list_move(&command[1], &command[2], from_front, to_front) {
+        Ok(value) => {
+            request
+                .data(ServerValue::RESP(
+                    value.map_or(RESP::Null, RESP::BulkString),
+                ))
+                .await
+        }
+        Err(e) => request.error(ServerError::from(e)).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::{add_list, add_strings, run, server_with};
+    use crate::server_result::ServerMessage;
+
+    fn server_with_list() -> Server {
+        server_with(|storage| {
+            add_list(storage, "list", &["a", "b", "c"]);
+            add_strings(storage, &[("string", "value")]);
+        })
+    }
+
+    fn bulk(value: &str) -> ServerMessage {
+        ServerMessage::Data(ServerValue::RESP(RESP::BulkString(
+            value.as_bytes().to_vec(),
+        )))
+    }
+
+    #[tokio::test]
+    async fn test_lmove() {
+        let mut server = server_with_list();
+        assert_eq!(
+            run(&mut server, "LMOVE list other LEFT RIGHT").await,
+            bulk("a")
+        );
+        assert_eq!(
+            run(&mut server, "LMOVE list other right left").await,
+            bulk("c")
+        );
+        let storage = server.storage.as_mut().unwrap();
+        assert_eq!(
+            storage.list_range(b"other", 0, -1).unwrap(),
+            vec![b"c".to_vec(), b"a".to_vec()]
+        );
+        assert_eq!(
+            run(&mut server, "LMOVE list other LEFT LEFT").await,
+            bulk("b")
+        );
+        assert!(!server.storage.as_mut().unwrap().exists(b"list"));
+        assert_eq!(
+            run(&mut server, "LMOVE list other LEFT LEFT").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Null))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rotate() {
+        let mut server = server_with_list();
+        assert_eq!(run(&mut server, "RPOPLPUSH list list").await, bulk("c"));
+        assert_eq!(
+            server
+                .storage
+                .as_mut()
+                .unwrap()
+                .list_range(b"list", 0, -1)
+                .unwrap(),
+            vec![b"c".to_vec(), b"a".to_vec(), b"b".to_vec()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_wrong_type() {
+        let mut server = server_with_list();
+        assert_eq!(
+            run(&mut server, "LMOVE list string LEFT LEFT").await,
+            ServerMessage::Error(ServerError::WrongType)
+        );
+        // nothing is popped when the destination is not a list
+        assert_eq!(
+            server.storage.as_mut().unwrap().list_len(b"list").unwrap(),
+            3
+        );
+        assert_eq!(
+            run(&mut server, "RPOPLPUSH string list").await,
+            ServerMessage::Error(ServerError::WrongType)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_wrong_syntax() {
+        let mut server = server_with_list();
+        assert_eq!(
+            run(&mut server, "LMOVE list other UP LEFT").await,
+            ServerMessage::Error(ServerError::CommandSyntaxError(String::from(
+                "LMOVE list other UP LEFT"
+            )))
+        );
+    }
+}
@@ -0,0 +1,178 @@
+use crate::{
+    blocking::{self, parse_timeout, BlockingCommand},
+    commands::lmove::parse_side,
+    number::parse_i64,
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{command_to_string, ServerError, ServerValue},
+};
+
+// Parse `numkeys key [key ...] LEFT|RIGHT [COUNT count]`
+fn parse_lmpop_arguments(
+    arguments: &[Vec<u8>],
+    command: &[Vec<u8>],
+) -> Result<BlockingCommand, ServerError> {
+    let numkeys = parse_i64(&arguments[0])
+        .filter(|&numkeys| numkeys > 0)
+        .ok_or_else(|| ServerError::MustBePositive(String::from("numkeys")))?;
+    let syntax_error = || ServerError::CommandSyntaxError(command_to_string(command));
+    let numkeys = usize::try_from(numkeys)
+        .ok()
+        .filter(|&numkeys| numkeys < arguments.len() - 1)
+        .ok_or_else(syntax_error)?;
+    let keys = arguments[1..=numkeys].to_vec();
+    let front = parse_side(&arguments[numkeys + 1]).ok_or_else(syntax_error)?;
+
+    let count = match &arguments[numkeys + 2..] {
+        [] => 1,
+        [option, count] if option.eq_ignore_ascii_case(b"count") => parse_i64(count)
+            .filter(|&count| count > 0)
+            .ok_or_else(|| ServerError::MustBePositive(String::from("count")))?
+            as usize,
+        _ => return Err(syntax_error()),
+    };
+    Ok(BlockingCommand::MultiPop { keys, front, count })
+}
+
+// LMPOP and BLMPOP, popping from the first non empty list among
+// the keys, BLMPOP waiting for one to receive values if needed
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let name = String::from_utf8_lossy(&command[0]).to_lowercase();
+    let blocking = name == "blmpop";
+    let minimum = if blocking { 5 } else { 4 };
+    if command.len() < minimum {
+        request
+            .error(ServerError::WrongNumberOfArguments(name))
+            .await;
+        return;
+    }
+
+    let timeout = if blocking {
+        match parse_timeout(&command[1]) {
+            Ok(timeout) => timeout,
+            Err(e) => {
+                request.error(e).await;
+                return;
+            }
+        }
+    } else {
+        None
+    };
+    let arguments = if blocking {
+        &command[2..]
+    } else {
+        &command[1..]
+    };
+    let operation = match parse_lmpop_arguments(arguments, command) {
+        Ok(operation) => operation,
+        Err(e) => {
+            request.error(e).await;
+            return;
+        }
+    };
+
+    if blocking {
+        blocking::run(server, request, operation, timeout).await;
+        return;
+    }
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+    match operation.attempt(storage, None) {
+        Ok(reply) => {
+            request
+                .data(ServerValue::RESP(reply.unwrap_or(RESP::NullArray)))
+                .await
+        }
+        Err(e) => request.error(ServerError::from(e)).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::{add_list, send, server_with};
+    use crate::server_result::ServerMessage;
+
+    fn server_with_list() -> Server {
+        server_with(|storage| add_list(storage, "list", &["a", "b", "c"]))
+    }
+
+    #[tokio::test]
+    async fn test_lmpop() {
+        let mut server = server_with_list();
+        let mut receiver = send(&mut server, "LMPOP 2 missing list RIGHT COUNT 2").await;
+        assert_eq!(
+            receiver.try_recv().unwrap(),
+            ServerMessage::Data(ServerValue::RESP(RESP::Array(vec![
+                RESP::BulkString(b"list".to_vec()),
+                RESP::Array(vec![
+                    RESP::BulkString(b"c".to_vec()),
+                    RESP::BulkString(b"b".to_vec()),
+                ]),
+            ])))
+        );
+        let mut receiver = send(&mut server, "LMPOP 1 missing LEFT").await;
+        assert_eq!(
+            receiver.try_recv().unwrap(),
+            ServerMessage::Data(ServerValue::RESP(RESP::NullArray))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_blmpop() {
+        let mut server = server_with_list();
+        let mut receiver = send(&mut server, "BLMPOP 0 1 list left").await;
+        assert_eq!(
+            receiver.try_recv().unwrap(),
+            ServerMessage::Data(ServerValue::RESP(RESP::Array(vec![
+                RESP::BulkString(b"list".to_vec()),
+                RESP::Array(vec![RESP::BulkString(b"a".to_vec())]),
+            ])))
+        );
+        let mut receiver = send(&mut server, "BLMPOP 0 1 other LEFT").await;
+        assert!(receiver.try_recv().is_err());
+        assert_eq!(server.blocked.waiting_on(b"other"), vec![0]);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_arguments() {
+        let mut server = server_with_list();
+        for (cmd, error) in [
+            (
+                "LMPOP 0 list LEFT",
+                ServerError::MustBePositive(String::from("numkeys")),
+            ),
+            (
+                "LMPOP 2 list LEFT",
+                ServerError::CommandSyntaxError(String::from("LMPOP 2 list LEFT")),
+            ),
+            (
+                "LMPOP 1 list UP",
+                ServerError::CommandSyntaxError(String::from("LMPOP 1 list UP")),
+            ),
+            (
+                "LMPOP 1 list LEFT COUNT 0",
+                ServerError::MustBePositive(String::from("count")),
+            ),
+            (
+                "LMPOP 1 list LEFT LIMIT 2",
+                ServerError::CommandSyntaxError(String::from("LMPOP 1 list LEFT LIMIT 2")),
+            ),
+            ("BLMPOP x 1 list LEFT", ServerError::TimeoutNotAFloat),
+        ] {
+            let mut receiver = send(&mut server, cmd).await;
+            assert_eq!(
+                receiver.try_recv().unwrap(),
+                ServerMessage::Error(error),
+                "{}",
+                cmd
+            );
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use crate::{
+    number::parse_i64,
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{ServerError, ServerValue},
+};
+
+// LPOP and RPOP, which reply with a single value
+// or with an array when given a count
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    let name = String::from_utf8_lossy(&command[0]).to_lowercase();
+    if command.len() != 2 && command.len() != 3 {
+        request
+            .error(ServerError::WrongNumberOfArguments(name))
+            .await;
+        return;
+    }
+
+    let count = match command.get(2).map(|count| parse_i64(count)) {
+        None => None,
+        Some(Some(count)) if count >= 0 => Some(count as usize),
+        Some(_) => {
+            request.error(ServerError::ValueMustBePositive).await;
+            return;
+        }
+    };
+
+    let front = name == "lpop";
+    let values = match storage.pop(&command[1], count.unwrap_or(1), front) {
+        Ok(values) => values,
+        Err(e) => {
+            request.error(ServerError::from(e)).await;
+            return;
+        }
+    };
+    let reply = match (count, values) {
+        (None, Some(mut values)) => values.pop().map_or(RESP::Null, RESP::BulkString),
+        (None, None) => RESP::Null,
+        (Some(_), Some(values)) => RESP::Array(values.into_iter().map(RESP::BulkString).collect()),
+        (Some(_), None) => RESP::NullArray,
+    };
+    request.data(ServerValue::RESP(reply)).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::{add_list, run, server_with};
+    use crate::server_result::ServerMessage;
+
+    fn server_with_list() -> Server {
+        server_with(|storage| add_list(storage, "list", &["a", "b", "c", "d"]))
+    }
+
+    #[tokio::test]
+    async fn test_pop() {
+        let mut server = server_with_list();
+        assert_eq!(
+            run(&mut server, "LPOP list").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::BulkString(b"a".to_vec())))
+        );
+        assert_eq!(
+            run(&mut server, "RPOP list").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::BulkString(b"d".to_vec())))
+        );
+        assert_eq!(
+            run(&mut server, "LPOP missing").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Null))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pop_count() {
+        let mut server = server_with_list();
+        assert_eq!(
+            run(&mut server, "RPOP list 3").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Array(vec![
+                RESP::BulkString(b"d".to_vec()),
+                RESP::BulkString(b"c".to_vec()),
+                RESP::BulkString(b"b".to_vec()),
+            ])))
+        );
+        assert_eq!(
+            run(&mut server, "LPOP list 0").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Array(vec![])))
+        );
+        assert_eq!(
+            run(&mut server, "LPOP list 5").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Array(vec![RESP::BulkString(
+                b"a".to_vec()
+            )])))
+        );
+        // the list is deleted once empty
+        assert!(!server.storage.as_mut().unwrap().exists(b"list"));
+        assert_eq!(
+            run(&mut server, "LPOP list 2").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::NullArray))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_invalid_count() {
+        let mut server = server_with_list();
+        for cmd in ["LPOP list -1", "LPOP list one"] {
+            assert_eq!(
+                run(&mut server, cmd).await,
+                ServerMessage::Error(ServerError::ValueMustBePositive)
+            );
+        }
+    }
+}
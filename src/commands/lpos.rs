@@ -0,0 +1,197 @@
+use crate::{
+    number::parse_i64,
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{command_to_string, ServerError, ServerValue},
+};
+
+#[derive(Debug, PartialEq)]
+struct LposArgs {
+    rank: i64,
+    count: Option<usize>,
+    maxlen: usize,
+}
+
+// Parse the RANK, COUNT and MAXLEN options following the element
+fn parse_lpos_arguments(command: &[Vec<u8>]) -> Result<LposArgs, ServerError> {
+    let mut args = LposArgs {
+        rank: 1,
+        count: None,
+        maxlen: 0,
+    };
+    for option in command[3..].chunks(2) {
+        let [name, value] = option else {
+            return Err(ServerError::CommandSyntaxError(command_to_string(command)));
+        };
+        let name = name.to_ascii_uppercase();
+        if !matches!(name.as_slice(), b"RANK" | b"COUNT" | b"MAXLEN") {
+            return Err(ServerError::CommandSyntaxError(command_to_string(command)));
+        }
+        let value = parse_i64(value).ok_or(ServerError::NotAnInteger)?;
+        match name.as_slice() {
+            b"RANK" if value == 0 => return Err(ServerError::ZeroRank),
+            b"RANK" if value == i64::MIN => return Err(ServerError::ValueOutOfRange),
+            b"RANK" => args.rank = value,
+            _ if value < 0 => {
+                return Err(ServerError::NegativeArgument(
+                    String::from_utf8_lossy(&name).into_owned(),
+                ))
+            }
+            b"COUNT" => args.count = Some(value as usize),
+            _ => args.maxlen = value as usize,
+        }
+    }
+    Ok(args)
+}
+
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    if command.len() < 3 {
+        request
+            .error(ServerError::WrongNumberOfArguments(String::from("lpos")))
+            .await;
+        return;
+    }
+    let args = match parse_lpos_arguments(command) {
+        Ok(args) => args,
+        Err(e) => {
+            request.error(e).await;
+            return;
+        }
+    };
+
+    let positions = match storage.list_positions(
+        &command[1],
+        &command[2],
+        args.rank,
+        args.count.unwrap_or(1),
+        args.maxlen,
+    ) {
+        Ok(positions) => positions,
+        Err(e) => {
+            request.error(ServerError::from(e)).await;
+            return;
+        }
+    };
+    // with COUNT the reply is an array, even for a single match
+    let reply = match args.count {
+        Some(_) => RESP::Array(
+            positions
+                .into_iter()
+                .map(|i| RESP::Integer(i as i64))
+                .collect(),
+        ),
+        None => positions
+            .first()
+            .map_or(RESP::Null, |&i| RESP::Integer(i as i64)),
+    };
+    request.data(ServerValue::RESP(reply)).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::{add_list, run, server_with};
+    use crate::server_result::ServerMessage;
+
+    fn server_with_list() -> Server {
+        server_with(|storage| add_list(storage, "list", &["a", "b", "c", "1", "2", "3", "c", "c"]))
+    }
+
+    fn integers(values: &[i64]) -> ServerMessage {
+        ServerMessage::Data(ServerValue::RESP(RESP::Array(
+            values.iter().map(|&v| RESP::Integer(v)).collect(),
+        )))
+    }
+
+    #[tokio::test]
+    async fn test_command() {
+        let mut server = server_with_list();
+        for (cmd, expected) in [
+            ("LPOS list c", RESP::Integer(2)),
+            ("LPOS list c RANK 2", RESP::Integer(6)),
+            ("LPOS list c RANK -1", RESP::Integer(7)),
+            ("LPOS list c RANK 4", RESP::Null),
+            ("LPOS list c MAXLEN 2", RESP::Null),
+            ("LPOS list z", RESP::Null),
+            ("LPOS missing c", RESP::Null),
+        ] {
+            assert_eq!(
+                run(&mut server, cmd).await,
+                ServerMessage::Data(ServerValue::RESP(expected)),
+                "{}",
+                cmd
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_count() {
+        let mut server = server_with_list();
+        assert_eq!(
+            run(&mut server, "LPOS list c COUNT 2").await,
+            integers(&[2, 6])
+        );
+        assert_eq!(
+            run(&mut server, "LPOS list c COUNT 0").await,
+            integers(&[2, 6, 7])
+        );
+        assert_eq!(
+            run(&mut server, "LPOS list c RANK -1 COUNT 2").await,
+            integers(&[7, 6])
+        );
+        assert_eq!(
+            run(&mut server, "LPOS list c COUNT 0 MAXLEN 7").await,
+            integers(&[2, 6])
+        );
+        assert_eq!(run(&mut server, "LPOS list z COUNT 1").await, integers(&[]));
+        assert_eq!(
+            run(&mut server, "LPOS missing z COUNT 1").await,
+            integers(&[])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_invalid_options() {
+        let mut server = server_with_list();
+        for (cmd, error) in [
+            ("LPOS list c RANK 0", ServerError::ZeroRank),
+            (
+                "LPOS list c RANK -9223372036854775808",
+                ServerError::ValueOutOfRange,
+            ),
+            (
+                "LPOS list c COUNT -1",
+                ServerError::NegativeArgument(String::from("COUNT")),
+            ),
+            (
+                "LPOS list c maxlen -1",
+                ServerError::NegativeArgument(String::from("MAXLEN")),
+            ),
+            ("LPOS list c COUNT x", ServerError::NotAnInteger),
+            (
+                "LPOS list c COUNT",
+                ServerError::CommandSyntaxError(String::from("LPOS list c COUNT")),
+            ),
+            (
+                "LPOS list c LIMIT 1",
+                ServerError::CommandSyntaxError(String::from("LPOS list c LIMIT 1")),
+            ),
+        ] {
+            assert_eq!(
+                run(&mut server, cmd).await,
+                ServerMessage::Error(error),
+                "{}",
+                cmd
+            );
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use crate::{
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{ServerError, ServerValue},
+};
+
+// LPUSH, RPUSH, LPUSHX and RPUSHX, the latter two
+// only pushing to lists which already exist
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    let name = String::from_utf8_lossy(&command[0]).to_lowercase();
+    if command.len() < 3 {
+        request
+            .error(ServerError::WrongNumberOfArguments(name))
+            .await;
+        return;
+    }
+
+    let front = name.starts_with('l');
+    let existing = name.ends_with('x');
+    match storage.push(&command[1], &command[2..], front, existing) {
+        Ok(length) => {
+            request
+                .data(ServerValue::RESP(RESP::Integer(length as i64)))
+                .await
+        }
+        Err(e) => request.error(ServerError::from(e)).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::run;
+    use crate::server_result::ServerMessage;
+    use crate::set::SetArgs;
+    use crate::storage::Storage;
+
+    #[tokio::test]
+    async fn test_push() {
+        let mut server = Server::with_new(Storage::new());
+        assert_eq!(
+            run(&mut server, "LPUSH list b a").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(2)))
+        );
+        assert_eq!(
+            run(&mut server, "RPUSH list c d").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(4)))
+        );
+        let storage = server.storage.as_mut().unwrap();
+        assert_eq!(
+            storage.list_range(b"list", 0, -1).unwrap(),
+            vec![b"a".to_vec(), b"b".to_vec(), b"c".to_vec(), b"d".to_vec()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_push_existing() {
+        let mut server = Server::with_new(Storage::new());
+        assert_eq!(
+            run(&mut server, "LPUSHX list a").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(0)))
+        );
+        assert!(!server.storage.as_mut().unwrap().exists(b"list"));
+        run(&mut server, "RPUSH list a").await;
+        assert_eq!(
+            run(&mut server, "RPUSHX list b").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(2)))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_wrong_type() {
+        let mut storage = Storage::new();
+        storage
+            .set(b"key".to_vec(), b"value".to_vec(), SetArgs::new())
+            .unwrap();
+        let mut server = Server::with_new(storage);
+        assert_eq!(
+            run(&mut server, "LPUSH key a").await,
+            ServerMessage::Error(ServerError::WrongType)
+        );
+        assert_eq!(
+            run(&mut server, "RPUSHX key a").await,
+            ServerMessage::Error(ServerError::WrongType)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_wrong_number_of_arguments() {
+        let mut server = Server::with_new(Storage::new());
+        assert_eq!(
+            run(&mut server, "RPUSH list").await,
+            ServerMessage::Error(ServerError::WrongNumberOfArguments(String::from("rpush")))
+        );
+    }
+}
@@ -0,0 +1,91 @@
+use crate::{
+    number::parse_i64,
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{ServerError, ServerValue},
+};
+
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    if command.len() != 4 {
+        request
+            .error(ServerError::WrongNumberOfArguments(String::from("lrange")))
+            .await;
+        return;
+    }
+    let (start, end) = match (parse_i64(&command[2]), parse_i64(&command[3])) {
+        (Some(start), Some(end)) => (start, end),
+        _ => {
+            request.error(ServerError::NotAnInteger).await;
+            return;
+        }
+    };
+
+    match storage.list_range(&command[1], start, end) {
+        Ok(values) => {
+            request
+                .data(ServerValue::RESP(RESP::Array(
+                    values.into_iter().map(RESP::BulkString).collect(),
+                )))
+                .await
+        }
+        Err(e) => request.error(ServerError::from(e)).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::run;
+    use crate::server_result::ServerMessage;
+    use crate::storage::Storage;
+
+    fn array(values: &[&str]) -> ServerMessage {
+        ServerMessage::Data(ServerValue::RESP(RESP::Array(
+            values
+                .iter()
+                .map(|v| RESP::BulkString(v.as_bytes().to_vec()))
+                .collect(),
+        )))
+    }
+
+    #[tokio::test]
+    async fn test_command() {
+        let mut storage = Storage::new();
+        let values: Vec<Vec<u8>> = ["a", "b", "c", "d"]
+            .iter()
+            .map(|v| v.as_bytes().to_vec())
+            .collect();
+        storage.push(b"list", &values, false, false).unwrap();
+        let mut server = Server::with_new(storage);
+        for (cmd, expected) in [
+            ("LRANGE list 0 -1", vec!["a", "b", "c", "d"]),
+            ("LRANGE list 1 2", vec!["b", "c"]),
+            ("LRANGE list -2 100", vec!["c", "d"]),
+            ("LRANGE list -100 0", vec!["a"]),
+            ("LRANGE list 3 1", vec![]),
+            ("LRANGE list 4 10", vec![]),
+            ("LRANGE list -10 -5", vec![]),
+            ("LRANGE missing 0 -1", vec![]),
+        ] {
+            assert_eq!(run(&mut server, cmd).await, array(&expected), "{}", cmd);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_not_an_integer() {
+        let mut server = Server::with_new(Storage::new());
+        assert_eq!(
+            run(&mut server, "LRANGE list 0 end").await,
+            ServerMessage::Error(ServerError::NotAnInteger)
+        );
+    }
+}
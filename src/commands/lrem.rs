@@ -0,0 +1,88 @@
+use crate::{
+    number::parse_i64,
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{ServerError, ServerValue},
+};
+
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    if command.len() != 4 {
+        request
+            .error(ServerError::WrongNumberOfArguments(String::from("lrem")))
+            .await;
+        return;
+    }
+    let Some(count) = parse_i64(&command[2]) else {
+        request.error(ServerError::NotAnInteger).await;
+        return;
+    };
+
+    match storage.list_remove(&command[1], count, &command[3]) {
+        Ok(removed) => {
+            request
+                .data(ServerValue::RESP(RESP::Integer(removed as i64)))
+                .await
+        }
+        Err(e) => request.error(ServerError::from(e)).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::run;
+    use crate::server_result::ServerMessage;
+    use crate::storage::Storage;
+
+    #[tokio::test]
+    async fn test_command() {
+        let mut storage = Storage::new();
+        let values: Vec<Vec<u8>> = ["x", "a", "x", "b", "x", "x"]
+            .iter()
+            .map(|v| v.as_bytes().to_vec())
+            .collect();
+        storage.push(b"list", &values, false, false).unwrap();
+        let mut server = Server::with_new(storage);
+        assert_eq!(
+            run(&mut server, "LREM list -2 x").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(2)))
+        );
+        assert_eq!(
+            run(&mut server, "LREM list 1 x").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(1)))
+        );
+        let storage = server.storage.as_mut().unwrap();
+        assert_eq!(
+            storage.list_range(b"list", 0, -1).unwrap(),
+            vec![b"a".to_vec(), b"x".to_vec(), b"b".to_vec()]
+        );
+        assert_eq!(
+            run(&mut server, "LREM list 0 a").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(1)))
+        );
+        assert_eq!(
+            run(&mut server, "LREM missing 0 a").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(0)))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_removes_empty_list() {
+        let mut storage = Storage::new();
+        storage
+            .push(b"list", &[b"x".to_vec(), b"x".to_vec()], false, false)
+            .unwrap();
+        let mut server = Server::with_new(storage);
+        run(&mut server, "LREM list 0 x").await;
+        assert!(!server.storage.as_mut().unwrap().exists(b"list"));
+    }
+}
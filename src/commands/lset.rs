@@ -0,0 +1,75 @@
+use crate::{
+    number::parse_i64,
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{ServerError, ServerValue},
+};
+
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    if command.len() != 4 {
+        request
+            .error(ServerError::WrongNumberOfArguments(String::from("lset")))
+            .await;
+        return;
+    }
+    let Some(index) = parse_i64(&command[2]) else {
+        request.error(ServerError::NotAnInteger).await;
+        return;
+    };
+
+    match storage.list_set(&command[1], index, &command[3]) {
+        Ok(()) => {
+            request
+                .data(ServerValue::RESP(RESP::SimpleString(String::from("OK"))))
+                .await
+        }
+        Err(e) => request.error(ServerError::from(e)).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::run;
+    use crate::server_result::ServerMessage;
+    use crate::storage::Storage;
+
+    #[tokio::test]
+    async fn test_command() {
+        let mut storage = Storage::new();
+        storage
+            .push(b"list", &[b"a".to_vec(), b"b".to_vec()], false, false)
+            .unwrap();
+        let mut server = Server::with_new(storage);
+        assert_eq!(
+            run(&mut server, "LSET list -1 z").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::SimpleString(String::from("OK"))))
+        );
+        assert_eq!(
+            server
+                .storage
+                .as_mut()
+                .unwrap()
+                .list_range(b"list", 0, -1)
+                .unwrap(),
+            vec![b"a".to_vec(), b"z".to_vec()]
+        );
+        assert_eq!(
+            run(&mut server, "LSET list 2 z").await,
+            ServerMessage::Error(ServerError::IndexOutOfRange)
+        );
+        assert_eq!(
+            run(&mut server, "LSET missing 0 z").await,
+            ServerMessage::Error(ServerError::NoSuchKey)
+        );
+    }
+}
@@ -0,0 +1,74 @@
+use crate::{
+    number::parse_i64,
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{ServerError, ServerValue},
+};
+
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    if command.len() != 4 {
+        request
+            .error(ServerError::WrongNumberOfArguments(String::from("ltrim")))
+            .await;
+        return;
+    }
+    let (start, end) = match (parse_i64(&command[2]), parse_i64(&command[3])) {
+        (Some(start), Some(end)) => (start, end),
+        _ => {
+            request.error(ServerError::NotAnInteger).await;
+            return;
+        }
+    };
+
+    match storage.list_trim(&command[1], start, end) {
+        Ok(()) => {
+            request
+                .data(ServerValue::RESP(RESP::SimpleString(String::from("OK"))))
+                .await
+        }
+        Err(e) => request.error(ServerError::from(e)).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::run;
+    use crate::server_result::ServerMessage;
+    use crate::storage::Storage;
+
+    #[tokio::test]
+    async fn test_command() {
+        let mut storage = Storage::new();
+        let values: Vec<Vec<u8>> = ["a", "b", "c", "d"]
+            .iter()
+            .map(|v| v.as_bytes().to_vec())
+            .collect();
+        storage.push(b"list", &values, false, false).unwrap();
+        let mut server = Server::with_new(storage);
+        assert_eq!(
+            run(&mut server, "LTRIM list 1 -1").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::SimpleString(String::from("OK"))))
+        );
+        assert_eq!(
+            server
+                .storage
+                .as_mut()
+                .unwrap()
+                .list_range(b"list", 0, -1)
+                .unwrap(),
+            vec![b"b".to_vec(), b"c".to_vec(), b"d".to_vec()]
+        );
+        run(&mut server, "LTRIM list 5 10").await;
+        assert!(!server.storage.as_mut().unwrap().exists(b"list"));
+    }
+}
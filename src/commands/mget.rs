@@ -0,0 +1,97 @@
+use crate::{
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{ServerError, ServerValue},
+    storage_result::StorageError,
+};
+
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    if command.len() < 2 {
+        request
+            .error(ServerError::WrongNumberOfArguments(String::from("mget")))
+            .await;
+        return;
+    }
+
+    let mut values = Vec::with_capacity(command.len() - 1);
+    for key in command[1..].iter() {
+        match storage.get(key) {
+            Ok(Some(v)) => values.push(RESP::BulkString(v)),
+            // values of other types are reported as missing
+            Ok(None) | Err(StorageError::WrongType) => values.push(RESP::Null),
+            Err(e) => {
+                request.error(ServerError::from(e)).await;
+                return;
+            }
+        }
+    }
+    request.data(ServerValue::RESP(RESP::Array(values))).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server_result::ServerMessage;
+    use crate::set::SetArgs;
+    use crate::storage::Storage;
+    use tokio::sync::mpsc;
+
+    #[tokio::test]
+    async fn test_command() {
+        let mut storage = Storage::new();
+        storage
+            .set(b"key1".to_vec(), b"value1".to_vec(), SetArgs::new())
+            .unwrap();
+        storage
+            .set(b"key2".to_vec(), b"value2".to_vec(), SetArgs::new())
+            .unwrap();
+        let mut server = Server::with_new(storage);
+        let cmd = vec![
+            b"mget".to_vec(),
+            b"key1".to_vec(),
+            b"missing".to_vec(),
+            b"key2".to_vec(),
+        ];
+        let (connection_sender, mut connection_receiver) = mpsc::channel::<ServerMessage>(32);
+        let request = Request {
+            value: RESP::Null,
+            sender: connection_sender,
+            client_id: 0,
+        };
+        command(&mut server, &request, &cmd).await;
+        assert_eq!(
+            connection_receiver.try_recv().unwrap(),
+            ServerMessage::Data(ServerValue::RESP(RESP::Array(vec![
+                RESP::BulkString(b"value1".to_vec()),
+                RESP::Null,
+                RESP::BulkString(b"value2".to_vec()),
+            ])))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_wrong_syntax() {
+        let mut server = Server::with_new(Storage::new());
+        let cmd = vec![b"mget".to_vec()];
+        let (connection_sender, mut connection_receiver) = mpsc::channel::<ServerMessage>(32);
+        let request = Request {
+            value: RESP::Null,
+            sender: connection_sender,
+            client_id: 0,
+        };
+        command(&mut server, &request, &cmd).await;
+        assert_eq!(
+            connection_receiver.try_recv().unwrap(),
+            ServerMessage::Error(ServerError::WrongNumberOfArguments("mget".to_string()))
+        );
+    }
+}
@@ -1,4 +1,171 @@
+pub mod append;
+pub mod blmove;
+pub mod blpop;
+pub mod bzpopmin;
+pub mod copy;
+pub mod del;
 pub mod echo;
+pub mod exists;
+pub mod expire;
 pub mod get;
+pub mod getdel;
+pub mod getex;
+pub mod getrange;
+pub mod getset;
+pub mod hdel;
+pub mod hello;
+pub mod hexists;
+pub mod hexpire;
+pub mod hget;
+pub mod hgetall;
+pub mod hincrby;
+pub mod hincrbyfloat;
+pub mod hlen;
+pub mod hmget;
+pub mod hpersist;
+pub mod hrandfield;
+pub mod hscan;
+pub mod hset;
+pub mod hsetnx;
+pub mod hstrlen;
+pub mod httl;
+pub mod incr;
+pub mod incrbyfloat;
+pub mod keys;
+pub mod keytype;
+pub mod lindex;
+pub mod linsert;
+pub mod llen;
+pub mod lmove;
+pub mod lmpop;
+pub mod lpop;
+pub mod lpos;
+pub mod lpush;
+pub mod lrange;
+pub mod lrem;
+pub mod lset;
+pub mod ltrim;
+pub mod mget;
+pub mod mset;
+pub mod persist;
 pub mod ping;
+pub mod rename;
+pub mod sadd;
+pub mod scan;
+pub mod scard;
 pub mod set;
+pub mod setex;
+pub mod setnx;
+pub mod setrange;
+pub mod sinter;
+pub mod sintercard;
+pub mod sismember;
+pub mod smembers;
+pub mod smove;
+pub mod spop;
+pub mod srandmember;
+pub mod srem;
+pub mod strlen;
+pub mod ttl;
+pub mod zadd;
+pub mod zcard;
+pub mod zincrby;
+pub mod zmpop;
+pub mod zrange;
+pub mod zrank;
+pub mod zrem;
+pub mod zscore;
+pub mod zunionstore;
+
+// Helpers shared by the tests of the commands, which run them through
+// the dispatch of the server as a client would send them
+#[cfg(test)]
+pub mod test_utils {
+    use tokio::sync::mpsc;
+
+    use crate::{
+        request::Request,
+        resp::RESP,
+        server::{execute_request, Server},
+        server_result::ServerMessage,
+        set::SetArgs,
+        storage::Storage,
+        zset::ZAddOptions,
+    };
+
+    pub fn bytes(values: &[&str]) -> Vec<Vec<u8>> {
+        values.iter().map(|v| v.as_bytes().to_vec()).collect()
+    }
+
+    // Run a command given as arguments, returns the
+    // receiver of the replies, if any
+    pub async fn send_args(server: &mut Server, args: &[&str]) -> mpsc::Receiver<ServerMessage> {
+        let (sender, receiver) = mpsc::channel::<ServerMessage>(32);
+        let request = Request {
+            value: RESP::Array(bytes(args).into_iter().map(RESP::BulkString).collect()),
+            sender,
+            client_id: 0,
+        };
+        execute_request(request, server).await;
+        receiver
+    }
+
+    // Same as send_args for a command whose arguments are separated by spaces
+    pub async fn send(server: &mut Server, cmd: &str) -> mpsc::Receiver<ServerMessage> {
+        send_args(server, &cmd.split(' ').collect::<Vec<&str>>()).await
+    }
+
+    // Run a command given as arguments, returns its reply
+    pub async fn run_args(server: &mut Server, args: &[&str]) -> ServerMessage {
+        send_args(server, args).await.try_recv().unwrap()
+    }
+
+    // Run a command whose arguments are separated by spaces, returns its reply
+    pub async fn run(server: &mut Server, cmd: &str) -> ServerMessage {
+        send(server, cmd).await.try_recv().unwrap()
+    }
+
+    pub fn add_strings(storage: &mut Storage, pairs: &[(&str, &str)]) {
+        for (key, value) in pairs {
+            storage
+                .set(
+                    key.as_bytes().to_vec(),
+                    value.as_bytes().to_vec(),
+                    SetArgs::new(),
+                )
+                .unwrap();
+        }
+    }
+
+    pub fn add_list(storage: &mut Storage, key: &str, values: &[&str]) {
+        storage
+            .push(key.as_bytes(), &bytes(values), false, false)
+            .unwrap();
+    }
+
+    // `pairs` alternates fields and values
+    pub fn add_hash(storage: &mut Storage, key: &str, pairs: &[&str]) {
+        storage.hash_set(key.as_bytes(), &bytes(pairs)).unwrap();
+    }
+
+    pub fn add_set(storage: &mut Storage, key: &str, members: &[&str]) {
+        storage.set_add(key.as_bytes(), &bytes(members)).unwrap();
+    }
+
+    pub fn add_zset(storage: &mut Storage, key: &str, entries: &[(f64, &str)]) {
+        let entries: Vec<(f64, Vec<u8>)> = entries
+            .iter()
+            .map(|(score, member)| (*score, member.as_bytes().to_vec()))
+            .collect();
+        storage
+            .zset_add(key.as_bytes(), &entries, &ZAddOptions::default())
+            .unwrap();
+    }
+
+    // Server whose storage is filled by `fill`
+    pub fn server_with(fill: impl FnOnce(&mut Storage)) -> Server {
+        let mut storage = Storage::new();
+        fill(&mut storage);
+        Server::with_new(storage)
+    }
+}
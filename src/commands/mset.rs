@@ -0,0 +1,103 @@
+use crate::{
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{ServerError, ServerValue},
+    set::SetArgs,
+};
+
+// MSET and MSETNX, the latter setting the keys only if none
+// of them exists. Requests are processed one at a time by the
+// server, so no other client sees the keys partially set.
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    let name = String::from_utf8_lossy(&command[0]).to_lowercase();
+    if command.len() < 3 || command.len().is_multiple_of(2) {
+        request
+            .error(ServerError::WrongNumberOfArguments(name))
+            .await;
+        return;
+    }
+
+    let pairs = command[1..].chunks(2);
+    if name == "msetnx" && pairs.clone().any(|pair| storage.exists(&pair[0])) {
+        request.data(ServerValue::RESP(RESP::Integer(0))).await;
+        return;
+    }
+    for pair in pairs {
+        if let Err(e) = storage.set(pair[0].clone(), pair[1].clone(), SetArgs::new()) {
+            request.error(ServerError::from(e)).await;
+            return;
+        }
+    }
+
+    let reply = if name == "msetnx" {
+        RESP::Integer(1)
+    } else {
+        RESP::SimpleString(String::from("OK"))
+    };
+    request.data(ServerValue::RESP(reply)).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::run;
+    use crate::server_result::ServerMessage;
+    use crate::storage::Storage;
+
+    fn get(server: &mut Server, key: &str) -> Option<Vec<u8>> {
+        server
+            .storage
+            .as_mut()
+            .unwrap()
+            .get(key.as_bytes())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_mset() {
+        let mut server = Server::with_new(Storage::new());
+        assert_eq!(
+            run(&mut server, "MSET a 1 b 2 a 3").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::SimpleString(String::from("OK"))))
+        );
+        assert_eq!(get(&mut server, "a"), Some(b"3".to_vec()));
+        assert_eq!(get(&mut server, "b"), Some(b"2".to_vec()));
+    }
+
+    #[tokio::test]
+    async fn test_msetnx() {
+        let mut server = Server::with_new(Storage::new());
+        assert_eq!(
+            run(&mut server, "MSETNX a 1 b 2").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(1)))
+        );
+        assert_eq!(
+            run(&mut server, "MSETNX c 3 b 4").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(0)))
+        );
+        assert_eq!(get(&mut server, "b"), Some(b"2".to_vec()));
+        assert_eq!(get(&mut server, "c"), None);
+    }
+
+    #[tokio::test]
+    async fn test_wrong_number_of_arguments() {
+        let mut server = Server::with_new(Storage::new());
+        assert_eq!(
+            run(&mut server, "MSET a 1 b").await,
+            ServerMessage::Error(ServerError::WrongNumberOfArguments(String::from("mset")))
+        );
+        assert_eq!(
+            run(&mut server, "msetnx a").await,
+            ServerMessage::Error(ServerError::WrongNumberOfArguments(String::from("msetnx")))
+        );
+    }
+}
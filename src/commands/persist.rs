@@ -0,0 +1,67 @@
+use crate::{
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{ServerError, ServerValue},
+};
+
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    if command.len() != 2 {
+        request
+            .error(ServerError::WrongNumberOfArguments(String::from("persist")))
+            .await;
+        return;
+    }
+
+    match storage.persist(&command[1]) {
+        Ok(removed) => {
+            request
+                .data(ServerValue::RESP(RESP::Integer(removed as i64)))
+                .await
+        }
+        Err(e) => request.error(ServerError::from(e)).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server_result::ServerMessage;
+    use crate::set::{KeyExipry, SetArgs};
+    use crate::storage::Storage;
+    use tokio::sync::mpsc;
+
+    #[tokio::test]
+    async fn test_command() {
+        let mut storage = Storage::new();
+        let mut args = SetArgs::new();
+        args.expiry = Some(KeyExipry::EX(100));
+        storage
+            .set(b"key".to_vec(), b"value".to_vec(), args)
+            .unwrap();
+        let mut server = Server::with_new(storage);
+        let cmd = vec![b"persist".to_vec(), b"key".to_vec()];
+
+        for expected in [1, 0] {
+            let (connection_sender, mut connection_receiver) = mpsc::channel::<ServerMessage>(32);
+            let request = Request {
+                value: RESP::Null,
+                sender: connection_sender,
+                client_id: 0,
+            };
+            command(&mut server, &request, &cmd).await;
+            assert_eq!(
+                connection_receiver.try_recv().unwrap(),
+                ServerMessage::Data(ServerValue::RESP(RESP::Integer(expected)))
+            );
+        }
+    }
+}
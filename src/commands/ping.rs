@@ -1,9 +1,28 @@
-use crate::{request::Request, resp::RESP, server::Server, server_result::ServerValue};
+use crate::{
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{ServerError, ServerValue},
+};
 
-pub async fn command(_server: &Server, request: &Request, _command: &Vec<String>) {
-    request
-        .data(ServerValue::RESP(RESP::SimpleString("PONG".to_string())))
-        .await;
+pub async fn command(_server: &Server, request: &Request, command: &[Vec<u8>]) {
+    match command.len() {
+        1 => {
+            request
+                .data(ServerValue::RESP(RESP::SimpleString("PONG".to_string())))
+                .await
+        }
+        2 => {
+            request
+                .data(ServerValue::RESP(RESP::BulkString(command[1].clone())))
+                .await
+        }
+        _ => {
+            request
+                .error(ServerError::WrongNumberOfArguments(String::from("ping")))
+                .await
+        }
+    }
 }
 
 #[cfg(test)]
@@ -13,12 +32,13 @@ mod tests {
     use tokio::sync::mpsc;
     #[tokio::test]
     async fn test_command_ping() {
-        let cmd = vec![String::from("ping")];
+        let cmd = vec![b"ping".to_vec()];
         let server = Server::new();
         let (connection_sender, mut connection_receiver) = mpsc::channel::<ServerMessage>(32);
         let request = Request {
             value: RESP::Null,
             sender: connection_sender,
+            client_id: 0,
         };
         command(&server, &request, &cmd).await;
         assert_eq!(
@@ -28,12 +48,13 @@ mod tests {
     }
     #[tokio::test]
     async fn test_command_ping_uppercase() {
-        let cmd = vec![String::from("PING")];
+        let cmd = vec![b"PING".to_vec()];
         let server = Server::new();
         let (connection_sender, mut connection_receiver) = mpsc::channel::<ServerMessage>(32);
         let request = Request {
             value: RESP::Null,
             sender: connection_sender,
+            client_id: 0,
         };
         command(&server, &request, &cmd).await;
         assert_eq!(
@@ -41,4 +62,21 @@ mod tests {
             ServerMessage::Data(ServerValue::RESP(RESP::SimpleString(String::from("PONG"))))
         )
     }
+
+    #[tokio::test]
+    async fn test_command_ping_message() {
+        let cmd = vec![b"ping".to_vec(), b"hello".to_vec()];
+        let server = Server::new();
+        let (connection_sender, mut connection_receiver) = mpsc::channel::<ServerMessage>(32);
+        let request = Request {
+            value: RESP::Null,
+            sender: connection_sender,
+            client_id: 0,
+        };
+        command(&server, &request, &cmd).await;
+        assert_eq!(
+            connection_receiver.try_recv().unwrap(),
+            ServerMessage::Data(ServerValue::RESP(RESP::BulkString(b"hello".to_vec())))
+        )
+    }
 }
@@ -0,0 +1,86 @@
+use crate::{
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{ServerError, ServerValue},
+};
+
+// RENAME and RENAMENX
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    let name = String::from_utf8_lossy(&command[0]).to_lowercase();
+    if command.len() != 3 {
+        request
+            .error(ServerError::WrongNumberOfArguments(name))
+            .await;
+        return;
+    }
+
+    let nx = name == "renamenx";
+    match storage.rename(&command[1], &command[2], nx) {
+        Ok(renamed) if nx => {
+            request
+                .data(ServerValue::RESP(RESP::Integer(renamed as i64)))
+                .await
+        }
+        Ok(_) => {
+            request
+                .data(ServerValue::RESP(RESP::SimpleString(String::from("OK"))))
+                .await
+        }
+        Err(e) => request.error(ServerError::from(e)).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::{add_strings, run, server_with};
+    use crate::server_result::ServerMessage;
+
+    fn server_with_keys() -> Server {
+        server_with(|storage| add_strings(storage, &[("a", "a"), ("b", "b")]))
+    }
+
+    #[tokio::test]
+    async fn test_rename() {
+        let mut server = server_with_keys();
+        assert_eq!(
+            run(&mut server, "RENAME a b").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::SimpleString(String::from("OK"))))
+        );
+        let storage = server.storage.as_mut().unwrap();
+        assert!(!storage.exists(b"a"));
+        assert_eq!(storage.get(b"b").unwrap(), Some(b"a".to_vec()));
+        assert_eq!(
+            run(&mut server, "RENAME a c").await,
+            ServerMessage::Error(ServerError::NoSuchKey)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_renamenx() {
+        let mut server = server_with_keys();
+        assert_eq!(
+            run(&mut server, "RENAMENX a b").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(0)))
+        );
+        assert_eq!(
+            run(&mut server, "RENAMENX a c").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(1)))
+        );
+        assert_eq!(
+            run(&mut server, "RENAMENX a").await,
+            ServerMessage::Error(ServerError::WrongNumberOfArguments(String::from(
+                "renamenx"
+            )))
+        );
+    }
+}
@@ -0,0 +1,59 @@
+use crate::{
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{ServerError, ServerValue},
+};
+
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    if command.len() < 3 {
+        request
+            .error(ServerError::WrongNumberOfArguments(String::from("sadd")))
+            .await;
+        return;
+    }
+
+    match storage.set_add(&command[1], &command[2..]) {
+        Ok(added) => {
+            request
+                .data(ServerValue::RESP(RESP::Integer(added as i64)))
+                .await
+        }
+        Err(e) => request.error(ServerError::from(e)).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::run;
+    use crate::server_result::ServerMessage;
+    use crate::storage::Storage;
+
+    #[tokio::test]
+    async fn test_sadd() {
+        let mut server = Server::with_new(Storage::new());
+        assert_eq!(
+            run(&mut server, "SADD set a b a").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(2)))
+        );
+        assert_eq!(
+            run(&mut server, "SADD set b c").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(1)))
+        );
+        let storage = server.storage.as_mut().unwrap();
+        assert_eq!(storage.set_len(b"set").unwrap(), 3);
+        assert_eq!(
+            run(&mut server, "SADD set").await,
+            ServerMessage::Error(ServerError::WrongNumberOfArguments(String::from("sadd")))
+        );
+    }
+}
@@ -0,0 +1,238 @@
+use crate::{
+    glob::string_match,
+    number::parse_i64,
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{command_to_string, ServerError, ServerValue},
+};
+
+// Types that TYPE can report, accepted by the TYPE option
+const TYPE_NAMES: [&str; 6] = ["string", "list", "set", "zset", "hash", "stream"];
+
+#[derive(Debug, PartialEq)]
+struct ScanArgs {
+    pattern: Option<Vec<u8>>,
+    count: usize,
+    key_type: Option<String>,
+}
+
+fn parse_scan_arguments(arguments: &[Vec<u8>]) -> Result<ScanArgs, ServerError> {
+    let mut args = ScanArgs {
+        pattern: None,
+        count: 10,
+        key_type: None,
+    };
+    let mut idx: usize = 0;
+    while idx < arguments.len() {
+        if idx + 1 == arguments.len() {
+            return Err(ServerError::CommandSyntaxError(command_to_string(
+                arguments,
+            )));
+        }
+        let value = &arguments[idx + 1];
+        match arguments[idx].to_ascii_lowercase().as_slice() {
+            b"match" => args.pattern = Some(value.clone()),
+            b"count" => {
+                args.count = match parse_i64(value) {
+                    Some(count) if count >= 1 => count as usize,
+                    Some(_) => {
+                        return Err(ServerError::CommandSyntaxError(command_to_string(
+                            arguments,
+                        )))
+                    }
+                    None => return Err(ServerError::NotAnInteger),
+                }
+            }
+            b"type" => {
+                let name = String::from_utf8_lossy(value).to_lowercase();
+                if !TYPE_NAMES.contains(&name.as_str()) {
+                    return Err(ServerError::UnknownTypeName(
+                        String::from_utf8_lossy(value).into_owned(),
+                    ));
+                }
+                args.key_type = Some(name);
+            }
+            _ => {
+                return Err(ServerError::CommandSyntaxError(command_to_string(
+                    arguments,
+                )))
+            }
+        }
+        idx += 2;
+    }
+    Ok(args)
+}
+
+// SCAN cursor [MATCH pattern] [COUNT count] [TYPE type]
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    if command.len() < 2 {
+        request
+            .error(ServerError::WrongNumberOfArguments(String::from("scan")))
+            .await;
+        return;
+    }
+    let cursor = match std::str::from_utf8(&command[1])
+        .ok()
+        .and_then(|c| c.parse::<u64>().ok())
+    {
+        Some(cursor) => cursor,
+        None => {
+            request.error(ServerError::InvalidCursor).await;
+            return;
+        }
+    };
+    let args = match parse_scan_arguments(&command[2..]) {
+        Ok(args) => args,
+        Err(ServerError::CommandSyntaxError(_)) => {
+            request
+                .error(ServerError::CommandSyntaxError(command_to_string(command)))
+                .await;
+            return;
+        }
+        Err(e) => {
+            request.error(e).await;
+            return;
+        }
+    };
+
+    let (cursor, mut keys) = storage.scan(cursor, args.count);
+    if let Some(pattern) = &args.pattern {
+        keys.retain(|key| string_match(pattern, key, false));
+    }
+    if let Some(key_type) = &args.key_type {
+        keys.retain(|key| storage.key_type(key) == Some(key_type.as_str()));
+    }
+    request
+        .data(ServerValue::RESP(RESP::Array(vec![
+            RESP::BulkString(cursor.to_string().into_bytes()),
+            RESP::Array(keys.into_iter().map(RESP::BulkString).collect()),
+        ])))
+        .await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::run;
+    use crate::server_result::ServerMessage;
+    use crate::set::SetArgs;
+    use crate::storage::Storage;
+
+    // Run a full scan, returning the keys found, sorted
+    async fn scan_all(server: &mut Server, options: &str) -> Vec<Vec<u8>> {
+        let mut cursor = b"0".to_vec();
+        let mut found = Vec::new();
+        loop {
+            let cmd = format!("SCAN {}{}", String::from_utf8_lossy(&cursor), options);
+            match run(server, &cmd).await {
+                ServerMessage::Data(ServerValue::RESP(RESP::Array(reply))) => match &reply[..] {
+                    [RESP::BulkString(next), RESP::Array(keys)] => {
+                        for key in keys {
+                            if let RESP::BulkString(key) = key {
+                                found.push(key.clone());
+                            }
+                        }
+                        cursor = next.clone();
+                    }
+                    other => panic!("unexpected reply {:?}", other),
+                },
+                other => panic!("unexpected reply {:?}", other),
+            }
+            if cursor == b"0" {
+                break;
+            }
+        }
+        found.sort();
+        found.dedup();
+        found
+    }
+
+    fn server_with_keys() -> Server {
+        let mut storage = Storage::new();
+        for i in 0..50 {
+            storage
+                .set(
+                    format!("user:{}", i).into_bytes(),
+                    b"v".to_vec(),
+                    SetArgs::new(),
+                )
+                .unwrap();
+            storage
+                .set(
+                    format!("session:{}", i).into_bytes(),
+                    b"v".to_vec(),
+                    SetArgs::new(),
+                )
+                .unwrap();
+        }
+        Server::with_new(storage)
+    }
+
+    #[test]
+    fn test_parse_scan_arguments() {
+        let parse = |arguments: &str| {
+            let arguments: Vec<Vec<u8>> = arguments
+                .split(' ')
+                .map(|a| a.as_bytes().to_vec())
+                .collect();
+            parse_scan_arguments(&arguments)
+        };
+        assert_eq!(
+            parse("MATCH user:* COUNT 100 TYPE String").unwrap(),
+            ScanArgs {
+                pattern: Some(b"user:*".to_vec()),
+                count: 100,
+                key_type: Some(String::from("string")),
+            }
+        );
+        assert_eq!(parse("COUNT ten").unwrap_err(), ServerError::NotAnInteger);
+        assert_eq!(
+            parse("COUNT 0").unwrap_err(),
+            ServerError::CommandSyntaxError(String::from("COUNT 0"))
+        );
+        assert_eq!(
+            parse("TYPE foo").unwrap_err(),
+            ServerError::UnknownTypeName(String::from("foo"))
+        );
+        assert_eq!(
+            parse("MATCH").unwrap_err(),
+            ServerError::CommandSyntaxError(String::from("MATCH"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_command() {
+        let mut server = server_with_keys();
+        assert_eq!(scan_all(&mut server, "").await.len(), 100);
+        assert_eq!(scan_all(&mut server, " COUNT 3").await.len(), 100);
+        let users = scan_all(&mut server, " MATCH user:* COUNT 7").await;
+        assert_eq!(users.len(), 50);
+        assert!(users.iter().all(|key| key.starts_with(b"user:")));
+        assert_eq!(scan_all(&mut server, " TYPE string").await.len(), 100);
+        assert_eq!(scan_all(&mut server, " TYPE list").await.len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_errors() {
+        let mut server = server_with_keys();
+        assert_eq!(
+            run(&mut server, "SCAN abc").await,
+            ServerMessage::Error(ServerError::InvalidCursor)
+        );
+        assert_eq!(
+            run(&mut server, "SCAN 0 FOO bar").await,
+            ServerMessage::Error(ServerError::CommandSyntaxError(String::from(
+                "SCAN 0 FOO bar"
+            )))
+        );
+    }
+}
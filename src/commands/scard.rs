@@ -0,0 +1,65 @@
+use crate::{
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{ServerError, ServerValue},
+};
+
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    if command.len() != 2 {
+        request
+            .error(ServerError::WrongNumberOfArguments(String::from("scard")))
+            .await;
+        return;
+    }
+
+    match storage.set_len(&command[1]) {
+        Ok(len) => {
+            request
+                .data(ServerValue::RESP(RESP::Integer(len as i64)))
+                .await
+        }
+        Err(e) => request.error(ServerError::from(e)).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::run;
+    use crate::server_result::ServerMessage;
+    use crate::set::SetArgs;
+    use crate::storage::Storage;
+
+    #[tokio::test]
+    async fn test_scard() {
+        let mut storage = Storage::new();
+        storage
+            .set_add(b"set", &[b"a".to_vec(), b"b".to_vec()])
+            .unwrap();
+        storage
+            .set(b"string".to_vec(), b"value".to_vec(), SetArgs::new())
+            .unwrap();
+        let mut server = Server::with_new(storage);
+        assert_eq!(
+            run(&mut server, "SCARD set").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(2)))
+        );
+        assert_eq!(
+            run(&mut server, "SCARD missing").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(0)))
+        );
+        assert_eq!(
+            run(&mut server, "SCARD string").await,
+            ServerMessage::Error(ServerError::WrongType)
+        );
+    }
+}
@@ -2,11 +2,12 @@ use crate::{
     request::Request,
     resp::RESP,
     server::Server,
-    server_result::{ServerError, ServerValue},
+    server_result::{command_to_string, ServerError, ServerValue},
     set::parse_set_arguments,
+    storage_result::StorageError,
 };
 
-pub async fn command(server: &mut Server, request: &Request, command: &Vec<String>) {
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
     let storage = match server.storage.as_mut() {
         Some(storage) => storage,
         None => {
@@ -17,29 +18,177 @@ pub async fn command(server: &mut Server, request: &Request, command: &Vec<Strin
 
     if command.len() < 3 {
         request
-            .error(ServerError::CommandSyntaxError(command.join("")))
+            .error(ServerError::WrongNumberOfArguments(String::from("set")))
             .await;
         return;
     }
     let key = command[1].clone();
     let value = command[2].clone();
-    let args = match parse_set_arguments(&command[3..].to_vec()) {
+    let args = match parse_set_arguments(&command[3..]) {
         Ok(args) => args,
-        Err(_) => {
+        Err(StorageError::CommandSyntaxError(_)) => {
             request
-                .error(ServerError::CommandSyntaxError(command.join(" ")))
+                .error(ServerError::CommandSyntaxError(command_to_string(command)))
                 .await;
             return;
         }
+        Err(e) => {
+            request.error(ServerError::from(e)).await;
+            return;
+        }
     };
 
-    if let Err(_) = storage.set(key, value, args) {
-        request
-            .error(ServerError::CommandInternalError(command.join(" ")))
-            .await;
-        return;
+    let get = args.get;
+    let outcome = match storage.set(key, value, args) {
+        Ok(outcome) => outcome,
+        Err(e) => {
+            request.error(ServerError::from(e)).await;
+            return;
+        }
+    };
+
+    // With GET the reply is the old value, whether the write happened or not.
+    // Otherwise a write blocked by NX or XX replies nil.
+    let reply = match (get, outcome.written, outcome.previous) {
+        (true, _, Some(previous)) => RESP::BulkString(previous),
+        (true, _, None) | (false, false, _) => RESP::Null,
+        (false, true, _) => RESP::SimpleString("OK".to_string()),
+    };
+    request.data(ServerValue::RESP(reply)).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::run;
+    use crate::server_result::ServerMessage;
+    use crate::storage::Storage;
+
+    fn ok() -> ServerMessage {
+        ServerMessage::Data(ServerValue::RESP(RESP::SimpleString(String::from("OK"))))
+    }
+
+    fn null() -> ServerMessage {
+        ServerMessage::Data(ServerValue::RESP(RESP::Null))
+    }
+
+    fn bulk(value: &str) -> ServerMessage {
+        ServerMessage::Data(ServerValue::RESP(RESP::BulkString(
+            value.as_bytes().to_vec(),
+        )))
+    }
+
+    fn get(server: &mut Server, key: &str) -> Option<Vec<u8>> {
+        server
+            .storage
+            .as_mut()
+            .unwrap()
+            .get(key.as_bytes())
+            .unwrap()
+    }
+
+    // Runs the command on a missing key and on a key holding "old",
+    // checking the reply and the value stored afterwards
+    async fn check(
+        options: &str,
+        missing: (ServerMessage, Option<&str>),
+        present: (ServerMessage, Option<&str>),
+    ) {
+        let mut server = Server::with_new(Storage::new());
+        let cmd = format!("set key new {}", options);
+        assert_eq!(run(&mut server, cmd.trim()).await, missing.0, "{}", cmd);
+        assert_eq!(
+            get(&mut server, "key"),
+            missing.1.map(|v| v.as_bytes().to_vec()),
+            "{}",
+            cmd
+        );
+
+        let mut server = Server::with_new(Storage::new());
+        assert_eq!(run(&mut server, "set key old").await, ok());
+        assert_eq!(run(&mut server, cmd.trim()).await, present.0, "{}", cmd);
+        assert_eq!(
+            get(&mut server, "key"),
+            present.1.map(|v| v.as_bytes().to_vec()),
+            "{}",
+            cmd
+        );
     }
-    request
-        .data(ServerValue::RESP(RESP::SimpleString("OK".to_string())))
+
+    #[tokio::test]
+    async fn test_command_plain() {
+        check("", (ok(), Some("new")), (ok(), Some("new"))).await;
+    }
+
+    #[tokio::test]
+    async fn test_command_nx() {
+        check("NX", (ok(), Some("new")), (null(), Some("old"))).await;
+    }
+
+    #[tokio::test]
+    async fn test_command_xx() {
+        check("XX", (null(), None), (ok(), Some("new"))).await;
+    }
+
+    #[tokio::test]
+    async fn test_command_get() {
+        check("GET", (null(), Some("new")), (bulk("old"), Some("new"))).await;
+    }
+
+    #[tokio::test]
+    async fn test_command_nx_get() {
+        check("NX GET", (null(), Some("new")), (bulk("old"), Some("old"))).await;
+    }
+
+    #[tokio::test]
+    async fn test_command_xx_get() {
+        check("XX GET", (null(), None), (bulk("old"), Some("new"))).await;
+    }
+
+    #[tokio::test]
+    async fn test_command_get_with_expiry() {
+        check(
+            "GET EX 100",
+            (null(), Some("new")),
+            (bulk("old"), Some("new")),
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_command_expiry_overflow() {
+        let error = || ServerMessage::Error(ServerError::InvalidExpireTime(String::from("set")));
+        check(
+            "PX 9223372036854775807",
+            (error(), None),
+            (error(), Some("old")),
+        )
+        .await;
+        check(
+            "NX EX 9223372036854775",
+            (error(), None),
+            (error(), Some("old")),
+        )
         .await;
+    }
+
+    #[tokio::test]
+    async fn test_command_nx_xx() {
+        let mut server = Server::with_new(Storage::new());
+        assert_eq!(
+            run(&mut server, "set key new NX XX").await,
+            ServerMessage::Error(ServerError::CommandSyntaxError(String::from(
+                "set key new NX XX"
+            )))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_command_wrong_number_of_arguments() {
+        let mut server = Server::with_new(Storage::new());
+        assert_eq!(
+            run(&mut server, "set key").await,
+            ServerMessage::Error(ServerError::WrongNumberOfArguments(String::from("set")))
+        );
+    }
 }
@@ -0,0 +1,91 @@
+use crate::{
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{ServerError, ServerValue},
+    set::{parse_expiry, SetArgs},
+};
+
+// SETEX and PSETEX
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    let name = String::from_utf8_lossy(&command[0]).to_lowercase();
+    if command.len() != 4 {
+        request
+            .error(ServerError::WrongNumberOfArguments(name))
+            .await;
+        return;
+    }
+    let option: &[u8] = if name == "setex" { b"ex" } else { b"px" };
+    let mut args = SetArgs::new();
+    args.expiry = match parse_expiry(option, &command[2], &name) {
+        Ok(expiry) => Some(expiry),
+        Err(e) => {
+            request.error(ServerError::from(e)).await;
+            return;
+        }
+    };
+
+    match storage.set(command[1].clone(), command[3].clone(), args) {
+        Ok(_) => {
+            request
+                .data(ServerValue::RESP(RESP::SimpleString(String::from("OK"))))
+                .await
+        }
+        Err(e) => request.error(ServerError::from(e)).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use crate::commands::test_utils::run;
+    use crate::expire::KeyTTL;
+    use crate::server_result::ServerMessage;
+    use crate::storage::Storage;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn ok() -> ServerMessage {
+        ServerMessage::Data(ServerValue::RESP(RESP::SimpleString(String::from("OK"))))
+    }
+
+    #[tokio::test]
+    async fn test_command() {
+        let clock = ManualClock::new(UNIX_EPOCH + Duration::from_secs(1700000000));
+        let mut server = Server::with_new(Storage::with_clock(clock));
+        assert_eq!(run(&mut server, "SETEX key 10 value").await, ok());
+        assert_eq!(run(&mut server, "PSETEX pkey 1500 value").await, ok());
+        let storage = server.storage.as_mut().unwrap();
+        assert_eq!(storage.get(b"key").unwrap(), Some(b"value".to_vec()));
+        assert_eq!(storage.ttl(b"key").unwrap(), KeyTTL::Expires(1700000010000));
+        assert_eq!(
+            storage.ttl(b"pkey").unwrap(),
+            KeyTTL::Expires(1700000001500)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_errors() {
+        let mut server = Server::with_new(Storage::new());
+        assert_eq!(
+            run(&mut server, "SETEX key 0 value").await,
+            ServerMessage::Error(ServerError::InvalidExpireTime(String::from("setex")))
+        );
+        assert_eq!(
+            run(&mut server, "PSETEX key ten value").await,
+            ServerMessage::Error(ServerError::NotAnInteger)
+        );
+        assert_eq!(
+            run(&mut server, "SETEX key 10").await,
+            ServerMessage::Error(ServerError::WrongNumberOfArguments(String::from("setex")))
+        );
+    }
+}
@@ -0,0 +1,105 @@
+use crate::{
+    number::parse_i64,
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{ServerError, ServerValue},
+};
+
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    if command.len() != 4 {
+        request
+            .error(ServerError::WrongNumberOfArguments(String::from(
+                "setrange",
+            )))
+            .await;
+        return;
+    }
+    let offset = match parse_i64(&command[2]) {
+        Some(offset) if offset < 0 => {
+            request.error(ServerError::OffsetOutOfRange).await;
+            return;
+        }
+        Some(offset) => offset as usize,
+        None => {
+            request.error(ServerError::NotAnInteger).await;
+            return;
+        }
+    };
+
+    match storage.set_range(&command[1], offset, &command[3]) {
+        Ok(length) => {
+            request
+                .data(ServerValue::RESP(RESP::Integer(length as i64)))
+                .await
+        }
+        Err(e) => request.error(ServerError::from(e)).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::run_args;
+    use crate::server_result::ServerMessage;
+    use crate::set::SetArgs;
+    use crate::storage::Storage;
+
+    fn integer(value: i64) -> ServerMessage {
+        ServerMessage::Data(ServerValue::RESP(RESP::Integer(value)))
+    }
+
+    #[tokio::test]
+    async fn test_command() {
+        let mut storage = Storage::new();
+        storage
+            .set(b"key".to_vec(), b"Hello World".to_vec(), SetArgs::new())
+            .unwrap();
+        let mut server = Server::with_new(storage);
+        assert_eq!(
+            run_args(&mut server, &["setrange", "key", "6", "Redis"]).await,
+            integer(11)
+        );
+        assert_eq!(
+            run_args(&mut server, &["setrange", "padded", "5", "Redis"]).await,
+            integer(10)
+        );
+        let storage = server.storage.as_mut().unwrap();
+        assert_eq!(storage.get(b"key").unwrap(), Some(b"Hello Redis".to_vec()));
+        assert_eq!(
+            storage.get(b"padded").unwrap(),
+            Some(b"\0\0\0\0\0Redis".to_vec())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_errors() {
+        let mut server = Server::with_new(Storage::new());
+        assert_eq!(
+            run_args(&mut server, &["setrange", "key", "-1", "a"]).await,
+            ServerMessage::Error(ServerError::OffsetOutOfRange)
+        );
+        assert_eq!(
+            run_args(&mut server, &["setrange", "key", "one", "a"]).await,
+            ServerMessage::Error(ServerError::NotAnInteger)
+        );
+        assert_eq!(
+            run_args(&mut server, &["setrange", "key", "536870912", "a"]).await,
+            ServerMessage::Error(ServerError::StringTooLong)
+        );
+        assert_eq!(
+            run_args(&mut server, &["setrange", "key", "1"]).await,
+            ServerMessage::Error(ServerError::WrongNumberOfArguments(String::from(
+                "setrange"
+            )))
+        );
+    }
+}
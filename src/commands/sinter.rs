@@ -0,0 +1,112 @@
+use crate::{
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{ServerError, ServerValue},
+    set_type::SetOperation,
+};
+
+// SINTER, SUNION and SDIFF with their STORE variants,
+// which take the destination before the keys
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    let name = String::from_utf8_lossy(&command[0]).to_lowercase();
+    let store = name.ends_with("store");
+    let minimum = if store { 3 } else { 2 };
+    if command.len() < minimum {
+        request
+            .error(ServerError::WrongNumberOfArguments(name))
+            .await;
+        return;
+    }
+    let operation = if name.starts_with("sinter") {
+        SetOperation::Intersection
+    } else if name.starts_with("sunion") {
+        SetOperation::Union
+    } else {
+        SetOperation::Difference
+    };
+
+    let reply = if store {
+        storage
+            .set_combine_store(&command[1], &command[2..], operation)
+            .map(|len| RESP::Integer(len as i64))
+    } else {
+        storage
+            .set_combine(&command[1..], operation)
+            .map(|members| RESP::Set(members.into_iter().map(RESP::BulkString).collect()))
+    };
+    match reply {
+        Ok(reply) => request.data(ServerValue::RESP(reply)).await,
+        Err(e) => request.error(ServerError::from(e)).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::{add_set, run, server_with};
+    use crate::server_result::ServerMessage;
+
+    fn members(members: &[&str]) -> ServerMessage {
+        ServerMessage::Data(ServerValue::RESP(RESP::Set(
+            members
+                .iter()
+                .map(|member| RESP::BulkString(member.as_bytes().to_vec()))
+                .collect(),
+        )))
+    }
+
+    fn server_with_sets() -> Server {
+        server_with(|storage| {
+            add_set(storage, "a", &["1", "2", "3"]);
+            add_set(storage, "b", &["2", "3", "4"]);
+        })
+    }
+
+    #[tokio::test]
+    async fn test_operations() {
+        let mut server = server_with_sets();
+        assert_eq!(run(&mut server, "SINTER a b").await, members(&["2", "3"]));
+        assert_eq!(
+            run(&mut server, "SUNION a b missing").await,
+            members(&["1", "2", "3", "4"])
+        );
+        assert_eq!(run(&mut server, "SDIFF a b").await, members(&["1"]));
+        assert_eq!(run(&mut server, "SINTER a missing").await, members(&[]));
+    }
+
+    #[tokio::test]
+    async fn test_store() {
+        let mut server = server_with_sets();
+        assert_eq!(
+            run(&mut server, "SUNIONSTORE dest a b").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(4)))
+        );
+        assert_eq!(
+            run(&mut server, "SDIFFSTORE dest b a").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(1)))
+        );
+        let storage = server.storage.as_mut().unwrap();
+        assert_eq!(storage.set_members(b"dest").unwrap(), vec![b"4".to_vec()]);
+        assert_eq!(
+            run(&mut server, "SINTERSTORE dest a missing").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(0)))
+        );
+        let storage = server.storage.as_mut().unwrap();
+        assert!(!storage.exists(b"dest"));
+        assert_eq!(
+            run(&mut server, "SINTERSTORE dest").await,
+            ServerMessage::Error(ServerError::WrongNumberOfArguments(String::from(
+                "sinterstore"
+            )))
+        );
+    }
+}
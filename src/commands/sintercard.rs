@@ -0,0 +1,132 @@
+use crate::{
+    number::parse_i64,
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{command_to_string, ServerError, ServerValue},
+};
+
+// SINTERCARD numkeys key [key ...] [LIMIT limit]
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    if command.len() < 3 {
+        request
+            .error(ServerError::WrongNumberOfArguments(String::from(
+                "sintercard",
+            )))
+            .await;
+        return;
+    }
+
+    let numkeys = match parse_i64(&command[1]).filter(|&numkeys| numkeys > 0) {
+        Some(numkeys) => numkeys as usize,
+        None => {
+            request
+                .error(ServerError::MustBePositive(String::from("numkeys")))
+                .await;
+            return;
+        }
+    };
+    if numkeys > command.len() - 2 {
+        request.error(ServerError::NumKeysTooLarge).await;
+        return;
+    }
+    let limit = match &command[numkeys + 2..] {
+        [] => 0,
+        [option, limit] if option.eq_ignore_ascii_case(b"limit") => match parse_i64(limit) {
+            Some(limit) if limit >= 0 => limit as usize,
+            Some(_) => {
+                request
+                    .error(ServerError::NegativeArgument(String::from("LIMIT")))
+                    .await;
+                return;
+            }
+            None => {
+                request.error(ServerError::NotAnInteger).await;
+                return;
+            }
+        },
+        _ => {
+            request
+                .error(ServerError::CommandSyntaxError(command_to_string(command)))
+                .await;
+            return;
+        }
+    };
+
+    match storage.set_intersection_len(&command[2..numkeys + 2], limit) {
+        Ok(len) => {
+            request
+                .data(ServerValue::RESP(RESP::Integer(len as i64)))
+                .await
+        }
+        Err(e) => request.error(ServerError::from(e)).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::run;
+    use crate::server_result::ServerMessage;
+    use crate::storage::Storage;
+
+    fn integer(value: i64) -> ServerMessage {
+        ServerMessage::Data(ServerValue::RESP(RESP::Integer(value)))
+    }
+
+    #[tokio::test]
+    async fn test_sintercard() {
+        let mut storage = Storage::new();
+        for (key, values) in [("a", ["1", "2", "3"]), ("b", ["2", "3", "4"])] {
+            let values: Vec<Vec<u8>> = values.iter().map(|v| v.as_bytes().to_vec()).collect();
+            storage.set_add(key.as_bytes(), &values).unwrap();
+        }
+        let mut server = Server::with_new(storage);
+        assert_eq!(run(&mut server, "SINTERCARD 2 a b").await, integer(2));
+        assert_eq!(
+            run(&mut server, "SINTERCARD 2 a b LIMIT 1").await,
+            integer(1)
+        );
+        assert_eq!(
+            run(&mut server, "SINTERCARD 2 a b limit 0").await,
+            integer(2)
+        );
+        assert_eq!(run(&mut server, "SINTERCARD 1 a").await, integer(3));
+        assert_eq!(run(&mut server, "SINTERCARD 2 a missing").await, integer(0));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_arguments() {
+        let mut server = Server::with_new(Storage::new());
+        for (cmd, error) in [
+            (
+                "SINTERCARD 0 a",
+                ServerError::MustBePositive(String::from("numkeys")),
+            ),
+            ("SINTERCARD 3 a b", ServerError::NumKeysTooLarge),
+            (
+                "SINTERCARD 1 a LIMIT -1",
+                ServerError::NegativeArgument(String::from("LIMIT")),
+            ),
+            (
+                "SINTERCARD 1 a b",
+                ServerError::CommandSyntaxError(String::from("SINTERCARD 1 a b")),
+            ),
+        ] {
+            assert_eq!(
+                run(&mut server, cmd).await,
+                ServerMessage::Error(error),
+                "{}",
+                cmd
+            );
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use crate::{
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{ServerError, ServerValue},
+};
+
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    if command.len() != 3 {
+        request
+            .error(ServerError::WrongNumberOfArguments(String::from(
+                "sismember",
+            )))
+            .await;
+        return;
+    }
+
+    match storage.set_is_member(&command[1], &command[2]) {
+        Ok(member) => {
+            request
+                .data(ServerValue::RESP(RESP::Integer(member as i64)))
+                .await
+        }
+        Err(e) => request.error(ServerError::from(e)).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::run;
+    use crate::server_result::ServerMessage;
+    use crate::storage::Storage;
+
+    #[tokio::test]
+    async fn test_sismember() {
+        let mut storage = Storage::new();
+        storage.set_add(b"set", &[b"12".to_vec()]).unwrap();
+        let mut server = Server::with_new(storage);
+        assert_eq!(
+            run(&mut server, "SISMEMBER set 12").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(1)))
+        );
+        assert_eq!(
+            run(&mut server, "SISMEMBER set 012").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(0)))
+        );
+        assert_eq!(
+            run(&mut server, "SISMEMBER missing 12").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(0)))
+        );
+    }
+}
@@ -0,0 +1,66 @@
+use crate::{
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{ServerError, ServerValue},
+};
+
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    if command.len() != 2 {
+        request
+            .error(ServerError::WrongNumberOfArguments(String::from(
+                "smembers",
+            )))
+            .await;
+        return;
+    }
+
+    match storage.set_members(&command[1]) {
+        Ok(members) => {
+            request
+                .data(ServerValue::RESP(RESP::Set(
+                    members.into_iter().map(RESP::BulkString).collect(),
+                )))
+                .await
+        }
+        Err(e) => request.error(ServerError::from(e)).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::run;
+    use crate::server_result::ServerMessage;
+    use crate::storage::Storage;
+
+    #[tokio::test]
+    async fn test_smembers() {
+        let mut storage = Storage::new();
+        storage
+            .set_add(b"set", &[b"3".to_vec(), b"1".to_vec(), b"2".to_vec()])
+            .unwrap();
+        let mut server = Server::with_new(storage);
+        // integer sets are kept in order
+        assert_eq!(
+            run(&mut server, "SMEMBERS set").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Set(vec![
+                RESP::BulkString(b"1".to_vec()),
+                RESP::BulkString(b"2".to_vec()),
+                RESP::BulkString(b"3".to_vec()),
+            ])))
+        );
+        assert_eq!(
+            run(&mut server, "SMEMBERS missing").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Set(vec![])))
+        );
+    }
+}
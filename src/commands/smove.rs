@@ -0,0 +1,67 @@
+use crate::{
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{ServerError, ServerValue},
+};
+
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    if command.len() != 4 {
+        request
+            .error(ServerError::WrongNumberOfArguments(String::from("smove")))
+            .await;
+        return;
+    }
+
+    match storage.set_move(&command[1], &command[2], &command[3]) {
+        Ok(moved) => {
+            request
+                .data(ServerValue::RESP(RESP::Integer(moved as i64)))
+                .await
+        }
+        Err(e) => request.error(ServerError::from(e)).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::run;
+    use crate::server_result::ServerMessage;
+    use crate::storage::Storage;
+
+    #[tokio::test]
+    async fn test_smove() {
+        let mut storage = Storage::new();
+        storage
+            .set_add(b"source", &[b"a".to_vec(), b"b".to_vec()])
+            .unwrap();
+        let mut server = Server::with_new(storage);
+        assert_eq!(
+            run(&mut server, "SMOVE source destination a").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(1)))
+        );
+        assert_eq!(
+            run(&mut server, "SMOVE source destination a").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(0)))
+        );
+        assert_eq!(
+            run(&mut server, "SMOVE source source b").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(1)))
+        );
+        let storage = server.storage.as_mut().unwrap();
+        assert_eq!(
+            storage.set_members(b"destination").unwrap(),
+            vec![b"a".to_vec()]
+        );
+        assert_eq!(storage.set_members(b"source").unwrap(), vec![b"b".to_vec()]);
+    }
+}
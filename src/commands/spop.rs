@@ -0,0 +1,88 @@
+use crate::{
+    number::parse_i64,
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{ServerError, ServerValue},
+};
+
+// SPOP key [count], a single member being replied without a count
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    if command.len() != 2 && command.len() != 3 {
+        request
+            .error(ServerError::WrongNumberOfArguments(String::from("spop")))
+            .await;
+        return;
+    }
+
+    let count = match command.get(2).map(|count| parse_i64(count)) {
+        None => None,
+        Some(Some(count)) if count >= 0 => Some(count as usize),
+        Some(_) => {
+            request.error(ServerError::ValueMustBePositive).await;
+            return;
+        }
+    };
+
+    let mut members = match storage.set_pop(&command[1], count.unwrap_or(1)) {
+        Ok(members) => members,
+        Err(e) => {
+            request.error(ServerError::from(e)).await;
+            return;
+        }
+    };
+    let reply = match count {
+        None => members.pop().map_or(RESP::Null, RESP::BulkString),
+        Some(_) => RESP::Set(members.into_iter().map(RESP::BulkString).collect()),
+    };
+    request.data(ServerValue::RESP(reply)).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::run;
+    use crate::server_result::ServerMessage;
+    use crate::storage::Storage;
+
+    #[tokio::test]
+    async fn test_spop() {
+        let mut storage = Storage::new();
+        storage
+            .set_add(b"set", &[b"a".to_vec(), b"b".to_vec(), b"c".to_vec()])
+            .unwrap();
+        let mut server = Server::with_new(storage);
+        let ServerMessage::Data(ServerValue::RESP(RESP::Set(members))) =
+            run(&mut server, "SPOP set 2").await
+        else {
+            panic!("expected a set");
+        };
+        assert_eq!(members.len(), 2);
+        assert!(matches!(
+            run(&mut server, "SPOP set").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::BulkString(_)))
+        ));
+        let storage = server.storage.as_mut().unwrap();
+        assert!(!storage.exists(b"set"));
+        assert_eq!(
+            run(&mut server, "SPOP set").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Null))
+        );
+        assert_eq!(
+            run(&mut server, "SPOP set 3").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Set(vec![])))
+        );
+        assert_eq!(
+            run(&mut server, "SPOP set -1").await,
+            ServerMessage::Error(ServerError::ValueMustBePositive)
+        );
+    }
+}
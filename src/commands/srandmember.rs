@@ -0,0 +1,110 @@
+use crate::{
+    number::parse_i64,
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{ServerError, ServerValue},
+};
+
+// SRANDMEMBER key [count], a negative count allowing
+// the same member to be returned several times
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    if command.len() != 2 && command.len() != 3 {
+        request
+            .error(ServerError::WrongNumberOfArguments(String::from(
+                "srandmember",
+            )))
+            .await;
+        return;
+    }
+
+    // without a count, a single member is replied
+    if command.len() == 2 {
+        match storage.set_random_members(&command[1], 1, true) {
+            Ok(mut members) => {
+                let reply = members.pop().map_or(RESP::Null, RESP::BulkString);
+                request.data(ServerValue::RESP(reply)).await
+            }
+            Err(e) => request.error(ServerError::from(e)).await,
+        }
+        return;
+    }
+
+    let count = match parse_i64(&command[2]) {
+        Some(count) if count != i64::MIN => count,
+        Some(_) => {
+            request.error(ServerError::ValueOutOfRange).await;
+            return;
+        }
+        None => {
+            request.error(ServerError::NotAnInteger).await;
+            return;
+        }
+    };
+    match storage.set_random_members(&command[1], count.unsigned_abs() as usize, count >= 0) {
+        Ok(members) => {
+            request
+                .data(ServerValue::RESP(RESP::Array(
+                    members.into_iter().map(RESP::BulkString).collect(),
+                )))
+                .await
+        }
+        Err(e) => request.error(ServerError::from(e)).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::run;
+    use crate::server_result::ServerMessage;
+    use crate::storage::Storage;
+
+    fn member_count(message: ServerMessage) -> usize {
+        match message {
+            ServerMessage::Data(ServerValue::RESP(RESP::Array(members))) => members.len(),
+            message => panic!("unexpected reply {:?}", message),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_srandmember() {
+        let mut storage = Storage::new();
+        storage
+            .set_add(b"set", &[b"a".to_vec(), b"b".to_vec()])
+            .unwrap();
+        let mut server = Server::with_new(storage);
+        assert!(matches!(
+            run(&mut server, "SRANDMEMBER set").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::BulkString(_)))
+        ));
+        assert_eq!(member_count(run(&mut server, "SRANDMEMBER set 5").await), 2);
+        assert_eq!(
+            member_count(run(&mut server, "SRANDMEMBER set -5").await),
+            5
+        );
+        assert_eq!(
+            member_count(run(&mut server, "SRANDMEMBER missing 5").await),
+            0
+        );
+        assert_eq!(
+            run(&mut server, "SRANDMEMBER missing").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Null))
+        );
+        // the members are left in the set
+        let storage = server.storage.as_mut().unwrap();
+        assert_eq!(storage.set_len(b"set").unwrap(), 2);
+        assert_eq!(
+            run(&mut server, "SRANDMEMBER set -9223372036854775808").await,
+            ServerMessage::Error(ServerError::ValueOutOfRange)
+        );
+    }
+}
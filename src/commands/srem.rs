@@ -0,0 +1,64 @@
+use crate::{
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{ServerError, ServerValue},
+};
+
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    if command.len() < 3 {
+        request
+            .error(ServerError::WrongNumberOfArguments(String::from("srem")))
+            .await;
+        return;
+    }
+
+    match storage.set_remove(&command[1], &command[2..]) {
+        Ok(removed) => {
+            request
+                .data(ServerValue::RESP(RESP::Integer(removed as i64)))
+                .await
+        }
+        Err(e) => request.error(ServerError::from(e)).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::run;
+    use crate::server_result::ServerMessage;
+    use crate::storage::Storage;
+
+    #[tokio::test]
+    async fn test_srem() {
+        let mut storage = Storage::new();
+        storage
+            .set_add(b"set", &[b"a".to_vec(), b"b".to_vec()])
+            .unwrap();
+        let mut server = Server::with_new(storage);
+        assert_eq!(
+            run(&mut server, "SREM set a c").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(1)))
+        );
+        assert_eq!(
+            run(&mut server, "SREM set b").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(1)))
+        );
+        // the set is deleted with its last member
+        let storage = server.storage.as_mut().unwrap();
+        assert!(!storage.exists(b"set"));
+        assert_eq!(
+            run(&mut server, "SREM missing a").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(0)))
+        );
+    }
+}
@@ -0,0 +1,128 @@
+use crate::{
+    expire::{unix_time_ms, KeyTTL},
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{ServerError, ServerValue},
+};
+
+// TTL, PTTL, EXPIRETIME and PEXPIRETIME
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    let name = String::from_utf8_lossy(&command[0]).to_lowercase();
+    if command.len() != 2 {
+        request
+            .error(ServerError::WrongNumberOfArguments(name))
+            .await;
+        return;
+    }
+
+    let expiry = match storage.ttl(&command[1]) {
+        Ok(KeyTTL::Missing) => -2,
+        Ok(KeyTTL::Persistent) => -1,
+        Ok(KeyTTL::Expires(deadline)) => {
+            let remaining = (deadline - unix_time_ms(storage.now())).max(0);
+            // seconds are rounded to the nearest value, as Redis does
+            match name.as_str() {
+                "ttl" => (remaining + 500) / 1000,
+                "pttl" => remaining,
+                "expiretime" => (deadline + 500) / 1000,
+                _ => deadline,
+            }
+        }
+        Err(e) => {
+            request.error(ServerError::from(e)).await;
+            return;
+        }
+    };
+    request.data(ServerValue::RESP(RESP::Integer(expiry))).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use crate::commands::test_utils::run;
+    use crate::expire::ExpireTime;
+    use crate::server_result::ServerMessage;
+    use crate::set::SetArgs;
+    use crate::storage::Storage;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn integer(value: i64) -> ServerMessage {
+        ServerMessage::Data(ServerValue::RESP(RESP::Integer(value)))
+    }
+
+    #[tokio::test]
+    async fn test_missing_and_persistent() {
+        let mut storage = Storage::new();
+        storage
+            .set(b"key".to_vec(), b"value".to_vec(), SetArgs::new())
+            .unwrap();
+        let mut server = Server::with_new(storage);
+        for name in ["TTL", "PTTL", "EXPIRETIME", "PEXPIRETIME"] {
+            assert_eq!(
+                run(&mut server, &format!("{} missing", name)).await,
+                integer(-2)
+            );
+            assert_eq!(
+                run(&mut server, &format!("{} key", name)).await,
+                integer(-1)
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_expiring_key() {
+        let clock = ManualClock::new(UNIX_EPOCH + Duration::from_secs(1700000000));
+        let mut storage = Storage::with_clock(clock.clone());
+        storage
+            .set(b"key".to_vec(), b"value".to_vec(), SetArgs::new())
+            .unwrap();
+        storage
+            .expire(b"key", ExpireTime::Relative(100_000), &[], "pexpire")
+            .unwrap();
+        storage
+            .set(b"abs".to_vec(), b"value".to_vec(), SetArgs::new())
+            .unwrap();
+        storage
+            .expire(
+                b"abs",
+                ExpireTime::Absolute(4102444800700),
+                &[],
+                "pexpireat",
+            )
+            .unwrap();
+        let mut server = Server::with_new(storage);
+
+        assert_eq!(run(&mut server, "TTL key").await, integer(100));
+        assert_eq!(run(&mut server, "PTTL key").await, integer(100_000));
+        clock.advance(Duration::from_millis(60_400));
+        assert_eq!(run(&mut server, "TTL key").await, integer(40));
+        assert_eq!(run(&mut server, "PTTL key").await, integer(39_600));
+        assert_eq!(
+            run(&mut server, "EXPIRETIME abs").await,
+            integer(4102444801)
+        );
+        assert_eq!(
+            run(&mut server, "PEXPIRETIME abs").await,
+            integer(4102444800700)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_wrong_number_of_arguments() {
+        let mut server = Server::with_new(Storage::new());
+        assert_eq!(
+            run(&mut server, "TTL a b").await,
+            ServerMessage::Error(ServerError::WrongNumberOfArguments(String::from("ttl")))
+        );
+    }
+}
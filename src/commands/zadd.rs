@@ -0,0 +1,212 @@
+use crate::{
+    number::parse_f64,
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{command_to_string, ServerError, ServerValue},
+    zset::ZAddOptions,
+};
+
+#[derive(Debug, PartialEq)]
+struct ZAddArgs {
+    options: ZAddOptions,
+    increment: bool,
+    entries: Vec<(f64, Vec<u8>)>,
+}
+
+// Parse `[NX|XX] [GT|LT] [CH] [INCR] score member [score member ...]`
+fn parse_zadd_arguments(
+    arguments: &[Vec<u8>],
+    command: &[Vec<u8>],
+) -> Result<ZAddArgs, ServerError> {
+    let mut options = ZAddOptions::default();
+    let mut increment = false;
+    let mut position = 0;
+    while let Some(argument) = arguments.get(position) {
+        match argument.to_ascii_lowercase().as_slice() {
+            b"nx" => options.nx = true,
+            b"xx" => options.xx = true,
+            b"gt" => options.gt = true,
+            b"lt" => options.lt = true,
+            b"ch" => options.ch = true,
+            b"incr" => increment = true,
+            _ => break,
+        }
+        position += 1;
+    }
+
+    let pairs = &arguments[position..];
+    if pairs.is_empty() || !pairs.len().is_multiple_of(2) {
+        return Err(ServerError::CommandSyntaxError(command_to_string(command)));
+    }
+    if increment && pairs.len() > 2 {
+        return Err(ServerError::ZAddIncrPairs);
+    }
+    if options.nx && options.xx {
+        return Err(ServerError::IncompatibleOptions(String::from("XX and NX")));
+    }
+    if (options.gt && options.lt) || (options.nx && (options.gt || options.lt)) {
+        return Err(ServerError::IncompatibleOptions(String::from(
+            "GT, LT, and/or NX",
+        )));
+    }
+    let entries = pairs
+        .chunks_exact(2)
+        .map(|pair| Some((parse_f64(&pair[0])?, pair[1].clone())))
+        .collect::<Option<Vec<_>>>()
+        .ok_or(ServerError::NotAFloat)?;
+    Ok(ZAddArgs {
+        options,
+        increment,
+        entries,
+    })
+}
+
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    if command.len() < 4 {
+        request
+            .error(ServerError::WrongNumberOfArguments(String::from("zadd")))
+            .await;
+        return;
+    }
+    let args = match parse_zadd_arguments(&command[2..], command) {
+        Ok(args) => args,
+        Err(e) => {
+            request.error(e).await;
+            return;
+        }
+    };
+
+    // with INCR, the new score is replied as ZINCRBY does
+    let reply = if args.increment {
+        let (increment, member) = &args.entries[0];
+        storage
+            .zset_incr_by(&command[1], member, *increment, &args.options)
+            .map(|score| score.map_or(RESP::Null, RESP::Double))
+    } else {
+        storage
+            .zset_add(&command[1], &args.entries, &args.options)
+            .map(|count| RESP::Integer(count as i64))
+    };
+    match reply {
+        Ok(reply) => request.data(ServerValue::RESP(reply)).await,
+        Err(e) => request.error(ServerError::from(e)).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::run;
+    use crate::server_result::ServerMessage;
+    use crate::storage::Storage;
+
+    fn reply(value: RESP) -> ServerMessage {
+        ServerMessage::Data(ServerValue::RESP(value))
+    }
+
+    #[tokio::test]
+    async fn test_zadd() {
+        let mut server = Server::with_new(Storage::new());
+        assert_eq!(
+            run(&mut server, "ZADD zset 1 a 2 b 1.5 a").await,
+            reply(RESP::Integer(2))
+        );
+        assert_eq!(
+            run(&mut server, "ZADD zset CH 3 a 2 b 4 c").await,
+            reply(RESP::Integer(2))
+        );
+        assert_eq!(
+            run(&mut server, "ZADD zset XX GT CH 1 a 5 b 1 d").await,
+            reply(RESP::Integer(1))
+        );
+        assert_eq!(
+            run(&mut server, "ZADD zset NX 0 a 0 e").await,
+            reply(RESP::Integer(1))
+        );
+        let storage = server.storage.as_mut().unwrap();
+        assert_eq!(storage.zset_score(b"zset", b"a").unwrap(), Some(3.0));
+        assert_eq!(storage.zset_score(b"zset", b"b").unwrap(), Some(5.0));
+        assert_eq!(storage.zset_score(b"zset", b"d").unwrap(), None);
+        assert_eq!(storage.zset_len(b"zset").unwrap(), 4);
+
+        // XX does not create the key
+        assert_eq!(
+            run(&mut server, "ZADD other XX 1 a").await,
+            reply(RESP::Integer(0))
+        );
+        let storage = server.storage.as_mut().unwrap();
+        assert!(!storage.exists(b"other"));
+    }
+
+    #[tokio::test]
+    async fn test_zadd_incr() {
+        let mut server = Server::with_new(Storage::new());
+        assert_eq!(
+            run(&mut server, "ZADD zset INCR 2.5 a").await,
+            reply(RESP::Double(2.5))
+        );
+        assert_eq!(
+            run(&mut server, "ZADD zset incr 1 a").await,
+            reply(RESP::Double(3.5))
+        );
+        assert_eq!(
+            run(&mut server, "ZADD zset INCR GT -1 a").await,
+            reply(RESP::Null)
+        );
+        assert_eq!(
+            run(&mut server, "ZADD zset INCR NX 1 a").await,
+            reply(RESP::Null)
+        );
+        run(&mut server, "ZADD zset inf b").await;
+        assert_eq!(
+            run(&mut server, "ZADD zset INCR -inf b").await,
+            ServerMessage::Error(ServerError::ScoreNotANumber)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_invalid_arguments() {
+        let mut server = Server::with_new(Storage::new());
+        for (cmd, error) in [
+            (
+                "ZADD zset 1 a 2",
+                ServerError::CommandSyntaxError(String::from("ZADD zset 1 a 2")),
+            ),
+            (
+                "ZADD zset NX CH",
+                ServerError::CommandSyntaxError(String::from("ZADD zset NX CH")),
+            ),
+            ("ZADD zset INCR 1 a 2 b", ServerError::ZAddIncrPairs),
+            (
+                "ZADD zset NX XX 1 a",
+                ServerError::IncompatibleOptions(String::from("XX and NX")),
+            ),
+            (
+                "ZADD zset NX GT 1 a",
+                ServerError::IncompatibleOptions(String::from("GT, LT, and/or NX")),
+            ),
+            (
+                "ZADD zset GT LT 1 a",
+                ServerError::IncompatibleOptions(String::from("GT, LT, and/or NX")),
+            ),
+            ("ZADD zset x a", ServerError::NotAFloat),
+            ("ZADD zset nan a", ServerError::NotAFloat),
+        ] {
+            assert_eq!(
+                run(&mut server, cmd).await,
+                ServerMessage::Error(error),
+                "{}",
+                cmd
+            );
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use crate::{
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{ServerError, ServerValue},
+};
+
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    if command.len() != 2 {
+        request
+            .error(ServerError::WrongNumberOfArguments(String::from("zcard")))
+            .await;
+        return;
+    }
+
+    match storage.zset_len(&command[1]) {
+        Ok(len) => {
+            request
+                .data(ServerValue::RESP(RESP::Integer(len as i64)))
+                .await
+        }
+        Err(e) => request.error(ServerError::from(e)).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::run;
+    use crate::server_result::ServerMessage;
+    use crate::storage::Storage;
+    use crate::zset::ZAddOptions;
+
+    #[tokio::test]
+    async fn test_zcard() {
+        let mut storage = Storage::new();
+        storage
+            .zset_add(
+                b"zset",
+                &[(1.0, b"a".to_vec()), (2.0, b"b".to_vec())],
+                &ZAddOptions::default(),
+            )
+            .unwrap();
+        let mut server = Server::with_new(storage);
+        assert_eq!(
+            run(&mut server, "ZCARD zset").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(2)))
+        );
+        assert_eq!(
+            run(&mut server, "ZCARD missing").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(0)))
+        );
+    }
+}
@@ -0,0 +1,64 @@
+use crate::{
+    number::parse_f64,
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{ServerError, ServerValue},
+    zset::ZAddOptions,
+};
+
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    if command.len() != 4 {
+        request
+            .error(ServerError::WrongNumberOfArguments(String::from("zincrby")))
+            .await;
+        return;
+    }
+    let Some(increment) = parse_f64(&command[2]) else {
+        request.error(ServerError::NotAFloat).await;
+        return;
+    };
+
+    let options = ZAddOptions::default();
+    match storage.zset_incr_by(&command[1], &command[3], increment, &options) {
+        Ok(score) => {
+            request
+                .data(ServerValue::RESP(score.map_or(RESP::Null, RESP::Double)))
+                .await
+        }
+        Err(e) => request.error(ServerError::from(e)).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::run;
+    use crate::server_result::ServerMessage;
+    use crate::storage::Storage;
+
+    #[tokio::test]
+    async fn test_zincrby() {
+        let mut server = Server::with_new(Storage::new());
+        assert_eq!(
+            run(&mut server, "ZINCRBY zset 2 a").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Double(2.0)))
+        );
+        assert_eq!(
+            run(&mut server, "ZINCRBY zset -0.5 a").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Double(1.5)))
+        );
+        assert_eq!(
+            run(&mut server, "ZINCRBY zset one a").await,
+            ServerMessage::Error(ServerError::NotAFloat)
+        );
+    }
+}
@@ -0,0 +1,189 @@
+use crate::{
+    blocking::{self, parse_timeout, BlockingCommand},
+    number::parse_i64,
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{command_to_string, ServerError, ServerValue},
+};
+
+// Parse `numkeys key [key ...] MIN|MAX [COUNT count]`
+fn parse_zmpop_arguments(
+    arguments: &[Vec<u8>],
+    command: &[Vec<u8>],
+) -> Result<BlockingCommand, ServerError> {
+    let numkeys = parse_i64(&arguments[0])
+        .filter(|&numkeys| numkeys > 0)
+        .ok_or_else(|| ServerError::MustBePositive(String::from("numkeys")))?;
+    let syntax_error = || ServerError::CommandSyntaxError(command_to_string(command));
+    let numkeys = usize::try_from(numkeys)
+        .ok()
+        .filter(|&numkeys| numkeys < arguments.len() - 1)
+        .ok_or_else(syntax_error)?;
+    let keys = arguments[1..=numkeys].to_vec();
+    let max = match arguments[numkeys + 1].to_ascii_lowercase().as_slice() {
+        b"min" => false,
+        b"max" => true,
+        _ => return Err(syntax_error()),
+    };
+
+    let count = match &arguments[numkeys + 2..] {
+        [] => 1,
+        [option, count] if option.eq_ignore_ascii_case(b"count") => parse_i64(count)
+            .filter(|&count| count > 0)
+            .ok_or_else(|| ServerError::MustBePositive(String::from("count")))?
+            as usize,
+        _ => return Err(syntax_error()),
+    };
+    Ok(BlockingCommand::ZMultiPop { keys, max, count })
+}
+
+// ZMPOP and BZMPOP, popping from the first non empty sorted set
+// among the keys, BZMPOP waiting for one to receive members if needed
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let name = String::from_utf8_lossy(&command[0]).to_lowercase();
+    let blocking = name == "bzmpop";
+    let minimum = if blocking { 5 } else { 4 };
+    if command.len() < minimum {
+        request
+            .error(ServerError::WrongNumberOfArguments(name))
+            .await;
+        return;
+    }
+
+    let timeout = if blocking {
+        match parse_timeout(&command[1]) {
+            Ok(timeout) => timeout,
+            Err(e) => {
+                request.error(e).await;
+                return;
+            }
+        }
+    } else {
+        None
+    };
+    let arguments = if blocking {
+        &command[2..]
+    } else {
+        &command[1..]
+    };
+    let operation = match parse_zmpop_arguments(arguments, command) {
+        Ok(operation) => operation,
+        Err(e) => {
+            request.error(e).await;
+            return;
+        }
+    };
+
+    if blocking {
+        blocking::run(server, request, operation, timeout).await;
+        return;
+    }
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+    match operation.attempt(storage, None) {
+        Ok(reply) => {
+            request
+                .data(ServerValue::RESP(reply.unwrap_or(RESP::NullArray)))
+                .await
+        }
+        Err(e) => request.error(ServerError::from(e)).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::{add_zset, send, server_with};
+    use crate::server_result::ServerMessage;
+
+    fn server_with_zset() -> Server {
+        server_with(|storage| add_zset(storage, "zset", &[(0.0, "a"), (1.0, "b"), (2.0, "c")]))
+    }
+
+    fn entry(member: &str, score: f64) -> RESP {
+        RESP::Array(vec![
+            RESP::BulkString(member.as_bytes().to_vec()),
+            RESP::Double(score),
+        ])
+    }
+
+    #[tokio::test]
+    async fn test_zmpop() {
+        let mut server = server_with_zset();
+        let mut receiver = send(&mut server, "ZMPOP 2 missing zset MAX COUNT 2").await;
+        assert_eq!(
+            receiver.try_recv().unwrap(),
+            ServerMessage::Data(ServerValue::RESP(RESP::Array(vec![
+                RESP::BulkString(b"zset".to_vec()),
+                RESP::Array(vec![entry("c", 2.0), entry("b", 1.0)]),
+            ])))
+        );
+        let mut receiver = send(&mut server, "ZMPOP 1 zset min COUNT 5").await;
+        assert_eq!(
+            receiver.try_recv().unwrap(),
+            ServerMessage::Data(ServerValue::RESP(RESP::Array(vec![
+                RESP::BulkString(b"zset".to_vec()),
+                RESP::Array(vec![entry("a", 0.0)]),
+            ])))
+        );
+        let mut receiver = send(&mut server, "ZMPOP 1 zset MIN").await;
+        assert_eq!(
+            receiver.try_recv().unwrap(),
+            ServerMessage::Data(ServerValue::RESP(RESP::NullArray))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_bzmpop() {
+        let mut server = server_with_zset();
+        let mut receiver = send(&mut server, "BZMPOP 0 1 zset MIN").await;
+        assert_eq!(
+            receiver.try_recv().unwrap(),
+            ServerMessage::Data(ServerValue::RESP(RESP::Array(vec![
+                RESP::BulkString(b"zset".to_vec()),
+                RESP::Array(vec![entry("a", 0.0)]),
+            ])))
+        );
+        let mut receiver = send(&mut server, "BZMPOP 0.5 1 other MAX").await;
+        assert!(receiver.try_recv().is_err());
+        assert_eq!(server.blocked.waiting_on(b"other"), vec![0]);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_arguments() {
+        let mut server = server_with_zset();
+        for (cmd, error) in [
+            (
+                "ZMPOP 0 zset MIN",
+                ServerError::MustBePositive(String::from("numkeys")),
+            ),
+            (
+                "ZMPOP 2 zset MIN",
+                ServerError::CommandSyntaxError(String::from("ZMPOP 2 zset MIN")),
+            ),
+            (
+                "ZMPOP 1 zset LEFT",
+                ServerError::CommandSyntaxError(String::from("ZMPOP 1 zset LEFT")),
+            ),
+            (
+                "ZMPOP 1 zset MAX COUNT 0",
+                ServerError::MustBePositive(String::from("count")),
+            ),
+            ("BZMPOP -1 1 zset MIN", ServerError::NegativeTimeout),
+        ] {
+            let mut receiver = send(&mut server, cmd).await;
+            assert_eq!(
+                receiver.try_recv().unwrap(),
+                ServerMessage::Error(error),
+                "{}",
+                cmd
+            );
+        }
+    }
+}
@@ -0,0 +1,320 @@
+use crate::{
+    number::parse_i64,
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{command_to_string, ServerError, ServerValue},
+    zset::{LexBound, ScoreBound, ZRangeBy},
+};
+
+#[derive(Debug, PartialEq)]
+struct ZRangeArgs {
+    by: ZRangeBy,
+    reverse: bool,
+    limit: Option<(i64, i64)>,
+    with_scores: bool,
+}
+
+#[derive(PartialEq)]
+enum By {
+    Rank,
+    Score,
+    Lex,
+}
+
+// Parse `start stop [BYSCORE|BYLEX] [REV] [LIMIT offset count] [WITHSCORES]`,
+// ZRANGESTORE not taking WITHSCORES
+fn parse_zrange_arguments(
+    arguments: &[Vec<u8>],
+    command: &[Vec<u8>],
+    store: bool,
+) -> Result<ZRangeArgs, ServerError> {
+    let syntax_error = || ServerError::CommandSyntaxError(command_to_string(command));
+    let mut by = By::Rank;
+    let mut reverse = false;
+    let mut limit = None;
+    let mut with_scores = false;
+    let mut position = 2;
+    while let Some(argument) = arguments.get(position) {
+        match argument.to_ascii_lowercase().as_slice() {
+            b"byscore" if by == By::Rank => by = By::Score,
+            b"bylex" if by == By::Rank => by = By::Lex,
+            b"rev" => reverse = true,
+            b"withscores" if !store => with_scores = true,
+            b"limit" if position + 2 < arguments.len() => {
+                let offset = parse_i64(&arguments[position + 1]);
+                let count = parse_i64(&arguments[position + 2]);
+                match (offset, count) {
+                    (Some(offset), Some(count)) => limit = Some((offset, count)),
+                    _ => return Err(ServerError::NotAnInteger),
+                }
+                position += 2;
+            }
+            _ => return Err(syntax_error()),
+        }
+        position += 1;
+    }
+    if limit.is_some() && by == By::Rank {
+        return Err(ServerError::LimitWithoutBy);
+    }
+    if with_scores && by == By::Lex {
+        return Err(ServerError::WithScoresByLex);
+    }
+
+    // with REV, score and lex ranges are given highest first
+    let (first, second) = (&arguments[0], &arguments[1]);
+    let (min, max) = match reverse {
+        true => (second, first),
+        false => (first, second),
+    };
+    let by = match by {
+        By::Rank => match (parse_i64(first), parse_i64(second)) {
+            (Some(start), Some(stop)) => ZRangeBy::Rank(start, stop),
+            _ => return Err(ServerError::NotAnInteger),
+        },
+        By::Score => match (ScoreBound::parse(min), ScoreBound::parse(max)) {
+            (Some(min), Some(max)) => ZRangeBy::Score(min, max),
+            _ => return Err(ServerError::MinOrMaxNotAFloat),
+        },
+        By::Lex => match (LexBound::parse(min), LexBound::parse(max)) {
+            (Some(min), Some(max)) => ZRangeBy::Lex(min, max),
+            _ => return Err(ServerError::MinOrMaxNotAStringRange),
+        },
+    };
+    Ok(ZRangeArgs {
+        by,
+        reverse,
+        limit,
+        with_scores,
+    })
+}
+
+// ZRANGE, and ZRANGESTORE which takes a destination before the key
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    let name = String::from_utf8_lossy(&command[0]).to_lowercase();
+    let store = name == "zrangestore";
+    let minimum = if store { 5 } else { 4 };
+    if command.len() < minimum {
+        request
+            .error(ServerError::WrongNumberOfArguments(name))
+            .await;
+        return;
+    }
+    let key = if store { 2 } else { 1 };
+    let args = match parse_zrange_arguments(&command[key + 1..], command, store) {
+        Ok(args) => args,
+        Err(e) => {
+            request.error(e).await;
+            return;
+        }
+    };
+
+    if store {
+        match storage.zset_range_store(
+            &command[1],
+            &command[key],
+            &args.by,
+            args.reverse,
+            args.limit,
+        ) {
+            Ok(len) => {
+                request
+                    .data(ServerValue::RESP(RESP::Integer(len as i64)))
+                    .await
+            }
+            Err(e) => request.error(ServerError::from(e)).await,
+        }
+        return;
+    }
+    match storage.zset_range(&command[key], &args.by, args.reverse, args.limit) {
+        Ok(entries) if args.with_scores => {
+            request
+                .data(ServerValue::RESP(RESP::Pairs(
+                    entries
+                        .into_iter()
+                        .map(|(member, score)| (RESP::BulkString(member), RESP::Double(score)))
+                        .collect(),
+                )))
+                .await
+        }
+        Ok(entries) => {
+            request
+                .data(ServerValue::RESP(RESP::Array(
+                    entries
+                        .into_iter()
+                        .map(|(member, _)| RESP::BulkString(member))
+                        .collect(),
+                )))
+                .await
+        }
+        Err(e) => request.error(ServerError::from(e)).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::{add_zset, run, server_with};
+    use crate::server_result::ServerMessage;
+
+    fn members(members: &[&str]) -> ServerMessage {
+        ServerMessage::Data(ServerValue::RESP(RESP::Array(
+            members
+                .iter()
+                .map(|member| RESP::BulkString(member.as_bytes().to_vec()))
+                .collect(),
+        )))
+    }
+
+    fn server() -> Server {
+        server_with(|storage| {
+            add_zset(
+                storage,
+                "zset",
+                &[(0.0, "a"), (1.0, "b"), (2.0, "c"), (3.0, "d"), (4.0, "e")],
+            )
+        })
+    }
+
+    #[tokio::test]
+    async fn test_zrange_by_rank() {
+        let mut server = server();
+        assert_eq!(
+            run(&mut server, "ZRANGE zset 0 -1").await,
+            members(&["a", "b", "c", "d", "e"])
+        );
+        assert_eq!(
+            run(&mut server, "ZRANGE zset 1 2 REV").await,
+            members(&["d", "c"])
+        );
+        assert_eq!(run(&mut server, "ZRANGE zset 3 1").await, members(&[]));
+        assert_eq!(
+            run(&mut server, "ZRANGE zset -2 10 WITHSCORES").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Pairs(vec![
+                (RESP::BulkString(b"d".to_vec()), RESP::Double(3.0)),
+                (RESP::BulkString(b"e".to_vec()), RESP::Double(4.0)),
+            ])))
+        );
+        assert_eq!(run(&mut server, "ZRANGE missing 0 -1").await, members(&[]));
+    }
+
+    #[tokio::test]
+    async fn test_zrange_by_score() {
+        let mut server = server();
+        assert_eq!(
+            run(&mut server, "ZRANGE zset (1 3 BYSCORE").await,
+            members(&["c", "d"])
+        );
+        assert_eq!(
+            run(&mut server, "ZRANGE zset +inf 2 BYSCORE REV").await,
+            members(&["e", "d", "c"])
+        );
+        assert_eq!(
+            run(&mut server, "ZRANGE zset -inf +inf BYSCORE LIMIT 1 2").await,
+            members(&["b", "c"])
+        );
+        assert_eq!(
+            run(&mut server, "ZRANGE zset +inf -inf BYSCORE REV LIMIT 1 -1").await,
+            members(&["d", "c", "b", "a"])
+        );
+        assert_eq!(
+            run(&mut server, "ZRANGE zset 3 1 BYSCORE").await,
+            members(&[])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_zrange_by_lex() {
+        let mut server = server();
+        assert_eq!(
+            run(&mut server, "ZRANGE zset [b (d BYLEX").await,
+            members(&["b", "c"])
+        );
+        assert_eq!(
+            run(&mut server, "ZRANGE zset + (c BYLEX REV").await,
+            members(&["e", "d"])
+        );
+        assert_eq!(
+            run(&mut server, "ZRANGE zset - + BYLEX LIMIT 4 10").await,
+            members(&["e"])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_zrangestore() {
+        let mut server = server();
+        assert_eq!(
+            run(
+                &mut server,
+                "ZRANGESTORE out zset (1 +inf BYSCORE LIMIT 0 2"
+            )
+            .await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(2)))
+        );
+        assert_eq!(
+            run(&mut server, "ZRANGE out 0 -1").await,
+            members(&["c", "d"])
+        );
+        assert_eq!(
+            run(&mut server, "ZRANGESTORE out zset 0 0 REV").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(1)))
+        );
+        assert_eq!(run(&mut server, "ZRANGE out 0 -1").await, members(&["e"]));
+        assert_eq!(
+            run(&mut server, "ZRANGESTORE out missing 0 -1").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(0)))
+        );
+        assert_eq!(run(&mut server, "ZRANGE out 0 -1").await, members(&[]));
+        assert_eq!(
+            run(&mut server, "ZRANGESTORE out zset 0 -1 WITHSCORES").await,
+            ServerMessage::Error(ServerError::CommandSyntaxError(String::from(
+                "ZRANGESTORE out zset 0 -1 WITHSCORES"
+            )))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_invalid_arguments() {
+        let mut server = server();
+        for (cmd, error) in [
+            ("ZRANGE zset a 1", ServerError::NotAnInteger),
+            ("ZRANGE zset 0 1 LIMIT 0 1", ServerError::LimitWithoutBy),
+            ("ZRANGE zset a 1 BYSCORE", ServerError::MinOrMaxNotAFloat),
+            (
+                "ZRANGE zset a b BYLEX",
+                ServerError::MinOrMaxNotAStringRange,
+            ),
+            (
+                "ZRANGE zset - + BYLEX WITHSCORES",
+                ServerError::WithScoresByLex,
+            ),
+            (
+                "ZRANGE zset 0 1 BYSCORE LIMIT 0 x",
+                ServerError::NotAnInteger,
+            ),
+            (
+                "ZRANGE zset 0 1 BYSCORE LIMIT 0",
+                ServerError::CommandSyntaxError(String::from("ZRANGE zset 0 1 BYSCORE LIMIT 0")),
+            ),
+            (
+                "ZRANGE zset 0 1 BYSCORE BYLEX",
+                ServerError::CommandSyntaxError(String::from("ZRANGE zset 0 1 BYSCORE BYLEX")),
+            ),
+        ] {
+            assert_eq!(
+                run(&mut server, cmd).await,
+                ServerMessage::Error(error),
+                "{}",
+                cmd
+            );
+        }
+    }
+}
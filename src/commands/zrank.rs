@@ -0,0 +1,107 @@
+use crate::{
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{command_to_string, ServerError, ServerValue},
+};
+
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    // ZREVRANK counts ranks from the highest score
+    let name = String::from_utf8_lossy(&command[0]).to_lowercase();
+    if command.len() != 3 && command.len() != 4 {
+        request
+            .error(ServerError::WrongNumberOfArguments(name))
+            .await;
+        return;
+    }
+    let with_score = match command.get(3) {
+        Some(argument) if argument.eq_ignore_ascii_case(b"withscore") => true,
+        Some(_) => {
+            request
+                .error(ServerError::CommandSyntaxError(command_to_string(command)))
+                .await;
+            return;
+        }
+        None => false,
+    };
+
+    match storage.zset_rank(&command[1], &command[2], name == "zrevrank") {
+        Ok(Some((rank, score))) if with_score => {
+            request
+                .data(ServerValue::RESP(RESP::Array(vec![
+                    RESP::Integer(rank as i64),
+                    RESP::Double(score),
+                ])))
+                .await
+        }
+        Ok(Some((rank, _))) => {
+            request
+                .data(ServerValue::RESP(RESP::Integer(rank as i64)))
+                .await
+        }
+        Ok(None) if with_score => request.data(ServerValue::RESP(RESP::NullArray)).await,
+        Ok(None) => request.data(ServerValue::RESP(RESP::Null)).await,
+        Err(e) => request.error(ServerError::from(e)).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::run;
+    use crate::server_result::ServerMessage;
+    use crate::storage::Storage;
+    use crate::zset::ZAddOptions;
+
+    fn reply(value: RESP) -> ServerMessage {
+        ServerMessage::Data(ServerValue::RESP(value))
+    }
+
+    #[tokio::test]
+    async fn test_zrank() {
+        let mut storage = Storage::new();
+        storage
+            .zset_add(
+                b"zset",
+                &[
+                    (1.0, b"a".to_vec()),
+                    (2.0, b"b".to_vec()),
+                    (3.0, b"c".to_vec()),
+                ],
+                &ZAddOptions::default(),
+            )
+            .unwrap();
+        let mut server = Server::with_new(storage);
+        assert_eq!(
+            run(&mut server, "ZRANK zset a").await,
+            reply(RESP::Integer(0))
+        );
+        assert_eq!(
+            run(&mut server, "ZREVRANK zset a").await,
+            reply(RESP::Integer(2))
+        );
+        assert_eq!(
+            run(&mut server, "ZRANK zset b WITHSCORE").await,
+            reply(RESP::Array(vec![RESP::Integer(1), RESP::Double(2.0)]))
+        );
+        assert_eq!(run(&mut server, "ZRANK zset d").await, reply(RESP::Null));
+        assert_eq!(
+            run(&mut server, "ZREVRANK zset d withscore").await,
+            reply(RESP::NullArray)
+        );
+        assert_eq!(
+            run(&mut server, "ZRANK zset a SCORE").await,
+            ServerMessage::Error(ServerError::CommandSyntaxError(String::from(
+                "ZRANK zset a SCORE"
+            )))
+        );
+    }
+}
@@ -0,0 +1,62 @@
+use crate::{
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{ServerError, ServerValue},
+};
+
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    if command.len() != 3 {
+        request
+            .error(ServerError::WrongNumberOfArguments(String::from("zscore")))
+            .await;
+        return;
+    }
+
+    match storage.zset_score(&command[1], &command[2]) {
+        Ok(score) => {
+            request
+                .data(ServerValue::RESP(score.map_or(RESP::Null, RESP::Double)))
+                .await
+        }
+        Err(e) => request.error(ServerError::from(e)).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::run;
+    use crate::server_result::ServerMessage;
+    use crate::storage::Storage;
+    use crate::zset::ZAddOptions;
+
+    #[tokio::test]
+    async fn test_zscore() {
+        let mut storage = Storage::new();
+        storage
+            .zset_add(b"zset", &[(1.5, b"a".to_vec())], &ZAddOptions::default())
+            .unwrap();
+        let mut server = Server::with_new(storage);
+        assert_eq!(
+            run(&mut server, "ZSCORE zset a").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Double(1.5)))
+        );
+        assert_eq!(
+            run(&mut server, "ZSCORE zset b").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Null))
+        );
+        assert_eq!(
+            run(&mut server, "ZSCORE missing a").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Null))
+        );
+    }
+}
@@ -0,0 +1,228 @@
+use crate::{
+    number::{parse_f64, parse_i64},
+    request::Request,
+    resp::RESP,
+    server::Server,
+    server_result::{command_to_string, ServerError, ServerValue},
+    set_type::SetOperation,
+    zset::Aggregate,
+};
+
+#[derive(Debug, PartialEq)]
+struct ZStoreArgs {
+    keys: Vec<Vec<u8>>,
+    weights: Vec<f64>,
+    aggregate: Aggregate,
+}
+
+// Parse `numkeys key [key ...] [WEIGHTS weight [weight ...]]
+// [AGGREGATE SUM|MIN|MAX]`, ZDIFFSTORE taking neither option
+fn parse_zstore_arguments(
+    arguments: &[Vec<u8>],
+    command: &[Vec<u8>],
+    name: &str,
+) -> Result<ZStoreArgs, ServerError> {
+    let syntax_error = || ServerError::CommandSyntaxError(command_to_string(command));
+    let numkeys = parse_i64(&arguments[0]).ok_or(ServerError::NotAnInteger)?;
+    if numkeys < 1 {
+        return Err(ServerError::AtLeastOneInputKey(String::from(name)));
+    }
+    let numkeys = usize::try_from(numkeys)
+        .ok()
+        .filter(|&numkeys| numkeys < arguments.len())
+        .ok_or_else(syntax_error)?;
+    let keys = arguments[1..=numkeys].to_vec();
+
+    let options = name != "zdiffstore";
+    let mut weights = vec![1.0; numkeys];
+    let mut aggregate = Aggregate::default();
+    let mut position = numkeys + 1;
+    while let Some(argument) = arguments.get(position) {
+        match argument.to_ascii_lowercase().as_slice() {
+            b"weights" if options && position + numkeys < arguments.len() => {
+                for (weight, argument) in weights.iter_mut().zip(&arguments[position + 1..]) {
+                    *weight = parse_f64(argument).ok_or(ServerError::WeightNotAFloat)?;
+                }
+                position += numkeys;
+            }
+            b"aggregate" if options && position + 1 < arguments.len() => {
+                aggregate = match arguments[position + 1].to_ascii_lowercase().as_slice() {
+                    b"sum" => Aggregate::Sum,
+                    b"min" => Aggregate::Min,
+                    b"max" => Aggregate::Max,
+                    _ => return Err(syntax_error()),
+                };
+                position += 1;
+            }
+            _ => return Err(syntax_error()),
+        }
+        position += 1;
+    }
+    Ok(ZStoreArgs {
+        keys,
+        weights,
+        aggregate,
+    })
+}
+
+// ZUNIONSTORE, ZINTERSTORE and ZDIFFSTORE, which take sets as well
+pub async fn command(server: &mut Server, request: &Request, command: &[Vec<u8>]) {
+    let storage = match server.storage.as_mut() {
+        Some(storage) => storage,
+        None => {
+            request.error(ServerError::StorageNotInitialized).await;
+            return;
+        }
+    };
+
+    let name = String::from_utf8_lossy(&command[0]).to_lowercase();
+    if command.len() < 4 {
+        request
+            .error(ServerError::WrongNumberOfArguments(name))
+            .await;
+        return;
+    }
+    let args = match parse_zstore_arguments(&command[2..], command, &name) {
+        Ok(args) => args,
+        Err(e) => {
+            request.error(e).await;
+            return;
+        }
+    };
+    let operation = match name.as_str() {
+        "zinterstore" => SetOperation::Intersection,
+        "zdiffstore" => SetOperation::Difference,
+        _ => SetOperation::Union,
+    };
+
+    match storage.zset_combine_store(
+        &command[1],
+        &args.keys,
+        &args.weights,
+        args.aggregate,
+        operation,
+    ) {
+        Ok(len) => {
+            request
+                .data(ServerValue::RESP(RESP::Integer(len as i64)))
+                .await
+        }
+        Err(e) => request.error(ServerError::from(e)).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::{add_set, add_zset, run, server_with};
+    use crate::server_result::ServerMessage;
+    use crate::zset::ZRangeBy;
+
+    fn server() -> Server {
+        server_with(|storage| {
+            add_zset(storage, "a", &[(1.0, "x"), (2.0, "y")]);
+            add_zset(storage, "b", &[(10.0, "y"), (20.0, "z")]);
+            add_set(storage, "set", &["z"]);
+        })
+    }
+
+    fn scores(server: &mut Server, key: &[u8]) -> Vec<(Vec<u8>, f64)> {
+        let storage = server.storage.as_mut().unwrap();
+        storage
+            .zset_range(key, &ZRangeBy::Rank(0, -1), false, None)
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_zunionstore() {
+        let mut server = server();
+        assert_eq!(
+            run(&mut server, "ZUNIONSTORE out 3 a b set WEIGHTS 2 1 5").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(3)))
+        );
+        assert_eq!(
+            scores(&mut server, b"out"),
+            vec![
+                (b"x".to_vec(), 2.0),
+                (b"y".to_vec(), 14.0),
+                (b"z".to_vec(), 25.0)
+            ]
+        );
+        run(&mut server, "ZUNIONSTORE out 2 a b aggregate MIN").await;
+        assert_eq!(
+            scores(&mut server, b"out"),
+            vec![
+                (b"x".to_vec(), 1.0),
+                (b"y".to_vec(), 2.0),
+                (b"z".to_vec(), 20.0)
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_zinterstore_and_zdiffstore() {
+        let mut server = server();
+        assert_eq!(
+            run(&mut server, "ZINTERSTORE out 2 a b AGGREGATE MAX").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(1)))
+        );
+        assert_eq!(scores(&mut server, b"out"), vec![(b"y".to_vec(), 10.0)]);
+        assert_eq!(
+            run(&mut server, "ZDIFFSTORE out 2 b set").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(1)))
+        );
+        assert_eq!(scores(&mut server, b"out"), vec![(b"y".to_vec(), 10.0)]);
+
+        // an empty result deletes the destination
+        assert_eq!(
+            run(&mut server, "ZINTERSTORE out 2 a missing").await,
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(0)))
+        );
+        let storage = server.storage.as_mut().unwrap();
+        assert!(!storage.exists(b"out"));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_arguments() {
+        let mut server = server();
+        let storage = server.storage.as_mut().unwrap();
+        storage
+            .push(b"list", &[b"x".to_vec()], false, false)
+            .unwrap();
+        for (cmd, error) in [
+            (
+                "ZUNIONSTORE out 0 a",
+                ServerError::AtLeastOneInputKey(String::from("zunionstore")),
+            ),
+            ("ZUNIONSTORE out x a", ServerError::NotAnInteger),
+            (
+                "ZUNIONSTORE out 2 a",
+                ServerError::CommandSyntaxError(String::from("ZUNIONSTORE out 2 a")),
+            ),
+            (
+                "ZUNIONSTORE out 2 a b WEIGHTS 1",
+                ServerError::CommandSyntaxError(String::from("ZUNIONSTORE out 2 a b WEIGHTS 1")),
+            ),
+            (
+                "ZUNIONSTORE out 2 a b WEIGHTS 1 x",
+                ServerError::WeightNotAFloat,
+            ),
+            (
+                "ZINTERSTORE out 1 a AGGREGATE AVG",
+                ServerError::CommandSyntaxError(String::from("ZINTERSTORE out 1 a AGGREGATE AVG")),
+            ),
+            (
+                "ZDIFFSTORE out 1 a WEIGHTS 2",
+                ServerError::CommandSyntaxError(String::from("ZDIFFSTORE out 1 a WEIGHTS 2")),
+            ),
+            ("ZUNIONSTORE out 2 a list", ServerError::WrongType),
+        ] {
+            assert_eq!(
+                run(&mut server, cmd).await,
+                ServerMessage::Error(error),
+                "{}",
+                cmd
+            );
+        }
+    }
+}
@@ -1,4 +1,8 @@
 use core::fmt;
+use std::{
+    collections::VecDeque,
+    sync::atomic::{AtomicU64, Ordering},
+};
 use tokio::{
     io::{AsyncReadExt, AsyncWriteExt},
     net::{TcpListener, TcpStream},
@@ -7,9 +11,12 @@ use tokio::{
 };
 
 use crate::{
+    commands::hello,
     request::Request,
-    resp::bytes_to_resp,
-    server_result::{ServerError, ServerMessage, ServerValue},
+    resp::{Protocol, RESP},
+    resp_decoder::RESPDecoder,
+    resp_result::RESPError,
+    server_result::{ServerMessage, ServerValue},
 };
 
 #[derive(Debug)]
@@ -19,58 +26,97 @@ pub enum ConnectionMessage {
 
 #[derive(Debug)]
 pub enum ConnectionError {
-    ServerError(ServerError),
+    Protocol(RESPError),
 }
 
 impl fmt::Display for ConnectionError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            ConnectionError::ServerError(e) => {
-                write!(f, "{}", format!("Server error:{}", e))
+            ConnectionError::Protocol(e) => {
+                write!(f, "ERR Protocol error: {}", e)
             }
         }
     }
 }
 
+static NEXT_CLIENT_ID: AtomicU64 = AtomicU64::new(1);
+
+// State that belongs to a single connection
+#[derive(Debug)]
+struct Client {
+    id: u64,
+    protocol: Protocol,
+    name: Option<String>,
+}
+
+impl Client {
+    fn new() -> Self {
+        Self {
+            id: NEXT_CLIENT_ID.fetch_add(1, Ordering::Relaxed),
+            protocol: Protocol::default(),
+            name: None,
+        }
+    }
+}
+
+impl fmt::Display for Client {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.name {
+            Some(name) => write!(f, "id={} name={}", self.id, name),
+            None => write!(f, "id={}", self.id),
+        }
+    }
+}
+
 pub async fn handle_connection(
     mut stream: TcpStream,
     server_sender: mpsc::Sender<ConnectionMessage>,
 ) {
-    let mut buffer = [0; 512];
+    let mut client = Client::new();
+    let mut buffer = [0; 4096];
+    let mut decoder = RESPDecoder::new();
+    let mut pending: VecDeque<RESP> = VecDeque::new();
     let (connection_sender, mut connection_receiver) = mpsc::channel::<ServerMessage>(32);
+    // dropped once no more requests will be forwarded, the replies
+    // channel then closes after the reply to the last request
+    let mut connection_sender = Some(connection_sender);
+    let mut reading = true;
+    // the stream can't be resynchronised after a malformed frame,
+    // the error is sent after the replies to the previous frames
+    // and the connection closed, as Redis does
+    let mut protocol_error: Option<RESPError> = None;
 
     loop {
+        if !reading && pending.is_empty() {
+            connection_sender = None;
+        }
         select! {
-            result = stream.read(&mut buffer) => {
+            // Stop reading while decoded requests are still waiting
+            // to be forwarded, so a fast client can't grow the queue
+            result = stream.read(&mut buffer), if reading && pending.is_empty() => {
                 match result {
                     Ok(size) if size != 0 => {
-                        let mut index = 0;
+                        decoder.extend(&buffer[..size]);
 
-                        let resp = match bytes_to_resp(&buffer[..size].to_vec(), &mut index) {
-                            Ok(v) => v,
-                            Err(e) => {
-                                eprintln!("Error {}", e);
-                                return;
-                            }
-                        };
-                        eprintln!("resp {:?}", resp);
-                        let request = Request {
-                            value: resp,
-                            sender: connection_sender.clone()
-                        };
-
-                        let connection_message = ConnectionMessage::Request(request);
-                        match server_sender.send(connection_message).await {
-                            Ok(()) => {},
-                            Err(e) => {
-                                eprintln!("Error sending request: {}", e);
-                                return;
+                        // decode every complete frame, a partial one
+                        // stays in the decoder until the rest arrives
+                        loop {
+                            match decoder.decode() {
+                                Ok(Some(v)) => pending.push_back(v),
+                                Ok(None) => break,
+                                Err(e) => {
+                                    protocol_error = Some(e);
+                                    reading = false;
+                                    break;
+                                }
                             }
                         }
                     }
+                    // the client may only have closed its writing side,
+                    // it still gets the replies to what it sent
                     Ok(_) => {
-                        eprintln!("Connection Closed");
-                        break;
+                        eprintln!("Connection Closed {}", client);
+                        reading = false;
                     }
                     Err(e) => {
                         eprintln!("err ={}", e);
@@ -78,17 +124,55 @@ pub async fn handle_connection(
                     }
                 }
             }
-            Some(response) = connection_receiver.recv() => {
-                let _ = match response {
-                    ServerMessage::Data(ServerValue::RESP(v)) => stream.write_all(v.to_string().as_bytes()).await,
-                    ServerMessage::Error(e) => {
-                        eprintln!("Error: {}", ConnectionError::ServerError(e));
+            // Requests are forwarded one at a time while replies keep
+            // being written, otherwise both channels could fill up
+            permit = server_sender.reserve(), if !pending.is_empty() => {
+                let (permit, sender) = match (permit, connection_sender.clone()) {
+                    (Ok(permit), Some(sender)) => (permit, sender),
+                    (Err(e), _) => {
+                        eprintln!("Error sending request: {}", e);
                         return;
                     }
+                    (_, None) => return,
+                };
+                let request = Request {
+                    value: pending.pop_front().unwrap(),
+                    sender,
+                    client_id: client.id,
+                };
+                permit.send(ConnectionMessage::Request(request));
+            }
+            response = connection_receiver.recv() => {
+                let reply = match response {
+                    Some(ServerMessage::Data(ServerValue::RESP(v))) => v,
+                    // the reply to HELLO already uses the new protocol
+                    Some(ServerMessage::Data(ServerValue::Hello { protocol, name })) => {
+                        if let Some(protocol) = protocol {
+                            client.protocol = protocol;
+                        }
+                        if name.is_some() {
+                            client.name = name;
+                        }
+                        hello::reply(client.id, client.protocol)
+                    }
+                    // command errors are replied to the client,
+                    // the connection stays open
+                    Some(ServerMessage::Error(e)) => RESP::from(e),
+                    // every request was replied to
+                    None => break,
                 };
+                if let Err(e) = stream.write_all(&reply.to_bytes(client.protocol)).await {
+                    eprintln!("err ={}", e);
+                    return;
+                }
             }
         }
     }
+
+    if let Some(e) = protocol_error {
+        let error = RESP::SimpleError(ConnectionError::Protocol(e).to_string());
+        let _ = stream.write_all(&error.to_bytes(client.protocol)).await;
+    }
 }
 
 pub async fn run_listner(host: String, port: u16, server_sender: mpsc::Sender<ConnectionMessage>) {
@@ -112,3 +196,229 @@ pub async fn run_listner(host: String, port: u16, server_sender: mpsc::Sender<Co
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        server::{run_server, Server},
+        storage::Storage,
+    };
+
+    async fn connect() -> TcpStream {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let (server_sender, server_receiver) = mpsc::channel::<ConnectionMessage>(32);
+        tokio::spawn(run_server(
+            Server::with_new(Storage::new()),
+            server_receiver,
+        ));
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            handle_connection(stream, server_sender).await;
+        });
+        TcpStream::connect(address).await.unwrap()
+    }
+
+    async fn read_exact(stream: &mut TcpStream, length: usize) -> Vec<u8> {
+        let mut output = vec![0; length];
+        stream.read_exact(&mut output).await.unwrap();
+        output
+    }
+
+    #[tokio::test]
+    async fn test_pipelined_commands() {
+        let mut stream = connect().await;
+        stream
+            .write_all(
+                concat!(
+                    "*3\r\n$3\r\nSET\r\n$1\r\na\r\n$1\r\n1\r\n",
+                    "*3\r\n$3\r\nSET\r\n$1\r\nb\r\n$1\r\n2\r\n",
+                    "*2\r\n$3\r\nGET\r\n$1\r\na\r\n",
+                )
+                .as_bytes(),
+            )
+            .await
+            .unwrap();
+        let expected = "+OK\r\n+OK\r\n$1\r\n1\r\n";
+        assert_eq!(
+            read_exact(&mut stream, expected.len()).await,
+            expected.as_bytes()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pipelined_commands_split_across_writes() {
+        let mut stream = connect().await;
+        stream
+            .write_all("*1\r\n$4\r\nPING\r\n*2\r\n$4\r\nEC".as_bytes())
+            .await
+            .unwrap();
+        assert_eq!(read_exact(&mut stream, 7).await, "+PONG\r\n".as_bytes());
+        stream
+            .write_all("HO\r\n$2\r\nhi\r\n".as_bytes())
+            .await
+            .unwrap();
+        assert_eq!(read_exact(&mut stream, 8).await, "$2\r\nhi\r\n".as_bytes());
+    }
+
+    #[tokio::test]
+    async fn test_many_pipelined_commands() {
+        let mut stream = connect().await;
+        let count = 5000;
+        let requests = "*1\r\n$4\r\nPING\r\n".repeat(count);
+        let (mut reader, mut writer) = stream.split();
+        let (_, replies) = tokio::join!(writer.write_all(requests.as_bytes()), async {
+            let mut output = vec![0; 7 * count];
+            reader.read_exact(&mut output).await.unwrap();
+            output
+        });
+        assert_eq!(replies, "+PONG\r\n".repeat(count).as_bytes());
+    }
+
+    #[tokio::test]
+    async fn test_binary_value() {
+        let mut stream = connect().await;
+        let mut request = b"*3\r\n$3\r\nSET\r\n$2\r\n\xff\x00\r\n$4\r\n\x80\r\n\x00\r\n".to_vec();
+        request.extend_from_slice(b"*2\r\n$3\r\nGET\r\n$2\r\n\xff\x00\r\n");
+        stream.write_all(&request).await.unwrap();
+        let expected = b"+OK\r\n$4\r\n\x80\r\n\x00\r\n";
+        assert_eq!(read_exact(&mut stream, expected.len()).await, expected);
+    }
+
+    #[tokio::test]
+    async fn test_error_reply_keeps_connection_open() {
+        let mut stream = connect().await;
+        stream
+            .write_all("*1\r\n$4\r\nPIGN\r\n*1\r\n$4\r\nPING\r\n".as_bytes())
+            .await
+            .unwrap();
+        let expected = "-ERR unknown command 'PIGN'\r\n+PONG\r\n";
+        assert_eq!(
+            read_exact(&mut stream, expected.len()).await,
+            expected.as_bytes()
+        );
+
+        stream
+            .write_all("*4\r\n$3\r\nSET\r\n$1\r\na\r\n$1\r\n1\r\n$3\r\nBAD\r\n".as_bytes())
+            .await
+            .unwrap();
+        let expected = "-ERR syntax error\r\n";
+        assert_eq!(
+            read_exact(&mut stream, expected.len()).await,
+            expected.as_bytes()
+        );
+
+        stream
+            .write_all("*1\r\n$3\r\nGET\r\n*1\r\n$4\r\nPING\r\n".as_bytes())
+            .await
+            .unwrap();
+        let expected = "-ERR wrong number of arguments for 'get' command\r\n+PONG\r\n";
+        assert_eq!(
+            read_exact(&mut stream, expected.len()).await,
+            expected.as_bytes()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_protocol_error_closes_connection() {
+        let mut stream = connect().await;
+        stream
+            .write_all("*1\r\n$2\r\nPING\r\n".as_bytes())
+            .await
+            .unwrap();
+        let mut output = Vec::new();
+        stream.read_to_end(&mut output).await.unwrap();
+        assert_eq!(
+            output,
+            "-ERR Protocol error: Missing \\r\\n after bulk string\r\n".as_bytes()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_protocol_error_after_pipelined_commands() {
+        let mut stream = connect().await;
+        stream
+            .write_all("SET pre 1\r\nGET pre\r\nECHO \"abc\r\nPING\r\n".as_bytes())
+            .await
+            .unwrap();
+        let mut output = Vec::new();
+        stream.read_to_end(&mut output).await.unwrap();
+        assert_eq!(
+            output,
+            "+OK\r\n$1\r\n1\r\n-ERR Protocol error: unbalanced quotes in request\r\n".as_bytes()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_replies_after_half_close() {
+        let mut stream = connect().await;
+        stream
+            .write_all("SET a 1\r\nGET a\r\nPING\r\n".as_bytes())
+            .await
+            .unwrap();
+        stream.shutdown().await.unwrap();
+        let mut output = Vec::new();
+        stream.read_to_end(&mut output).await.unwrap();
+        assert_eq!(output, "+OK\r\n$1\r\n1\r\n+PONG\r\n".as_bytes());
+    }
+
+    #[tokio::test]
+    async fn test_hello_switches_protocol() {
+        let mut stream = connect().await;
+        stream
+            .write_all("*2\r\n$5\r\nHELLO\r\n$1\r\n3\r\n".as_bytes())
+            .await
+            .unwrap();
+        let mut prefix = read_exact(&mut stream, 4).await;
+        assert_eq!(prefix, "%7\r\n".as_bytes());
+
+        // The rest of the map ends with the empty modules array
+        loop {
+            prefix.extend(read_exact(&mut stream, 1).await);
+            if prefix.ends_with(b"$7\r\nmodules\r\n*0\r\n") {
+                break;
+            }
+        }
+
+        stream
+            .write_all("*2\r\n$3\r\nGET\r\n$7\r\nmissing\r\n".as_bytes())
+            .await
+            .unwrap();
+        assert_eq!(read_exact(&mut stream, 3).await, "_\r\n".as_bytes());
+
+        stream
+            .write_all("*2\r\n$5\r\nHELLO\r\n$1\r\n2\r\n".as_bytes())
+            .await
+            .unwrap();
+        assert_eq!(read_exact(&mut stream, 5).await, "*14\r\n".as_bytes());
+    }
+
+    #[tokio::test]
+    async fn test_hello_unsupported_protocol() {
+        let mut stream = connect().await;
+        stream
+            .write_all("*2\r\n$5\r\nHELLO\r\n$1\r\n4\r\n*1\r\n$4\r\nPING\r\n".as_bytes())
+            .await
+            .unwrap();
+        let expected = "-NOPROTO unsupported protocol version\r\n+PONG\r\n";
+        assert_eq!(
+            read_exact(&mut stream, expected.len()).await,
+            expected.as_bytes()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_inline_commands() {
+        let mut stream = connect().await;
+        stream
+            .write_all("PING\n\r\nset greeting 'hello world'\r\nget greeting\r\n".as_bytes())
+            .await
+            .unwrap();
+        let expected = "+PONG\r\n+OK\r\n$11\r\nhello world\r\n";
+        assert_eq!(
+            read_exact(&mut stream, expected.len()).await,
+            expected.as_bytes()
+        );
+    }
+}
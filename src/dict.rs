@@ -0,0 +1,463 @@
+use std::{collections::hash_map::RandomState, hash::BuildHasher, mem};
+
+use crate::active_expire::Rng;
+
+// Hash table modelled on dict.c in Redis: power of two sized tables
+// of buckets, resized by rehashing a few buckets at a time so that
+// growing a large table never blocks the server, and scanned with a
+// reverse binary cursor that returns every key present during the
+// whole scan, even if the table is resized between two calls
+
+const INITIAL_SIZE: usize = 4;
+// Shrink once less than this percentage of the buckets are used
+const MIN_FILL: usize = 10;
+// Empty buckets visited per rehash step before giving up
+const EMPTY_VISITS: usize = 10;
+
+type Bucket<V> = Vec<(Vec<u8>, V)>;
+
+#[derive(Clone)]
+pub struct Dict<V> {
+    // During a rehash, buckets move from the first to the second table
+    tables: [Vec<Bucket<V>>; 2],
+    used: [usize; 2],
+    // Next bucket of the first table to move, while rehashing
+    rehash_index: Option<usize>,
+    hasher: RandomState,
+}
+
+impl<V> Default for Dict<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V> Dict<V> {
+    pub fn new() -> Self {
+        Self {
+            tables: [Vec::new(), Vec::new()],
+            used: [0, 0],
+            rehash_index: None,
+            hasher: RandomState::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.used[0] + self.used[1]
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn is_rehashing(&self) -> bool {
+        self.rehash_index.is_some()
+    }
+
+    fn hash(&self, key: &[u8]) -> u64 {
+        self.hasher.hash_one(key)
+    }
+
+    fn bucket_index(&self, table: usize, hash: u64) -> usize {
+        hash as usize & (self.tables[table].len() - 1)
+    }
+
+    // Table and bucket holding a key
+    fn find(&self, key: &[u8]) -> Option<(usize, usize, usize)> {
+        if self.is_empty() {
+            return None;
+        }
+        let hash = self.hash(key);
+        let tables = if self.is_rehashing() { 2 } else { 1 };
+        for table in 0..tables {
+            let bucket = self.bucket_index(table, hash);
+            if let Some(position) = self.tables[table][bucket]
+                .iter()
+                .position(|(k, _)| k == key)
+            {
+                return Some((table, bucket, position));
+            }
+        }
+        None
+    }
+
+    pub fn get(&self, key: &[u8]) -> Option<&V> {
+        let (table, bucket, position) = self.find(key)?;
+        Some(&self.tables[table][bucket][position].1)
+    }
+
+    pub fn get_mut(&mut self, key: &[u8]) -> Option<&mut V> {
+        self.rehash_step();
+        let (table, bucket, position) = self.find(key)?;
+        Some(&mut self.tables[table][bucket][position].1)
+    }
+
+    pub fn contains_key(&self, key: &[u8]) -> bool {
+        self.find(key).is_some()
+    }
+
+    // Insert a value, returning the one it replaced
+    pub fn insert(&mut self, key: Vec<u8>, value: V) -> Option<V> {
+        self.rehash_step();
+        if let Some((table, bucket, position)) = self.find(&key) {
+            return Some(mem::replace(
+                &mut self.tables[table][bucket][position].1,
+                value,
+            ));
+        }
+        self.add(key, value);
+        None
+    }
+
+    // Add a key known to be missing, to the table being filled
+    fn add(&mut self, key: Vec<u8>, value: V) -> (usize, usize, usize) {
+        self.expand_if_needed();
+        let table = if self.is_rehashing() { 1 } else { 0 };
+        let bucket = self.bucket_index(table, self.hash(&key));
+        self.tables[table][bucket].push((key, value));
+        self.used[table] += 1;
+        (table, bucket, self.tables[table][bucket].len() - 1)
+    }
+
+    pub fn get_or_insert_with(&mut self, key: &[u8], f: impl FnOnce() -> V) -> &mut V {
+        self.rehash_step();
+        let (table, bucket, position) = match self.find(key) {
+            Some(location) => location,
+            None => self.add(key.to_vec(), f()),
+        };
+        &mut self.tables[table][bucket][position].1
+    }
+
+    pub fn remove(&mut self, key: &[u8]) -> Option<V> {
+        self.rehash_step();
+        let (table, bucket, position) = self.find(key)?;
+        let (_, value) = self.tables[table][bucket].swap_remove(position);
+        self.used[table] -= 1;
+        self.shrink_if_needed();
+        Some(value)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&[u8], &V)> {
+        self.tables
+            .iter()
+            .flatten()
+            .flatten()
+            .map(|(key, value)| (key.as_slice(), value))
+    }
+
+    // Random entry, found as dictGetRandomKey does by picking random
+    // buckets until a non empty one, then an entry of that bucket
+    pub fn random_entry(&self, rng: &mut Rng) -> Option<(&[u8], &V)> {
+        if self.is_empty() {
+            return None;
+        }
+        let sizes = [self.tables[0].len(), self.tables[1].len()];
+        loop {
+            let index = rng.below(sizes[0] + sizes[1]);
+            let bucket = if index < sizes[0] {
+                &self.tables[0][index]
+            } else {
+                &self.tables[1][index - sizes[0]]
+            };
+            if !bucket.is_empty() {
+                let (key, value) = &bucket[rng.below(bucket.len())];
+                return Some((key, value));
+            }
+        }
+    }
+
+    fn expand_if_needed(&mut self) {
+        if self.is_rehashing() {
+            return;
+        }
+        if self.tables[0].is_empty() {
+            self.tables[0] = Self::empty_table(INITIAL_SIZE);
+        } else if self.used[0] >= self.tables[0].len() {
+            self.start_rehash((self.used[0] + 1).next_power_of_two());
+        }
+    }
+
+    fn shrink_if_needed(&mut self) {
+        if self.is_rehashing() {
+            return;
+        }
+        let size = self.tables[0].len();
+        if size > INITIAL_SIZE && self.used[0] * 100 / size < MIN_FILL {
+            self.start_rehash(self.used[0].next_power_of_two().max(INITIAL_SIZE));
+        }
+    }
+
+    fn empty_table(size: usize) -> Vec<Bucket<V>> {
+        let mut table = Vec::with_capacity(size);
+        table.resize_with(size, Vec::new);
+        table
+    }
+
+    fn start_rehash(&mut self, size: usize) {
+        self.tables[1] = Self::empty_table(size);
+        self.rehash_index = Some(0);
+    }
+
+    // Move one bucket to the new table, done on each access
+    // like the incremental rehashing of Redis
+    fn rehash_step(&mut self) {
+        self.rehash(1);
+    }
+
+    // Move up to `buckets` non empty buckets to the new table,
+    // returns true if there is still some rehashing to do
+    pub fn rehash(&mut self, buckets: usize) -> bool {
+        let mut index = match self.rehash_index {
+            Some(index) => index,
+            None => return false,
+        };
+        let mut empty_visits = buckets * EMPTY_VISITS;
+        for _ in 0..buckets {
+            while index < self.tables[0].len() && self.tables[0][index].is_empty() {
+                index += 1;
+                empty_visits -= 1;
+                if empty_visits == 0 {
+                    self.rehash_index = Some(index);
+                    return true;
+                }
+            }
+            if index == self.tables[0].len() {
+                break;
+            }
+            for (key, value) in mem::take(&mut self.tables[0][index]) {
+                let bucket = self.bucket_index(1, self.hash(&key));
+                self.tables[1][bucket].push((key, value));
+                self.used[0] -= 1;
+                self.used[1] += 1;
+            }
+            index += 1;
+        }
+
+        if self.used[0] == 0 {
+            self.tables[0] = mem::take(&mut self.tables[1]);
+            self.used = [self.used[1], 0];
+            self.rehash_index = None;
+            return false;
+        }
+        self.rehash_index = Some(index);
+        true
+    }
+
+    // Call `f` on the keys of the bucket pointed by the cursor and
+    // return the next cursor, 0 once the whole table was visited.
+    // The cursor counts with its bits reversed, so that buckets
+    // already visited in a smaller or larger table are not missed.
+    pub fn scan(&self, cursor: u64, mut f: impl FnMut(&[u8], &V)) -> u64 {
+        if self.is_empty() {
+            return 0;
+        }
+        let mut cursor = cursor;
+        let emit = |bucket: &Bucket<V>, f: &mut dyn FnMut(&[u8], &V)| {
+            for (key, value) in bucket.iter() {
+                f(key, value);
+            }
+        };
+
+        if !self.is_rehashing() {
+            let mask = self.tables[0].len() as u64 - 1;
+            emit(&self.tables[0][(cursor & mask) as usize], &mut f);
+            return Self::next_cursor(cursor, mask);
+        }
+
+        let (small, large) = if self.tables[0].len() <= self.tables[1].len() {
+            (&self.tables[0], &self.tables[1])
+        } else {
+            (&self.tables[1], &self.tables[0])
+        };
+        let small_mask = small.len() as u64 - 1;
+        let large_mask = large.len() as u64 - 1;
+        emit(&small[(cursor & small_mask) as usize], &mut f);
+        // visit the buckets of the larger table that expand
+        // the one of the smaller table
+        loop {
+            emit(&large[(cursor & large_mask) as usize], &mut f);
+            cursor = Self::next_cursor(cursor, large_mask);
+            if cursor & (small_mask ^ large_mask) == 0 {
+                break;
+            }
+        }
+        cursor
+    }
+
+    // Increment the reversed bits of the cursor covered by the mask
+    fn next_cursor(cursor: u64, mask: u64) -> u64 {
+        let cursor = cursor | !mask;
+        cursor.reverse_bits().wrapping_add(1).reverse_bits()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn key(i: usize) -> Vec<u8> {
+        format!("key:{}", i).into_bytes()
+    }
+
+    fn finish_rehash(dict: &mut Dict<usize>) {
+        while dict.rehash(100) {}
+    }
+
+    #[test]
+    fn test_insert_get_remove() {
+        let mut dict = Dict::new();
+        assert_eq!(dict.insert(b"a".to_vec(), 1), None);
+        assert_eq!(dict.insert(b"b".to_vec(), 2), None);
+        assert_eq!(dict.insert(b"a".to_vec(), 3), Some(1));
+        assert_eq!(dict.len(), 2);
+        assert_eq!(dict.get(b"a"), Some(&3));
+        assert!(dict.contains_key(b"b"));
+        *dict.get_mut(b"b").unwrap() += 10;
+        assert_eq!(dict.remove(b"b"), Some(12));
+        assert_eq!(dict.remove(b"b"), None);
+        assert_eq!(*dict.get_or_insert_with(b"c", || 5), 5);
+        assert_eq!(*dict.get_or_insert_with(b"c", || 6), 5);
+        assert_eq!(dict.len(), 2);
+    }
+
+    #[test]
+    fn test_grow_and_shrink() {
+        let mut dict = Dict::new();
+        for i in 0..1000 {
+            dict.insert(key(i), i);
+        }
+        finish_rehash(&mut dict);
+        assert_eq!(dict.tables[0].len(), 1024);
+        for i in 0..1000 {
+            assert_eq!(dict.get(&key(i)), Some(&i));
+        }
+        for i in 0..990 {
+            assert_eq!(dict.remove(&key(i)), Some(i));
+        }
+        finish_rehash(&mut dict);
+        assert!(dict.tables[0].len() <= 128);
+        assert_eq!(dict.len(), 10);
+        assert_eq!(dict.iter().count(), 10);
+    }
+
+    #[test]
+    fn test_lookup_while_rehashing() {
+        let mut dict = Dict::new();
+        for i in 0..65 {
+            dict.insert(key(i), i);
+        }
+        assert!(dict.is_rehashing());
+        for i in 0..65 {
+            assert_eq!(dict.get(&key(i)), Some(&i));
+        }
+    }
+
+    fn scan_all(dict: &Dict<usize>) -> Vec<usize> {
+        let mut values = Vec::new();
+        let mut cursor = 0;
+        loop {
+            cursor = dict.scan(cursor, |_, &value| values.push(value));
+            if cursor == 0 {
+                return values;
+            }
+        }
+    }
+
+    #[test]
+    fn test_scan_stable_table() {
+        let mut dict = Dict::new();
+        for i in 0..500 {
+            dict.insert(key(i), i);
+        }
+        finish_rehash(&mut dict);
+        let mut values = scan_all(&dict);
+        values.sort();
+        assert_eq!(values, (0..500).collect::<Vec<_>>());
+    }
+
+    // Keys present for the whole scan have to be returned, while
+    // other keys are added or removed and the table is resized
+    fn check_scan_while_resizing(initial: usize, added_per_call: usize, removed_per_call: usize) {
+        // the table is only modified during the first calls,
+        // a table growing forever would never be fully scanned
+        let mut calls = 0;
+        let mut resized = false;
+        let mut dict = Dict::new();
+        for i in 0..initial {
+            dict.insert(key(i), i);
+        }
+        let mut seen = HashSet::new();
+        let mut next_added = initial;
+        let mut next_removed = 0;
+        let mut cursor = 0;
+        loop {
+            resized |= dict.is_rehashing();
+            cursor = dict.scan(cursor, |_, &value| {
+                seen.insert(value);
+            });
+            if cursor == 0 {
+                break;
+            }
+            calls += 1;
+            if calls > 30 {
+                continue;
+            }
+            for _ in 0..added_per_call {
+                dict.insert(key(next_added), next_added);
+                next_added += 1;
+            }
+            for _ in 0..removed_per_call {
+                if next_removed + 10 < initial {
+                    dict.remove(&key(next_removed));
+                    next_removed += 1;
+                }
+            }
+        }
+        assert!(resized);
+        for i in next_removed..initial {
+            assert!(seen.contains(&i), "key {} was not returned", i);
+        }
+    }
+
+    #[test]
+    fn test_scan_while_growing() {
+        check_scan_while_resizing(100, 50, 0);
+    }
+
+    #[test]
+    fn test_scan_while_shrinking() {
+        check_scan_while_resizing(2000, 0, 66);
+    }
+
+    #[test]
+    fn test_scan_while_growing_and_shrinking() {
+        check_scan_while_resizing(1000, 30, 60);
+    }
+
+    #[test]
+    fn test_random_entry() {
+        let mut rng = Rng::new(7);
+        let mut dict = Dict::new();
+        assert!(dict.random_entry(&mut rng).is_none());
+        for i in 0..100 {
+            dict.insert(key(i), i);
+        }
+        dict.remove(&key(0));
+        // picks are spread over the table, even while rehashing
+        let mut seen = [false; 100];
+        for _ in 0..5000 {
+            let (key, &value) = dict.random_entry(&mut rng).unwrap();
+            assert_eq!(dict.get(key), Some(&value));
+            seen[value] = true;
+        }
+        assert!(!seen[0]);
+        assert!(seen[1..].iter().all(|&seen| seen));
+    }
+
+    #[test]
+    fn test_scan_empty() {
+        let dict: Dict<usize> = Dict::new();
+        assert_eq!(dict.scan(0, |_, _| panic!()), 0);
+    }
+}
@@ -0,0 +1,158 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::{
+    number::parse_i64,
+    storage_result::{StorageError, StorageResult},
+};
+
+// Condition that EXPIRE and its variants check
+// against the current expiry of the key
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ExpireCondition {
+    NX, // set only if the key has no expiry
+    XX, // set only if the key has an expiry
+    GT, // set only if the new expiry is greater than the current one
+    LT, // set only if the new expiry is less than the current one
+}
+
+// Expiry requested by a command, in milliseconds
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ExpireTime {
+    Relative(i64), // from now
+    Absolute(i64), // unix time
+}
+
+// Expiry of a key as reported by TTL and its variants
+#[derive(Debug, PartialEq)]
+pub enum KeyTTL {
+    Missing,
+    Persistent,
+    Expires(i64), // unix time in milliseconds
+}
+
+// Milliseconds elapsed since the unix epoch
+pub fn unix_time_ms(time: SystemTime) -> i64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+// Parse `<time> [NX|XX|GT|LT]`, the time being expressed in
+// units of `unit_ms` milliseconds (1000 for seconds).
+// XX can be combined with GT or LT, all conditions must hold.
+pub fn parse_expire_arguments(
+    arguments: &[Vec<u8>],
+    unit_ms: i64,
+    command: &str,
+) -> StorageResult<(i64, Vec<ExpireCondition>)> {
+    let value = parse_i64(&arguments[0]).ok_or(StorageError::NotAnInteger)?;
+    let value = value
+        .checked_mul(unit_ms)
+        .ok_or_else(|| StorageError::InvalidExpireTime(command.to_string()))?;
+
+    let mut conditions = Vec::new();
+    for argument in arguments[1..].iter() {
+        let condition = match argument.to_ascii_lowercase().as_slice() {
+            b"nx" => ExpireCondition::NX,
+            b"xx" => ExpireCondition::XX,
+            b"gt" => ExpireCondition::GT,
+            b"lt" => ExpireCondition::LT,
+            _ => {
+                return Err(StorageError::CommandSyntaxError(
+                    String::from_utf8_lossy(argument).into_owned(),
+                ))
+            }
+        };
+        if !conditions.contains(&condition) {
+            conditions.push(condition);
+        }
+    }
+
+    let has = |condition| conditions.contains(&condition);
+    if has(ExpireCondition::NX) && conditions.len() > 1 {
+        return Err(StorageError::IncompatibleOptions(String::from(
+            "NX and XX, GT or LT",
+        )));
+    }
+    if has(ExpireCondition::GT) && has(ExpireCondition::LT) {
+        return Err(StorageError::IncompatibleOptions(String::from("GT and LT")));
+    }
+    Ok((value, conditions))
+}
+
+// Whether the new expiry can replace the current one,
+// a key without expiry is considered to never expire
+pub fn conditions_allow(conditions: &[ExpireCondition], current: Option<i64>, new: i64) -> bool {
+    conditions
+        .iter()
+        .all(|condition| match (condition, current) {
+            (ExpireCondition::NX, current) => current.is_none(),
+            (ExpireCondition::XX, current) => current.is_some(),
+            (ExpireCondition::GT, None) => false,
+            (ExpireCondition::GT, Some(current)) => new > current,
+            (ExpireCondition::LT, None) => true,
+            (ExpireCondition::LT, Some(current)) => new < current,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ExpireCondition::*;
+
+    fn parse(arguments: &str) -> StorageResult<(i64, Vec<ExpireCondition>)> {
+        let arguments: Vec<Vec<u8>> = arguments
+            .split(' ')
+            .map(|a| a.as_bytes().to_vec())
+            .collect();
+        parse_expire_arguments(&arguments, 1000, "expire")
+    }
+
+    #[test]
+    fn test_parse_expire_arguments() {
+        assert_eq!(parse("10").unwrap(), (10000, vec![]));
+        assert_eq!(parse("-5").unwrap(), (-5000, vec![]));
+        assert_eq!(parse("10 nx").unwrap(), (10000, vec![NX]));
+        assert_eq!(parse("10 XX GT").unwrap(), (10000, vec![XX, GT]));
+        assert_eq!(parse("10 LT xx lt").unwrap(), (10000, vec![LT, XX]));
+    }
+
+    #[test]
+    fn test_parse_expire_arguments_errors() {
+        assert_eq!(parse("ten").unwrap_err(), StorageError::NotAnInteger);
+        assert_eq!(
+            parse("9223372036854775807").unwrap_err(),
+            StorageError::InvalidExpireTime(String::from("expire"))
+        );
+        assert_eq!(
+            parse("10 NX XX").unwrap_err(),
+            StorageError::IncompatibleOptions(String::from("NX and XX, GT or LT"))
+        );
+        assert_eq!(
+            parse("10 GT LT").unwrap_err(),
+            StorageError::IncompatibleOptions(String::from("GT and LT"))
+        );
+        assert_eq!(
+            parse("10 KEEP").unwrap_err(),
+            StorageError::CommandSyntaxError(String::from("KEEP"))
+        );
+    }
+
+    #[test]
+    fn test_conditions_allow() {
+        assert!(conditions_allow(&[], Some(10), 5));
+        assert!(conditions_allow(&[NX], None, 5));
+        assert!(!conditions_allow(&[NX], Some(10), 5));
+        assert!(conditions_allow(&[XX], Some(10), 5));
+        assert!(!conditions_allow(&[XX], None, 5));
+        assert!(conditions_allow(&[GT], Some(10), 20));
+        assert!(!conditions_allow(&[GT], Some(10), 5));
+        assert!(!conditions_allow(&[GT], None, 5));
+        assert!(conditions_allow(&[LT], Some(10), 5));
+        assert!(!conditions_allow(&[LT], Some(10), 20));
+        assert!(conditions_allow(&[LT], None, 5));
+        assert!(!conditions_allow(&[XX, LT], None, 5));
+        assert!(conditions_allow(&[XX, LT], Some(10), 5));
+    }
+}
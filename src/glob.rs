@@ -0,0 +1,345 @@
+// Glob-style matching with the semantics of stringmatchlen in Redis,
+// shared by KEYS, SCAN MATCH and any later command taking a pattern:
+//
+// - `*` matches any sequence of bytes, `?` any single byte
+// - `[abc]` matches one of the bytes, `[^abc]` any other byte,
+//   `[a-z]` a range and `\` escapes a byte inside or outside a class
+// - an unterminated class like `[ab` runs to the end of the pattern
+//
+// An empty string is only matched by an empty pattern, even `*`,
+// as in Redis.
+
+// Recursion depth at which matching gives up, like Redis does
+// to protect the stack against patterns full of stars
+const MAX_NESTING: usize = 1000;
+
+pub fn string_match(pattern: &[u8], string: &[u8], nocase: bool) -> bool {
+    let mut skip_longer_matches = false;
+    match_impl(pattern, string, nocase, &mut skip_longer_matches, 0)
+}
+
+fn lower(c: u8, nocase: bool) -> u8 {
+    if nocase {
+        c.to_ascii_lowercase()
+    } else {
+        c
+    }
+}
+
+fn match_impl(
+    mut pattern: &[u8],
+    mut string: &[u8],
+    nocase: bool,
+    skip_longer_matches: &mut bool,
+    nesting: usize,
+) -> bool {
+    if nesting > MAX_NESTING {
+        return false;
+    }
+
+    while !pattern.is_empty() && !string.is_empty() {
+        match pattern[0] {
+            b'*' => {
+                while pattern.len() > 1 && pattern[1] == b'*' {
+                    pattern = &pattern[1..];
+                }
+                if pattern.len() == 1 {
+                    return true;
+                }
+                while !string.is_empty() {
+                    if match_impl(
+                        &pattern[1..],
+                        string,
+                        nocase,
+                        skip_longer_matches,
+                        nesting + 1,
+                    ) {
+                        return true;
+                    }
+                    if *skip_longer_matches {
+                        return false;
+                    }
+                    string = &string[1..];
+                }
+                // The rest of the pattern matches nowhere in the string,
+                // so giving a longer match to an earlier star, which
+                // would start the rest later in the string, cannot help.
+                // This keeps patterns like `*a*a*a*b` from backtracking
+                // exponentially.
+                *skip_longer_matches = true;
+                return false;
+            }
+            b'?' => string = &string[1..],
+            b'[' => {
+                let (matched, rest) = match_class(pattern, string[0], nocase);
+                if !matched {
+                    return false;
+                }
+                // `rest` starts at the closing bracket, consumed below
+                pattern = rest;
+                string = &string[1..];
+            }
+            c => {
+                let (c, rest) = if c == b'\\' && pattern.len() >= 2 {
+                    (pattern[1], &pattern[1..])
+                } else {
+                    (c, pattern)
+                };
+                if lower(c, nocase) != lower(string[0], nocase) {
+                    return false;
+                }
+                pattern = rest;
+                string = &string[1..];
+            }
+        }
+        pattern = &pattern[1..];
+        if string.is_empty() {
+            while !pattern.is_empty() && pattern[0] == b'*' {
+                pattern = &pattern[1..];
+            }
+            break;
+        }
+    }
+    pattern.is_empty() && string.is_empty()
+}
+
+// Match a byte against the class starting with `[`. Returns whether
+// it matched and the pattern from the last byte of the class, which
+// is the closing bracket unless the class is unterminated.
+fn match_class(class: &[u8], c: u8, nocase: bool) -> (bool, &[u8]) {
+    let negated = class.get(1) == Some(&b'^');
+    let mut pattern = if negated { &class[2..] } else { &class[1..] };
+    let mut matched = false;
+    loop {
+        if pattern.len() >= 2 && pattern[0] == b'\\' {
+            // escaped bytes are compared as is, even with nocase
+            pattern = &pattern[1..];
+            matched |= pattern[0] == c;
+        } else if pattern.is_empty() {
+            // unterminated class, step back on its last byte
+            // so that the whole pattern ends up consumed
+            return (matched != negated, &class[class.len() - 1..]);
+        } else if pattern[0] == b']' {
+            break;
+        } else if pattern.len() >= 3 && pattern[1] == b'-' {
+            let (low, high) = if pattern[0] <= pattern[2] {
+                (pattern[0], pattern[2])
+            } else {
+                (pattern[2], pattern[0])
+            };
+            let c = lower(c, nocase);
+            matched |= (lower(low, nocase)..=lower(high, nocase)).contains(&c);
+            pattern = &pattern[2..];
+        } else {
+            matched |= lower(pattern[0], nocase) == lower(c, nocase);
+        }
+        pattern = &pattern[1..];
+    }
+    (matched != negated, pattern)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::active_expire::Rng;
+
+    fn matches(pattern: &str, string: &str) -> bool {
+        string_match(pattern.as_bytes(), string.as_bytes(), false)
+    }
+
+    #[test]
+    fn test_wildcards() {
+        assert!(matches("*", "anything"));
+        assert!(matches("h?llo", "hello"));
+        assert!(!matches("h?llo", "hllo"));
+        assert!(matches("h*llo", "hllo"));
+        assert!(matches("h*llo", "heeeello"));
+        assert!(matches("a**b*", "ab"));
+        assert!(!matches("a*b", "acbd"));
+        assert!(matches("", ""));
+        assert!(!matches("*", ""));
+        assert!(!matches("", "a"));
+    }
+
+    #[test]
+    fn test_classes() {
+        assert!(matches("h[ae]llo", "hallo"));
+        assert!(!matches("h[ae]llo", "hillo"));
+        assert!(matches("h[^e]llo", "hallo"));
+        assert!(!matches("h[^e]llo", "hello"));
+        assert!(matches("h[a-b]llo", "hbllo"));
+        assert!(matches("h[b-a]llo", "hallo"));
+        assert!(matches("[\\]]", "]"));
+        assert!(matches("[a-]", "]"));
+        assert!(matches("a[bc", "ab"));
+        assert!(matches("a[bc", "ac"));
+        assert!(!matches("a[", "a"));
+    }
+
+    #[test]
+    fn test_escapes() {
+        assert!(matches("user:\\*", "user:*"));
+        assert!(!matches("user:\\*", "user:1"));
+        assert!(matches("\\?", "?"));
+        assert!(matches("a\\", "a\\"));
+    }
+
+    #[test]
+    fn test_nocase() {
+        assert!(string_match(b"HeLLo", b"hello", true));
+        assert!(string_match(b"h[A-Z]llo", b"hello", true));
+        assert!(string_match(b"h[^X]llo", b"hello", true));
+        assert!(!string_match(b"h[^E]llo", b"hello", true));
+        assert!(!string_match(b"HeLLo", b"hello", false));
+        assert!(!string_match(b"[\\A]", b"a", true));
+    }
+
+    #[test]
+    fn test_binary() {
+        assert!(string_match(b"\x00*\xff", b"\x00\x01\x02\xff", false));
+        assert!(string_match(b"[\x00-\x10]", b"\x05", false));
+    }
+
+    #[test]
+    fn test_pathological_backtracking() {
+        let pattern = "*a".repeat(30) + "b";
+        let string = "a".repeat(100);
+        assert!(!matches(&pattern, &string));
+    }
+
+    #[test]
+    fn test_nesting_limit() {
+        let pattern = "a*".repeat(2000) + "b";
+        let string = "a".repeat(2000) + "b";
+        assert!(!matches(&pattern, &string));
+        let pattern = "a*".repeat(100) + "b";
+        let string = "a".repeat(100) + "b";
+        assert!(matches(&pattern, &string));
+    }
+
+    // Reference implementation: the pattern is parsed into tokens,
+    // which are matched by plain exponential backtracking
+
+    #[derive(Debug)]
+    enum Item {
+        Byte(u8),
+        Escaped(u8),
+        Range(u8, u8),
+    }
+
+    #[derive(Debug)]
+    enum Token {
+        Star,
+        Any,
+        Literal(u8),
+        Class(bool, Vec<Item>),
+    }
+
+    fn tokenize(pattern: &[u8]) -> Vec<Token> {
+        let mut tokens = Vec::new();
+        let mut i = 0;
+        while i < pattern.len() {
+            match pattern[i] {
+                b'*' => tokens.push(Token::Star),
+                b'?' => tokens.push(Token::Any),
+                b'[' => {
+                    i += 1;
+                    let negated = pattern.get(i) == Some(&b'^');
+                    if negated {
+                        i += 1;
+                    }
+                    let mut items = Vec::new();
+                    loop {
+                        if pattern.len() - i >= 2 && pattern[i] == b'\\' {
+                            items.push(Item::Escaped(pattern[i + 1]));
+                            i += 2;
+                        } else if i == pattern.len() || pattern[i] == b']' {
+                            break;
+                        } else if pattern.len() - i >= 3 && pattern[i + 1] == b'-' {
+                            items.push(Item::Range(pattern[i], pattern[i + 2]));
+                            i += 3;
+                        } else {
+                            items.push(Item::Byte(pattern[i]));
+                            i += 1;
+                        }
+                    }
+                    tokens.push(Token::Class(negated, items));
+                }
+                b'\\' if i + 1 < pattern.len() => {
+                    i += 1;
+                    tokens.push(Token::Literal(pattern[i]));
+                }
+                c => tokens.push(Token::Literal(c)),
+            }
+            i += 1;
+        }
+        tokens
+    }
+
+    fn token_matches(token: &Token, c: u8, nocase: bool) -> bool {
+        let eq = |a: u8, b: u8| lower(a, nocase) == lower(b, nocase);
+        match token {
+            Token::Star => unreachable!(),
+            Token::Any => true,
+            Token::Literal(l) => eq(*l, c),
+            Token::Class(negated, items) => {
+                let matched = items.iter().any(|item| match item {
+                    Item::Byte(b) => eq(*b, c),
+                    Item::Escaped(b) => *b == c,
+                    Item::Range(a, b) => {
+                        let (low, high) = (*a.min(b), *a.max(b));
+                        (lower(low, nocase)..=lower(high, nocase)).contains(&lower(c, nocase))
+                    }
+                });
+                matched != *negated
+            }
+        }
+    }
+
+    fn reference_tokens(tokens: &[Token], string: &[u8], nocase: bool) -> bool {
+        match tokens.split_first() {
+            None => string.is_empty(),
+            Some((Token::Star, rest)) => {
+                (0..=string.len()).any(|skip| reference_tokens(rest, &string[skip..], nocase))
+            }
+            Some((token, rest)) => {
+                !string.is_empty()
+                    && token_matches(token, string[0], nocase)
+                    && reference_tokens(rest, &string[1..], nocase)
+            }
+        }
+    }
+
+    fn reference(pattern: &[u8], string: &[u8], nocase: bool) -> bool {
+        if string.is_empty() {
+            return pattern.is_empty();
+        }
+        reference_tokens(&tokenize(pattern), string, nocase)
+    }
+
+    fn random_bytes(rng: &mut Rng, alphabet: &[u8], max_length: usize) -> Vec<u8> {
+        let length = rng.below(max_length + 1);
+        (0..length)
+            .map(|_| alphabet[rng.below(alphabet.len())])
+            .collect()
+    }
+
+    #[test]
+    fn test_against_reference() {
+        let mut rng = Rng::new(2024);
+        for _ in 0..20000 {
+            let pattern = random_bytes(&mut rng, b"abA*?[]^-\\", 8);
+            let string = random_bytes(&mut rng, b"abA]-*\\", 6);
+            for nocase in [false, true] {
+                assert_eq!(
+                    string_match(&pattern, &string, nocase),
+                    reference(&pattern, &string, nocase),
+                    "pattern {:?} string {:?} nocase {}",
+                    String::from_utf8_lossy(&pattern),
+                    String::from_utf8_lossy(&string),
+                    nocase
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,416 @@
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    fmt,
+    time::SystemTime,
+};
+
+use crate::{active_expire::Rng, dict::Dict, listpack::Listpack};
+
+// Same defaults as hash-max-listpack-entries and hash-max-listpack-value
+// in Redis: past either limit a hash is converted to a hash table
+const LISTPACK_MAX_ENTRIES: usize = 128;
+const LISTPACK_MAX_VALUE: usize = 64;
+
+// Latest expiry of a field, in unix time milliseconds, as Redis
+// keeps field deadlines on 48 bits
+pub const MAX_FIELD_EXPIRY_MS: i64 = (1 << 48) - 1;
+
+// Fields of a hash with their values
+pub type Fields = Vec<(Vec<u8>, Vec<u8>)>;
+
+// A listpack of fields followed by their values while the hash is
+// small, where looking up a field is a cheap scan of a single buffer,
+// and a hash table once large. It never goes back to a listpack.
+#[derive(Clone)]
+enum Encoding {
+    Listpack(Listpack),
+    Dict(Dict<Vec<u8>>),
+}
+
+impl Default for Encoding {
+    fn default() -> Self {
+        Encoding::Listpack(Listpack::new())
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct Hash {
+    encoding: Encoding,
+    // Deadlines of the fields with an expiry, also kept in order
+    // so that the expired fields are found without a full scan
+    expiries: HashMap<Vec<u8>, SystemTime>,
+    deadlines: BTreeSet<(SystemTime, Vec<u8>)>,
+}
+
+impl Hash {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        match &self.encoding {
+            Encoding::Listpack(listpack) => listpack.len() / 2,
+            Encoding::Dict(dict) => dict.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // Number of allocations holding the hash
+    pub fn allocations(&self) -> usize {
+        let fields = match &self.encoding {
+            Encoding::Listpack(_) => 1,
+            Encoding::Dict(dict) => dict.len(),
+        };
+        fields + self.expiries.len()
+    }
+
+    // Index in the listpack of the value of a field
+    fn listpack_position(listpack: &Listpack, field: &[u8]) -> Option<usize> {
+        listpack
+            .iter()
+            .step_by(2)
+            .position(|f| f == field)
+            .map(|position| position * 2 + 1)
+    }
+
+    pub fn get(&self, field: &[u8]) -> Option<&[u8]> {
+        match &self.encoding {
+            Encoding::Listpack(listpack) => {
+                let position = Self::listpack_position(listpack, field)?;
+                listpack.get(position)
+            }
+            Encoding::Dict(dict) => dict.get(field).map(Vec::as_slice),
+        }
+    }
+
+    pub fn contains(&self, field: &[u8]) -> bool {
+        self.get(field).is_some()
+    }
+
+    // Set the value of a field, keeping its expiry if it has one.
+    // Returns true if the field is new.
+    pub fn insert(&mut self, field: &[u8], value: &[u8]) -> bool {
+        let new = match &mut self.encoding {
+            Encoding::Listpack(listpack) => match Self::listpack_position(listpack, field) {
+                Some(position) => {
+                    listpack.replace(position, value);
+                    false
+                }
+                None => {
+                    listpack.push_back(field);
+                    listpack.push_back(value);
+                    true
+                }
+            },
+            Encoding::Dict(dict) => dict.insert(field.to_vec(), value.to_vec()).is_none(),
+        };
+        if field.len() > LISTPACK_MAX_VALUE
+            || value.len() > LISTPACK_MAX_VALUE
+            || self.len() > LISTPACK_MAX_ENTRIES
+        {
+            self.convert_to_dict();
+        }
+        new
+    }
+
+    // Remove a field with its expiry, returns true if it existed
+    pub fn remove(&mut self, field: &[u8]) -> bool {
+        self.persist(field);
+        match &mut self.encoding {
+            Encoding::Listpack(listpack) => match Self::listpack_position(listpack, field) {
+                Some(position) => {
+                    listpack.remove(position);
+                    listpack.remove(position - 1);
+                    true
+                }
+                None => false,
+            },
+            Encoding::Dict(dict) => dict.remove(field).is_some(),
+        }
+    }
+
+    fn convert_to_dict(&mut self) {
+        if let Encoding::Listpack(_) = self.encoding {
+            let mut dict = Dict::new();
+            for (field, value) in self.iter() {
+                dict.insert(field.to_vec(), value.to_vec());
+            }
+            self.encoding = Encoding::Dict(dict);
+        }
+    }
+
+    pub fn expiry(&self, field: &[u8]) -> Option<SystemTime> {
+        self.expiries.get(field).copied()
+    }
+
+    // Set the expiry of a field, which has to be in the hash
+    pub fn set_expiry(&mut self, field: &[u8], deadline: SystemTime) {
+        self.persist(field);
+        self.expiries.insert(field.to_vec(), deadline);
+        self.deadlines.insert((deadline, field.to_vec()));
+    }
+
+    // Remove the expiry of a field, returns true if it had one
+    pub fn persist(&mut self, field: &[u8]) -> bool {
+        match self.expiries.remove(field) {
+            Some(deadline) => self.deadlines.remove(&(deadline, field.to_vec())),
+            None => false,
+        }
+    }
+
+    // Earliest deadline of the fields
+    pub fn next_deadline(&self) -> Option<SystemTime> {
+        self.deadlines.first().map(|(deadline, _)| *deadline)
+    }
+
+    // Remove the fields whose deadline has passed,
+    // returns the number of fields removed
+    pub fn remove_expired(&mut self, now: SystemTime) -> usize {
+        let mut removed = 0;
+        while let Some((deadline, field)) = self.deadlines.first().cloned() {
+            if deadline > now {
+                break;
+            }
+            self.remove(&field);
+            removed += 1;
+        }
+        removed
+    }
+
+    // Fields with their values
+    pub fn iter(&self) -> Box<dyn Iterator<Item = (&[u8], &[u8])> + '_> {
+        match &self.encoding {
+            Encoding::Listpack(listpack) => {
+                let mut entries = listpack.iter();
+                Box::new(std::iter::from_fn(move || {
+                    Some((entries.next()?, entries.next()?))
+                }))
+            }
+            Encoding::Dict(dict) => {
+                Box::new(dict.iter().map(|(field, value)| (field, value.as_slice())))
+            }
+        }
+    }
+
+    // Call `f` on the fields of the bucket pointed by the cursor, as
+    // Dict::scan does, returning the next cursor. A listpack is
+    // visited at once, the cursor being 0 right away as in Redis.
+    pub fn scan(&self, cursor: u64, mut f: impl FnMut(&[u8], &[u8])) -> u64 {
+        match &self.encoding {
+            Encoding::Listpack(_) => {
+                for (field, value) in self.iter() {
+                    f(field, value);
+                }
+                0
+            }
+            Encoding::Dict(dict) => dict.scan(cursor, |field, value| f(field, value)),
+        }
+    }
+
+    fn random_entry(&self, rng: &mut Rng) -> Option<(&[u8], &[u8])> {
+        match &self.encoding {
+            Encoding::Listpack(_) if self.is_empty() => None,
+            Encoding::Listpack(listpack) => {
+                let position = rng.below(self.len()) * 2;
+                Some((listpack.get(position)?, listpack.get(position + 1)?))
+            }
+            Encoding::Dict(dict) => dict
+                .random_entry(rng)
+                .map(|(field, value)| (field, value.as_slice())),
+        }
+    }
+
+    // Random fields with their values as HRANDFIELD picks them:
+    // `count` distinct ones, or all of them if there are fewer,
+    // unless `unique` is false, in which case a field can be
+    // picked several times
+    pub fn random_fields(&self, count: usize, unique: bool, rng: &mut Rng) -> Fields {
+        let to_owned = |(field, value): (&[u8], &[u8])| (field.to_vec(), value.to_vec());
+        if self.is_empty() {
+            return Vec::new();
+        }
+        if !unique {
+            return (0..count)
+                .filter_map(|_| self.random_entry(rng).map(to_owned))
+                .collect();
+        }
+        if count >= self.len() {
+            return self.iter().map(to_owned).collect();
+        }
+        // when most of the fields are requested, removing random ones
+        // from all of them is faster than picking until enough differ
+        if count * 3 > self.len() {
+            let mut fields: Fields = self.iter().map(to_owned).collect();
+            while fields.len() > count {
+                fields.swap_remove(rng.below(fields.len()));
+            }
+            return fields;
+        }
+        let mut picked = HashSet::new();
+        let mut fields = Vec::with_capacity(count);
+        while fields.len() < count {
+            if let Some((field, value)) = self.random_entry(rng) {
+                if picked.insert(field) {
+                    fields.push((field.to_vec(), value.to_vec()));
+                }
+            }
+        }
+        fields
+    }
+}
+
+impl fmt::Debug for Hash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.iter().map(|(field, value)| {
+                (
+                    String::from_utf8_lossy(field),
+                    String::from_utf8_lossy(value),
+                )
+            }))
+            .finish()
+    }
+}
+
+// Hashes are equal when their fields, values and expiries are,
+// whatever their encoding and the order of their fields
+impl PartialEq for Hash {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && self.expiries == other.expiries
+            && self
+                .iter()
+                .all(|(field, value)| other.get(field) == Some(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash(count: usize) -> Hash {
+        let mut hash = Hash::new();
+        for i in 0..count {
+            hash.insert(
+                format!("field:{}", i).as_bytes(),
+                format!("value:{}", i).as_bytes(),
+            );
+        }
+        hash
+    }
+
+    #[test]
+    fn test_insert_get_remove() {
+        let mut hash = Hash::new();
+        assert!(hash.insert(b"a", b"1"));
+        assert!(hash.insert(b"b", b"2"));
+        assert!(!hash.insert(b"a", b"3"));
+        assert_eq!(hash.len(), 2);
+        assert_eq!(hash.get(b"a"), Some(b"3".as_slice()));
+        assert_eq!(hash.get(b"c"), None);
+        assert!(hash.remove(b"a"));
+        assert!(!hash.remove(b"a"));
+        assert_eq!(hash.get(b"b"), Some(b"2".as_slice()));
+        assert_eq!(hash.len(), 1);
+        assert!(matches!(hash.encoding, Encoding::Listpack(_)));
+    }
+
+    #[test]
+    fn test_converts_when_large() {
+        let mut hash = hash(LISTPACK_MAX_ENTRIES);
+        assert!(matches!(hash.encoding, Encoding::Listpack(_)));
+        hash.insert(b"one more", b"value");
+        assert!(matches!(hash.encoding, Encoding::Dict(_)));
+        assert_eq!(hash.len(), LISTPACK_MAX_ENTRIES + 1);
+        assert_eq!(hash.get(b"field:7"), Some(b"value:7".as_slice()));
+
+        let mut hash = Hash::new();
+        hash.insert(b"field", &[b'x'; LISTPACK_MAX_VALUE + 1]);
+        assert!(matches!(hash.encoding, Encoding::Dict(_)));
+    }
+
+    #[test]
+    fn test_equality_ignores_encoding() {
+        let large = {
+            let mut hash = hash(LISTPACK_MAX_ENTRIES + 1);
+            hash.remove(format!("field:{}", LISTPACK_MAX_ENTRIES).as_bytes());
+            hash
+        };
+        assert!(matches!(large.encoding, Encoding::Dict(_)));
+        assert_eq!(large, hash(LISTPACK_MAX_ENTRIES));
+        assert_ne!(large, hash(3));
+    }
+
+    #[test]
+    fn test_field_expiry() {
+        let now = SystemTime::now();
+        let later = |secs| now + std::time::Duration::from_secs(secs);
+        let mut hash = hash(200);
+        hash.set_expiry(b"field:1", later(20));
+        hash.set_expiry(b"field:2", later(10));
+        hash.set_expiry(b"field:3", later(30));
+        hash.set_expiry(b"field:1", later(5));
+        assert_eq!(hash.expiry(b"field:1"), Some(later(5)));
+        assert_eq!(hash.next_deadline(), Some(later(5)));
+
+        // updating a value keeps its expiry, removing the field drops it
+        hash.insert(b"field:1", b"new");
+        assert_eq!(hash.expiry(b"field:1"), Some(later(5)));
+        assert!(hash.remove(b"field:1"));
+        assert_eq!(hash.expiry(b"field:1"), None);
+        assert_eq!(hash.next_deadline(), Some(later(10)));
+
+        assert!(hash.persist(b"field:3"));
+        assert!(!hash.persist(b"field:3"));
+        assert_eq!(hash.remove_expired(later(9)), 0);
+        assert_eq!(hash.remove_expired(later(100)), 1);
+        assert_eq!(hash.len(), 198);
+        assert!(!hash.contains(b"field:2"));
+        assert!(hash.contains(b"field:3"));
+        assert_eq!(hash.next_deadline(), None);
+    }
+
+    #[test]
+    fn test_scan() {
+        let small = hash(10);
+        let mut fields = Vec::new();
+        assert_eq!(small.scan(0, |field, _| fields.push(field.to_vec())), 0);
+        assert_eq!(fields.len(), 10);
+
+        let large = hash(1000);
+        let mut fields = HashSet::new();
+        let mut cursor = 0;
+        loop {
+            cursor = large.scan(cursor, |field, value| {
+                assert_eq!(large.get(field), Some(value));
+                fields.insert(field.to_vec());
+            });
+            if cursor == 0 {
+                break;
+            }
+        }
+        assert_eq!(fields.len(), 1000);
+    }
+
+    #[test]
+    fn test_random_fields() {
+        let mut rng = Rng::new(42);
+        for hash in [hash(50), hash(1000)] {
+            for count in [1, 10, 40, 50, 2000] {
+                let fields = hash.random_fields(count, true, &mut rng);
+                assert_eq!(fields.len(), count.min(hash.len()));
+                let distinct: HashSet<&Vec<u8>> = fields.iter().map(|(field, _)| field).collect();
+                assert_eq!(distinct.len(), fields.len());
+                assert!(fields
+                    .iter()
+                    .all(|(field, value)| hash.get(field) == Some(value.as_slice())));
+            }
+            let fields = hash.random_fields(3000, false, &mut rng);
+            assert_eq!(fields.len(), 3000);
+        }
+        assert!(Hash::new().random_fields(5, false, &mut rng).is_empty());
+    }
+}
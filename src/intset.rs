@@ -0,0 +1,161 @@
+// Sorted array of integers modelled on intset.c in Redis: the values
+// are packed little endian with the smallest width that holds all of
+// them, upgraded to a wider one when a larger value is added, so that
+// small integer sets take a single compact buffer searched by bisection
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Intset {
+    width: usize, // bytes per value: 2, 4 or 8
+    contents: Vec<u8>,
+}
+
+impl Default for Intset {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Smallest width that holds a value
+fn width_of(value: i64) -> usize {
+    if i16::try_from(value).is_ok() {
+        2
+    } else if i32::try_from(value).is_ok() {
+        4
+    } else {
+        8
+    }
+}
+
+impl Intset {
+    pub fn new() -> Self {
+        Self {
+            width: 2,
+            contents: Vec::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.contents.len() / self.width
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.contents.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> Option<i64> {
+        let start = index * self.width;
+        let bytes = self.contents.get(start..start + self.width)?;
+        Some(match self.width {
+            2 => i16::from_le_bytes([bytes[0], bytes[1]]) as i64,
+            4 => i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as i64,
+            _ => i64::from_le_bytes(bytes.try_into().unwrap()),
+        })
+    }
+
+    fn encode(value: i64, width: usize) -> Vec<u8> {
+        value.to_le_bytes()[..width].to_vec()
+    }
+
+    // Position of the value, or where it would be inserted
+    fn search(&self, value: i64) -> Result<usize, usize> {
+        let (mut low, mut high) = (0, self.len());
+        while low < high {
+            let middle = (low + high) / 2;
+            match self.get(middle).unwrap().cmp(&value) {
+                std::cmp::Ordering::Less => low = middle + 1,
+                std::cmp::Ordering::Greater => high = middle,
+                std::cmp::Ordering::Equal => return Ok(middle),
+            }
+        }
+        Err(low)
+    }
+
+    pub fn contains(&self, value: i64) -> bool {
+        width_of(value) <= self.width && self.search(value).is_ok()
+    }
+
+    // Returns true if the value was not in the set
+    pub fn insert(&mut self, value: i64) -> bool {
+        if width_of(value) > self.width {
+            self.upgrade(width_of(value));
+        }
+        match self.search(value) {
+            Ok(_) => false,
+            Err(position) => {
+                let start = position * self.width;
+                self.contents
+                    .splice(start..start, Self::encode(value, self.width));
+                true
+            }
+        }
+    }
+
+    // Returns true if the value was in the set
+    pub fn remove(&mut self, value: i64) -> bool {
+        if width_of(value) > self.width {
+            return false;
+        }
+        match self.search(value) {
+            Ok(position) => {
+                let start = position * self.width;
+                self.contents.drain(start..start + self.width);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    // Widen every value, which never goes back to a smaller width
+    fn upgrade(&mut self, width: usize) {
+        let contents = self
+            .iter()
+            .flat_map(|value| Self::encode(value, width))
+            .collect();
+        self.contents = contents;
+        self.width = width;
+    }
+
+    // Values in increasing order
+    pub fn iter(&self) -> impl Iterator<Item = i64> + '_ {
+        (0..self.len()).filter_map(|index| self.get(index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_remove() {
+        let mut intset = Intset::new();
+        for value in [5, -3, 12, 5, 0] {
+            intset.insert(value);
+        }
+        assert_eq!(intset.iter().collect::<Vec<_>>(), vec![-3, 0, 5, 12]);
+        assert!(intset.contains(12));
+        assert!(!intset.contains(7));
+        assert!(intset.remove(0));
+        assert!(!intset.remove(0));
+        assert!(!intset.remove(i64::MAX));
+        assert_eq!(intset.iter().collect::<Vec<_>>(), vec![-3, 5, 12]);
+        assert_eq!(intset.width, 2);
+    }
+
+    #[test]
+    fn test_upgrade() {
+        let mut intset = Intset::new();
+        intset.insert(1);
+        intset.insert(-1);
+        assert!(intset.insert(100000));
+        assert_eq!(intset.width, 4);
+        assert!(intset.insert(i64::MIN));
+        assert_eq!(intset.width, 8);
+        assert_eq!(
+            intset.iter().collect::<Vec<_>>(),
+            vec![i64::MIN, -1, 1, 100000]
+        );
+        assert_eq!(intset.len(), 4);
+        assert!(intset.contains(-1));
+        assert!(!intset.contains(i64::MAX));
+    }
+}
@@ -0,0 +1,13 @@
+use crate::storage::StorageData;
+
+// Same threshold as LAZYFREE_THRESHOLD in Redis: values needing
+// more allocations to be freed are dropped in the background
+const LAZYFREE_THRESHOLD: usize = 64;
+
+// Drop the data of a removed key without blocking the server
+// when freeing it is costly
+pub fn free(data: StorageData) {
+    if data.value.free_effort() > LAZYFREE_THRESHOLD {
+        tokio::task::spawn_blocking(move || drop(data));
+    }
+}
@@ -0,0 +1,353 @@
+use std::fmt;
+
+// Compact sequence of byte strings held in a single buffer, like the
+// listpack of Redis. Each entry is stored as its length, its bytes,
+// then the size of the two so the buffer can be walked from its end:
+//
+//   <length> <bytes> <backlen>
+//
+// The length is a little endian base 128 varint. The backlen holds
+// the same 7 bit groups in reverse, the last byte being the lowest
+// one, with the high bit set when more groups follow on the left.
+#[derive(Clone, Default)]
+pub struct Listpack {
+    buffer: Vec<u8>,
+    len: usize,
+}
+
+fn varint_size(mut value: usize) -> usize {
+    let mut size = 1;
+    while value >= 0x80 {
+        value >>= 7;
+        size += 1;
+    }
+    size
+}
+
+fn encode_entry(value: &[u8], output: &mut Vec<u8>) {
+    let mut length = value.len();
+    while length >= 0x80 {
+        output.push((length & 0x7f) as u8 | 0x80);
+        length >>= 7;
+    }
+    output.push(length as u8);
+    output.extend_from_slice(value);
+
+    let mut backlen = varint_size(value.len()) + value.len();
+    let mut groups = Vec::with_capacity(3);
+    loop {
+        groups.push((backlen & 0x7f) as u8);
+        backlen >>= 7;
+        if backlen == 0 {
+            break;
+        }
+    }
+    let last = groups.len() - 1;
+    for (i, group) in groups.iter().enumerate().rev() {
+        output.push(if i < last { group | 0x80 } else { *group });
+    }
+}
+
+// Bytes of the entry starting at `offset` and the offset of the next one
+fn decode_entry(buffer: &[u8], offset: usize) -> (&[u8], usize) {
+    let mut length = 0;
+    let mut shift = 0;
+    let mut position = offset;
+    loop {
+        let byte = buffer[position];
+        position += 1;
+        length |= ((byte & 0x7f) as usize) << shift;
+        if byte & 0x80 == 0 {
+            break;
+        }
+        shift += 7;
+    }
+    let value = &buffer[position..position + length];
+    let backlen = varint_size(position - offset + length);
+    (value, position + length + backlen)
+}
+
+// Offset of the entry ending at `end`
+fn previous_entry(buffer: &[u8], end: usize) -> usize {
+    let mut size = 0;
+    let mut shift = 0;
+    let mut position = end;
+    loop {
+        position -= 1;
+        let byte = buffer[position];
+        size |= ((byte & 0x7f) as usize) << shift;
+        if byte & 0x80 == 0 {
+            break;
+        }
+        shift += 7;
+    }
+    position - size
+}
+
+impl Listpack {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // Size of the buffer in bytes
+    pub fn size(&self) -> usize {
+        self.buffer.len()
+    }
+
+    // Bytes taken by a value once stored
+    pub fn entry_size(length: usize) -> usize {
+        let size = varint_size(length) + length;
+        size + varint_size(size)
+    }
+
+    // Offset of the entry at an index, walking from the closest end,
+    // the end of the buffer for an index equal to the length
+    fn offset(&self, index: usize) -> usize {
+        if index <= self.len / 2 {
+            let mut offset = 0;
+            for _ in 0..index {
+                offset = decode_entry(&self.buffer, offset).1;
+            }
+            offset
+        } else {
+            let mut offset = self.buffer.len();
+            for _ in index..self.len {
+                offset = previous_entry(&self.buffer, offset);
+            }
+            offset
+        }
+    }
+
+    pub fn get(&self, index: usize) -> Option<&[u8]> {
+        if index >= self.len {
+            return None;
+        }
+        Some(decode_entry(&self.buffer, self.offset(index)).0)
+    }
+
+    // Insert a value before the entry at an index, or at the
+    // end if the index is the length
+    pub fn insert(&mut self, index: usize, value: &[u8]) {
+        let offset = self.offset(index);
+        let mut entry = Vec::with_capacity(Self::entry_size(value.len()));
+        encode_entry(value, &mut entry);
+        self.buffer.splice(offset..offset, entry);
+        self.len += 1;
+    }
+
+    pub fn push_front(&mut self, value: &[u8]) {
+        self.insert(0, value);
+    }
+
+    pub fn push_back(&mut self, value: &[u8]) {
+        encode_entry(value, &mut self.buffer);
+        self.len += 1;
+    }
+
+    pub fn remove(&mut self, index: usize) -> Vec<u8> {
+        let offset = self.offset(index);
+        let (value, next) = decode_entry(&self.buffer, offset);
+        let value = value.to_vec();
+        self.buffer.drain(offset..next);
+        self.len -= 1;
+        value
+    }
+
+    pub fn replace(&mut self, index: usize, value: &[u8]) {
+        let offset = self.offset(index);
+        let next = decode_entry(&self.buffer, offset).1;
+        let mut entry = Vec::with_capacity(Self::entry_size(value.len()));
+        encode_entry(value, &mut entry);
+        self.buffer.splice(offset..next, entry);
+    }
+
+    // Move the entries from an index on to a new listpack
+    pub fn split_off(&mut self, index: usize) -> Listpack {
+        let offset = self.offset(index);
+        let tail = Listpack {
+            buffer: self.buffer.split_off(offset),
+            len: self.len - index,
+        };
+        self.len = index;
+        tail
+    }
+
+    pub fn append(&mut self, other: Listpack) {
+        self.buffer.extend_from_slice(&other.buffer);
+        self.len += other.len;
+    }
+
+    // Keep only the entries accepted by the filter, called in order
+    pub fn retain(&mut self, mut filter: impl FnMut(&[u8]) -> bool) {
+        let mut buffer = Vec::with_capacity(self.buffer.len());
+        let mut len = 0;
+        for value in self.iter() {
+            if filter(value) {
+                encode_entry(value, &mut buffer);
+                len += 1;
+            }
+        }
+        self.buffer = buffer;
+        self.len = len;
+    }
+
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            buffer: &self.buffer,
+            front: 0,
+            back: self.buffer.len(),
+            remaining: self.len,
+        }
+    }
+}
+
+pub struct Iter<'a> {
+    buffer: &'a [u8],
+    front: usize,
+    back: usize,
+    remaining: usize,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<&'a [u8]> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let (value, next) = decode_entry(self.buffer, self.front);
+        self.front = next;
+        self.remaining -= 1;
+        Some(value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl DoubleEndedIterator for Iter<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.back = previous_entry(self.buffer, self.back);
+        self.remaining -= 1;
+        Some(decode_entry(self.buffer, self.back).0)
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+
+impl fmt::Debug for Listpack {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.iter().map(String::from_utf8_lossy))
+            .finish()
+    }
+}
+
+impl PartialEq for Listpack {
+    fn eq(&self, other: &Self) -> bool {
+        self.buffer == other.buffer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn listpack(values: &[&str]) -> Listpack {
+        let mut listpack = Listpack::new();
+        for value in values {
+            listpack.push_back(value.as_bytes());
+        }
+        listpack
+    }
+
+    fn values(listpack: &Listpack) -> Vec<String> {
+        listpack
+            .iter()
+            .map(|v| String::from_utf8_lossy(v).into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn test_push_and_get() {
+        let mut listpack = listpack(&["b", "c"]);
+        listpack.push_front(b"a");
+        assert_eq!(listpack.len(), 3);
+        assert_eq!(listpack.get(0), Some(b"a".as_slice()));
+        assert_eq!(listpack.get(2), Some(b"c".as_slice()));
+        assert_eq!(listpack.get(3), None);
+        assert_eq!(listpack.size(), 3 * Listpack::entry_size(1));
+    }
+
+    #[test]
+    fn test_long_entries() {
+        // lengths needing several bytes in the header and the backlen
+        let long = vec![b'x'; 200];
+        let longer = vec![b'y'; 20000];
+        let mut listpack = Listpack::new();
+        listpack.push_back(&long);
+        listpack.push_back(b"");
+        listpack.push_back(&longer);
+        assert_eq!(
+            listpack.size(),
+            Listpack::entry_size(200) + Listpack::entry_size(0) + Listpack::entry_size(20000)
+        );
+        assert_eq!(listpack.get(2), Some(longer.as_slice()));
+        let reversed: Vec<&[u8]> = listpack.iter().rev().collect();
+        assert_eq!(reversed, vec![longer.as_slice(), b"", long.as_slice()]);
+    }
+
+    #[test]
+    fn test_insert_remove_replace() {
+        let mut listpack = listpack(&["a", "c", "d"]);
+        listpack.insert(1, b"b");
+        listpack.insert(4, b"e");
+        assert_eq!(values(&listpack), vec!["a", "b", "c", "d", "e"]);
+        assert_eq!(listpack.remove(3), b"d".to_vec());
+        listpack.replace(0, b"first");
+        assert_eq!(values(&listpack), vec!["first", "b", "c", "e"]);
+        assert_eq!(listpack.get(3), Some(b"e".as_slice()));
+    }
+
+    #[test]
+    fn test_split_and_append() {
+        let mut listpack = listpack(&["a", "b", "c", "d"]);
+        let tail = listpack.split_off(1);
+        assert_eq!(values(&listpack), vec!["a"]);
+        assert_eq!(values(&tail), vec!["b", "c", "d"]);
+        listpack.append(tail);
+        assert_eq!(listpack, self::listpack(&["a", "b", "c", "d"]));
+    }
+
+    #[test]
+    fn test_retain() {
+        let mut listpack = listpack(&["a", "b", "a", "c"]);
+        listpack.retain(|v| v != b"a");
+        assert_eq!(values(&listpack), vec!["b", "c"]);
+        assert_eq!(listpack.len(), 2);
+    }
+
+    #[test]
+    fn test_iterate_both_ends() {
+        let listpack = listpack(&["a", "b", "c", "d"]);
+        let mut iter = listpack.iter();
+        assert_eq!(iter.next(), Some(b"a".as_slice()));
+        assert_eq!(iter.next_back(), Some(b"d".as_slice()));
+        assert_eq!(iter.len(), 2);
+        assert_eq!(iter.next_back(), Some(b"c".as_slice()));
+        assert_eq!(iter.next(), Some(b"b".as_slice()));
+        assert_eq!(iter.next(), None);
+    }
+}
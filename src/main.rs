@@ -6,11 +6,48 @@ use crate::{
     storage::Storage,
 };
 
+// Settings given on the command line as `--name value`, as
+// redis-server accepts them
+#[derive(Debug, PartialEq)]
+struct Config {
+    active_expire_effort: u8,
+}
+
+impl Config {
+    fn from_args(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut config = Config {
+            active_expire_effort: 1,
+        };
+        while let Some(name) = args.next() {
+            let value = args
+                .next()
+                .ok_or_else(|| format!("missing value for {}", name))?;
+            match name.as_str() {
+                "--active-expire-effort" => {
+                    config.active_expire_effort = value
+                        .parse()
+                        .ok()
+                        .filter(|effort| (1..=10).contains(effort))
+                        .ok_or_else(|| {
+                            String::from("active-expire-effort must be between 1 and 10")
+                        })?;
+                }
+                _ => return Err(format!("unknown option {}", name)),
+            }
+        }
+        Ok(config)
+    }
+}
+
 #[tokio::main]
 async fn main() -> std::io::Result<()> {
+    let config = Config::from_args(std::env::args().skip(1))
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+
     let (server_sender, server_receiver) = mpsc::channel::<ConnectionMessage>(32);
     let mut storage = Storage::new();
     storage.set_active_expiry(true);
+    storage.set_active_expire_effort(config.active_expire_effort);
 
     let server = Server::with_new(storage);
     tokio::spawn(run_server(server, server_receiver));
@@ -19,16 +56,67 @@ async fn main() -> std::io::Result<()> {
     Ok(())
 }
 
+mod active_expire;
+mod blocking;
+mod clock;
 mod commands;
 mod connection;
+mod dict;
+mod expire;
+mod glob;
+mod hash;
+mod intset;
+mod lazyfree;
+mod listpack;
+mod number;
+mod quicklist;
 mod request;
 mod resp;
+mod resp_decoder;
+mod resp_inline;
 mod resp_result;
 mod server;
 mod server_result;
 mod set;
+mod set_type;
+mod skiplist;
 mod storage;
 mod storage_result;
+mod zset;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> impl Iterator<Item = String> + '_ {
+        line.split_whitespace().map(String::from)
+    }
+
+    #[test]
+    fn test_config_from_args() {
+        assert_eq!(
+            Config::from_args(args("")),
+            Ok(Config {
+                active_expire_effort: 1
+            })
+        );
+        assert_eq!(
+            Config::from_args(args("--active-expire-effort 7")),
+            Ok(Config {
+                active_expire_effort: 7
+            })
+        );
+    }
+
+    #[test]
+    fn test_config_from_args_errors() {
+        assert!(Config::from_args(args("--active-expire-effort 11")).is_err());
+        assert!(Config::from_args(args("--active-expire-effort high")).is_err());
+        assert!(Config::from_args(args("--active-expire-effort")).is_err());
+        assert!(Config::from_args(args("--port 6380")).is_err());
+    }
+}
+
 /*
 Handling concurrent connections we have
 1. multithreading
@@ -0,0 +1,273 @@
+// Parse an integer the way string2ll does in Redis: an optional
+// minus sign followed by digits, without spaces, plus sign or
+// leading zeros, so that the value prints back the same
+pub fn parse_i64(value: &[u8]) -> Option<i64> {
+    let digits = value.strip_prefix(b"-").unwrap_or(value);
+    match digits {
+        [] => return None,
+        [b'0'] if digits.len() < value.len() => return None,
+        [b'0', _, ..] => return None,
+        _ => {}
+    }
+    if !digits.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    std::str::from_utf8(value).ok()?.parse().ok()
+}
+
+// Parse a float the way string2ld does in Redis, spaces and
+// NaN are not accepted
+pub fn parse_f64(value: &[u8]) -> Option<f64> {
+    let string = std::str::from_utf8(value).ok()?;
+    if string.starts_with(|c: char| c.is_ascii_whitespace()) {
+        return None;
+    }
+    let number: f64 = string.parse().ok()?;
+    if number.is_nan() {
+        return None;
+    }
+    Some(number)
+}
+
+// Decimal number, `digits` times 10 to the power of `exponent`,
+// the digits coming most significant first
+struct Decimal {
+    negative: bool,
+    digits: Vec<u8>,
+    exponent: i32,
+}
+
+impl Decimal {
+    // The shortest decimal that reads back as the float, which is
+    // what was typed for a float parsed from a client
+    fn from_f64(value: f64) -> Self {
+        let formatted = format!("{:e}", value.abs());
+        let (mantissa, exponent) = formatted.split_once('e').unwrap_or((&formatted, "0"));
+        let fraction = mantissa
+            .split_once('.')
+            .map_or(0, |(_, fraction)| fraction.len());
+        Self {
+            negative: value.is_sign_negative(),
+            digits: mantissa
+                .bytes()
+                .filter(u8::is_ascii_digit)
+                .map(|d| d - b'0')
+                .collect(),
+            exponent: exponent.parse::<i32>().unwrap_or(0) - fraction as i32,
+        }
+    }
+
+    // Digits scaled to an exponent lower than the current one,
+    // padded on the left to a given length
+    fn scaled(&self, exponent: i32, len: usize) -> Vec<u8> {
+        let mut digits = self.digits.clone();
+        digits.resize(digits.len() + (self.exponent - exponent) as usize, 0);
+        let mut padded = vec![0; len.saturating_sub(digits.len())];
+        padded.extend(digits);
+        padded
+    }
+
+    fn add(&self, other: &Decimal) -> Decimal {
+        let exponent = self.exponent.min(other.exponent);
+        let len = 1 + [self, other]
+            .iter()
+            .map(|d| d.digits.len() + (d.exponent - exponent) as usize)
+            .max()
+            .unwrap_or(0);
+        let (mut a, mut b) = (self.scaled(exponent, len), other.scaled(exponent, len));
+        let mut negative = self.negative;
+        if self.negative != other.negative && a < b {
+            std::mem::swap(&mut a, &mut b);
+            negative = other.negative;
+        }
+        let mut carry = 0;
+        for (a, b) in a.iter_mut().zip(b).rev() {
+            let digit = if self.negative == other.negative {
+                *a as i8 + b as i8 + carry
+            } else {
+                *a as i8 - b as i8 + carry
+            };
+            *a = digit.rem_euclid(10) as u8;
+            carry = digit.div_euclid(10);
+        }
+        Decimal {
+            negative,
+            digits: a,
+            exponent,
+        }
+    }
+
+    // Fixed notation rounded to some decimals as %.17Lf does,
+    // without trailing zeros
+    fn to_fixed(&self, decimals: i32) -> String {
+        let mut digits = self.digits.clone();
+        let mut exponent = self.exponent;
+        if exponent < -decimals {
+            let dropped = (-decimals - exponent) as usize;
+            let mut padded = vec![0; (dropped + 1).saturating_sub(digits.len())];
+            padded.extend(digits);
+            let round_up = padded[padded.len() - dropped] >= 5;
+            padded.truncate(padded.len() - dropped);
+            if round_up {
+                let mut position = padded.len();
+                while position > 0 && padded[position - 1] == 9 {
+                    padded[position - 1] = 0;
+                    position -= 1;
+                }
+                match position {
+                    0 => padded.insert(0, 1),
+                    _ => padded[position - 1] += 1,
+                }
+            }
+            digits = padded;
+            exponent = -decimals;
+        }
+        let fraction = exponent.min(0).unsigned_abs() as usize;
+        digits.resize(digits.len() + exponent.max(0) as usize, 0);
+        let mut padded = vec![0; (fraction + 1).saturating_sub(digits.len())];
+        padded.extend(digits);
+        let (integer, fraction) = padded.split_at(padded.len() - fraction);
+
+        let to_string =
+            |digits: &[u8]| -> String { digits.iter().map(|&d| char::from(b'0' + d)).collect() };
+        let integer = to_string(integer).trim_start_matches('0').to_owned();
+        let fraction = to_string(fraction).trim_end_matches('0').to_owned();
+        let mut formatted = if integer.is_empty() {
+            String::from("0")
+        } else {
+            integer
+        };
+        if !fraction.is_empty() {
+            formatted = format!("{}.{}", formatted, fraction);
+        }
+        if self.negative && formatted != "0" {
+            formatted.insert(0, '-');
+        }
+        formatted
+    }
+}
+
+// Decimals of the numbers INCRBYFLOAT stores, from %.17Lf in Redis
+const FLOAT_DECIMALS: i32 = 17;
+
+// Add two floats and format the result the way INCRBYFLOAT does: in
+// fixed notation with 17 decimals then without trailing zeros. None
+// if the result is infinite or NaN, which cannot be stored.
+// Redis adds long doubles, which keep 0.1 + 0.2 from being printed
+// as 0.30000000000000004. Rust has no long double, so the decimals
+// the floats were read from are added exactly instead. This gives
+// the same digits as Redis while the sum fits in the 64 bits of
+// precision of a long double, but differs past them: Redis drops
+// the digits that don't fit, or prints the binary rounding error.
+pub fn add_f64(value: f64, increment: f64) -> Option<String> {
+    if !(value + increment).is_finite() {
+        return None;
+    }
+    let sum = Decimal::from_f64(value).add(&Decimal::from_f64(increment));
+    Some(sum.to_fixed(FLOAT_DECIMALS))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_i64() {
+        assert_eq!(parse_i64(b"0"), Some(0));
+        assert_eq!(parse_i64(b"42"), Some(42));
+        assert_eq!(parse_i64(b"-42"), Some(-42));
+        assert_eq!(parse_i64(b"9223372036854775807"), Some(i64::MAX));
+        assert_eq!(parse_i64(b"-9223372036854775808"), Some(i64::MIN));
+    }
+
+    #[test]
+    fn test_parse_i64_invalid() {
+        for value in [
+            "",
+            "-",
+            "-0",
+            "007",
+            "+5",
+            " 5",
+            "5 ",
+            "1.5",
+            "1e3",
+            "ten",
+            "9223372036854775808",
+        ] {
+            assert_eq!(parse_i64(value.as_bytes()), None, "{:?}", value);
+        }
+    }
+
+    #[test]
+    fn test_parse_f64() {
+        assert_eq!(parse_f64(b"10.5"), Some(10.5));
+        assert_eq!(parse_f64(b"-3"), Some(-3.0));
+        assert_eq!(parse_f64(b"5.0e3"), Some(5000.0));
+        assert_eq!(parse_f64(b"inf"), Some(f64::INFINITY));
+        assert_eq!(parse_f64(b"nan"), None);
+        assert_eq!(parse_f64(b" 1.5"), None);
+        assert_eq!(parse_f64(b"1.5 "), None);
+        assert_eq!(parse_f64(b"abc"), None);
+    }
+
+    #[test]
+    fn test_add_f64_format() {
+        assert_eq!(add_f64(10.6, 0.0), Some(String::from("10.6")));
+        assert_eq!(add_f64(3.0, 0.0), Some(String::from("3")));
+        assert_eq!(add_f64(-0.25, 0.0), Some(String::from("-0.25")));
+        assert_eq!(add_f64(5.0e3, 0.0), Some(String::from("5000")));
+        assert_eq!(
+            add_f64(1e20, 0.0),
+            Some(String::from("100000000000000000000"))
+        );
+        assert_eq!(add_f64(1e-7, 0.0), Some(String::from("0.0000001")));
+        // rounded to 17 decimals
+        assert_eq!(
+            add_f64(1.0 / 3.0, 0.0),
+            Some(String::from("0.3333333333333333"))
+        );
+        assert_eq!(add_f64(1e-18, 0.0), Some(String::from("0")));
+        assert_eq!(
+            add_f64(-6e-18, 0.0),
+            Some(String::from("-0.00000000000000001"))
+        );
+        assert_eq!(
+            add_f64(9.5e-17, 0.0),
+            Some(String::from("0.0000000000000001"))
+        );
+        assert_eq!(add_f64(-0.0, 0.0), Some(String::from("0")));
+        assert_eq!(add_f64(f64::INFINITY, 0.0), None);
+        assert_eq!(add_f64(f64::NAN, 0.0), None);
+    }
+
+    #[test]
+    fn test_add_f64() {
+        assert_eq!(add_f64(0.1, 0.2), Some(String::from("0.3")));
+        assert_eq!(add_f64(10.5, 0.1), Some(String::from("10.6")));
+        assert_eq!(add_f64(10.6, -5.0), Some(String::from("5.6")));
+        assert_eq!(add_f64(0.1, -0.3), Some(String::from("-0.2")));
+        assert_eq!(add_f64(0.5, -0.5), Some(String::from("0")));
+        assert_eq!(
+            add_f64(1e20, 1e-5),
+            Some(String::from("100000000000000000000.00001"))
+        );
+        assert_eq!(add_f64(f64::MAX, f64::MAX), None);
+        assert_eq!(add_f64(1.0, f64::NEG_INFINITY), None);
+    }
+
+    #[test]
+    fn test_add_f64_past_long_double_precision() {
+        // Redis replies 100000000000000000000, the increment being
+        // below the precision of a long double of that magnitude
+        assert_eq!(
+            add_f64(1e20, 1e-4),
+            Some(String::from("100000000000000000000.0001"))
+        );
+        // Redis replies 12345678901234.50000953674316406
+        assert_eq!(
+            add_f64(12345678901234.5, 1e-5),
+            Some(String::from("12345678901234.50001"))
+        );
+    }
+}
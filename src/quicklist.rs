@@ -0,0 +1,391 @@
+use std::{collections::VecDeque, fmt};
+
+use crate::listpack::Listpack;
+
+// Size of a node, as with the default list-max-listpack-size of -2
+// in Redis. A value larger than this gets a node of its own.
+const NODE_SIZE_LIMIT: usize = 8 * 1024;
+
+// List stored as a deque of listpacks, like the quicklist of Redis:
+// pushing and popping at both ends is cheap, and the values are
+// packed in a few allocations instead of one each
+#[derive(Clone, Default)]
+pub struct Quicklist {
+    nodes: VecDeque<Listpack>,
+    len: usize,
+}
+
+impl Quicklist {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    fn fits(node: &Listpack, value: &[u8]) -> bool {
+        node.size() + Listpack::entry_size(value.len()) <= NODE_SIZE_LIMIT
+    }
+
+    pub fn push_front(&mut self, value: &[u8]) {
+        if !self
+            .nodes
+            .front()
+            .is_some_and(|node| Self::fits(node, value))
+        {
+            self.nodes.push_front(Listpack::new());
+        }
+        if let Some(node) = self.nodes.front_mut() {
+            node.push_front(value);
+        }
+        self.len += 1;
+    }
+
+    pub fn push_back(&mut self, value: &[u8]) {
+        if !self
+            .nodes
+            .back()
+            .is_some_and(|node| Self::fits(node, value))
+        {
+            self.nodes.push_back(Listpack::new());
+        }
+        if let Some(node) = self.nodes.back_mut() {
+            node.push_back(value);
+        }
+        self.len += 1;
+    }
+
+    pub fn pop_front(&mut self) -> Option<Vec<u8>> {
+        let node = self.nodes.front_mut()?;
+        let value = node.remove(0);
+        if node.is_empty() {
+            self.nodes.pop_front();
+        }
+        self.len -= 1;
+        Some(value)
+    }
+
+    pub fn pop_back(&mut self) -> Option<Vec<u8>> {
+        let node = self.nodes.back_mut()?;
+        let value = node.remove(node.len() - 1);
+        if node.is_empty() {
+            self.nodes.pop_back();
+        }
+        self.len -= 1;
+        Some(value)
+    }
+
+    // Node holding an index and the index within the node,
+    // walking the nodes from the closest end
+    fn locate(&self, index: usize) -> (usize, usize) {
+        if index < self.len / 2 {
+            let mut index = index;
+            for (position, node) in self.nodes.iter().enumerate() {
+                if index < node.len() {
+                    return (position, index);
+                }
+                index -= node.len();
+            }
+        } else {
+            let mut from_end = self.len - index;
+            for (position, node) in self.nodes.iter().enumerate().rev() {
+                if from_end <= node.len() {
+                    return (position, node.len() - from_end);
+                }
+                from_end -= node.len();
+            }
+        }
+        unreachable!("index {} out of a list of {}", index, self.len)
+    }
+
+    pub fn get(&self, index: usize) -> Option<&[u8]> {
+        if index >= self.len {
+            return None;
+        }
+        let (node, index) = self.locate(index);
+        self.nodes[node].get(index)
+    }
+
+    // Replace the value at an index, which has to be in the list
+    pub fn replace(&mut self, index: usize, value: &[u8]) {
+        let (node, index) = self.locate(index);
+        self.nodes[node].replace(index, value);
+        self.split_if_needed(node);
+    }
+
+    // Insert a value before an index, at the end for the length
+    pub fn insert(&mut self, index: usize, value: &[u8]) {
+        if index == self.len {
+            self.push_back(value);
+            return;
+        }
+        let (node, index) = self.locate(index);
+        self.nodes[node].insert(index, value);
+        self.len += 1;
+        self.split_if_needed(node);
+    }
+
+    // Split in halves a node which grew too large
+    fn split_if_needed(&mut self, node: usize) {
+        let listpack = &mut self.nodes[node];
+        if listpack.size() > NODE_SIZE_LIMIT && listpack.len() > 1 {
+            let tail = listpack.split_off(listpack.len() / 2);
+            self.nodes.insert(node + 1, tail);
+        }
+    }
+
+    // Merge the neighbouring nodes which fit together,
+    // so that removals do not leave many small nodes around
+    fn merge_small_nodes(&mut self) {
+        let mut node = 0;
+        while node + 1 < self.nodes.len() {
+            if self.nodes[node].size() + self.nodes[node + 1].size() <= NODE_SIZE_LIMIT {
+                let next = self.nodes.remove(node + 1).unwrap_or_default();
+                self.nodes[node].append(next);
+            } else {
+                node += 1;
+            }
+        }
+    }
+
+    // Values from start to end included, both in the list
+    pub fn range(&self, start: usize, end: usize) -> Vec<Vec<u8>> {
+        let (node, index) = self.locate(start);
+        self.nodes
+            .range(node..)
+            .flat_map(|node| node.iter())
+            .skip(index)
+            .take(end - start + 1)
+            .map(|value| value.to_vec())
+            .collect()
+    }
+
+    // Keep the values from start to end included, both in the list
+    pub fn trim(&mut self, start: usize, end: usize) {
+        let mut front = start;
+        while let Some(node) = self.nodes.front_mut() {
+            if front < node.len() {
+                *node = node.split_off(front);
+                break;
+            }
+            front -= node.len();
+            self.nodes.pop_front();
+        }
+        let mut back = self.len - end - 1;
+        while let Some(node) = self.nodes.back_mut() {
+            if back < node.len() {
+                node.split_off(node.len() - back);
+                break;
+            }
+            back -= node.len();
+            self.nodes.pop_back();
+        }
+        self.nodes.retain(|node| !node.is_empty());
+        self.len = end - start + 1;
+    }
+
+    // Remove up to `count` occurrences of a value, starting from the
+    // tail when `from_tail` is set. Returns the number removed.
+    pub fn remove_value(&mut self, value: &[u8], count: usize, from_tail: bool) -> usize {
+        let mut removed = 0;
+        let nodes: Box<dyn Iterator<Item = &mut Listpack>> = if from_tail {
+            Box::new(self.nodes.iter_mut().rev())
+        } else {
+            Box::new(self.nodes.iter_mut())
+        };
+        for node in nodes {
+            if removed == count {
+                break;
+            }
+            let matches = node.iter().filter(|v| *v == value).count();
+            if matches == 0 {
+                continue;
+            }
+            let to_remove = matches.min(count - removed);
+            // occurrences kept at the start of the node, which
+            // are the last ones reached when going from the tail
+            let mut skip = if from_tail { matches - to_remove } else { 0 };
+            let mut left = to_remove;
+            node.retain(|v| {
+                if v != value || left == 0 {
+                    return true;
+                }
+                if skip > 0 {
+                    skip -= 1;
+                    return true;
+                }
+                left -= 1;
+                false
+            });
+            removed += to_remove;
+        }
+        self.nodes.retain(|node| !node.is_empty());
+        if removed > 0 {
+            self.merge_small_nodes();
+        }
+        self.len -= removed;
+        removed
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &[u8]> {
+        self.nodes.iter().flat_map(|node| node.iter())
+    }
+}
+
+impl fmt::Debug for Quicklist {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.iter().map(String::from_utf8_lossy))
+            .finish()
+    }
+}
+
+// Lists are equal when their values are, however they are split in nodes
+impl PartialEq for Quicklist {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quicklist(count: usize) -> Quicklist {
+        let mut list = Quicklist::new();
+        for i in 0..count {
+            list.push_back(format!("value:{}", i).as_bytes());
+        }
+        list
+    }
+
+    fn value(i: usize) -> Vec<u8> {
+        format!("value:{}", i).into_bytes()
+    }
+
+    #[test]
+    fn test_push_and_pop() {
+        let mut list = Quicklist::new();
+        list.push_back(b"b");
+        list.push_front(b"a");
+        list.push_back(b"c");
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.node_count(), 1);
+        assert_eq!(list.pop_front(), Some(b"a".to_vec()));
+        assert_eq!(list.pop_back(), Some(b"c".to_vec()));
+        assert_eq!(list.pop_back(), Some(b"b".to_vec()));
+        assert_eq!(list.pop_back(), None);
+        assert_eq!(list.node_count(), 0);
+    }
+
+    #[test]
+    fn test_nodes_are_bounded() {
+        let list = quicklist(10000);
+        assert!(list.node_count() > 1);
+        assert!(list.nodes.iter().all(|node| node.size() <= NODE_SIZE_LIMIT));
+        for i in [0, 1, 4999, 5000, 9999] {
+            assert_eq!(list.get(i), Some(value(i).as_slice()));
+        }
+        assert_eq!(list.get(10000), None);
+        assert_eq!(list.iter().next_back(), Some(value(9999).as_slice()));
+    }
+
+    #[test]
+    fn test_large_value_has_its_own_node() {
+        let mut list = quicklist(2);
+        list.push_back(&vec![b'x'; NODE_SIZE_LIMIT * 2]);
+        list.push_back(b"after");
+        assert_eq!(list.node_count(), 3);
+        assert_eq!(list.get(3), Some(b"after".as_slice()));
+    }
+
+    #[test]
+    fn test_insert_splits_nodes() {
+        let mut list = quicklist(1000);
+        let nodes = list.node_count();
+        for i in 0..1000 {
+            list.insert(500, format!("inserted:{}", i).as_bytes());
+        }
+        assert_eq!(list.len(), 2000);
+        assert!(list.node_count() > nodes);
+        assert!(list.nodes.iter().all(|node| node.size() <= NODE_SIZE_LIMIT));
+        assert_eq!(list.get(499), Some(value(499).as_slice()));
+        assert_eq!(list.get(500), Some(b"inserted:999".as_slice()));
+        assert_eq!(list.get(1500), Some(value(500).as_slice()));
+        list.insert(2000, b"last");
+        assert_eq!(list.get(2000), Some(b"last".as_slice()));
+    }
+
+    #[test]
+    fn test_replace() {
+        let mut list = quicklist(3000);
+        list.replace(1500, b"new");
+        list.replace(0, &vec![b'x'; NODE_SIZE_LIMIT]);
+        assert_eq!(list.get(1500), Some(b"new".as_slice()));
+        assert_eq!(list.get(1), Some(value(1).as_slice()));
+        assert_eq!(list.len(), 3000);
+    }
+
+    #[test]
+    fn test_range() {
+        let list = quicklist(3000);
+        let range = list.range(1000, 2999);
+        assert_eq!(range.len(), 2000);
+        assert_eq!(range[0], value(1000));
+        assert_eq!(range[1999], value(2999));
+        assert_eq!(list.range(5, 5), vec![value(5)]);
+    }
+
+    #[test]
+    fn test_trim() {
+        let mut list = quicklist(3000);
+        list.trim(1000, 1999);
+        assert_eq!(list.len(), 1000);
+        assert_eq!(list.iter().count(), 1000);
+        assert_eq!(list.get(0), Some(value(1000).as_slice()));
+        assert_eq!(list.get(999), Some(value(1999).as_slice()));
+        list.trim(0, 0);
+        assert_eq!(list.len(), 1);
+        assert_eq!(list.node_count(), 1);
+    }
+
+    #[test]
+    fn test_remove_value() {
+        let mut list = Quicklist::new();
+        for i in 0..3000 {
+            list.push_back(if i % 3 == 0 { b"x" } else { b"y" });
+        }
+        assert_eq!(list.remove_value(b"x", 2, true), 2);
+        assert_eq!(list.get(2997), Some(b"y".as_slice()));
+        assert_eq!(list.get(2991), Some(b"x".as_slice()));
+        assert!(list.range(2992, 2997).iter().all(|v| v == b"y"));
+        assert_eq!(list.remove_value(b"x", 1, false), 1);
+        assert_eq!(list.get(0), Some(b"y".as_slice()));
+        let nodes = list.node_count();
+        assert_eq!(list.remove_value(b"x", usize::MAX, false), 997);
+        assert_eq!(list.len(), 2000);
+        assert!(list.iter().all(|v| v == b"y"));
+        assert!(list.node_count() < nodes);
+    }
+
+    #[test]
+    fn test_equality_ignores_nodes() {
+        let mut list = quicklist(10);
+        let mut other = Quicklist::new();
+        for i in (0..10).rev() {
+            other.push_front(&value(i));
+        }
+        assert_eq!(list, other);
+        list.pop_back();
+        assert_ne!(list, other);
+    }
+}
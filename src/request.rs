@@ -8,14 +8,18 @@ use tokio::sync::mpsc;
 pub struct Request {
     pub value: RESP,
     pub sender: mpsc::Sender<ServerMessage>,
+    // identifier of the connection which sent the request
+    pub client_id: u64,
 }
 
 impl Request {
+    // The client may have disconnected in the meantime,
+    // in that case there is nobody left to reply to
     pub async fn error(&self, e: ServerError) {
-        self.sender.send(ServerMessage::Error(e)).await.unwrap();
+        let _ = self.sender.send(ServerMessage::Error(e)).await;
     }
 
     pub async fn data(&self, d: ServerValue) {
-        self.sender.send(ServerMessage::Data(d)).await.unwrap();
+        let _ = self.sender.send(ServerMessage::Data(d)).await;
     }
 }
@@ -2,30 +2,203 @@ use std::fmt::{self};
 
 use crate::resp_result::{RESPError, RESPLength, RESPResult};
 
+// Same limit as the proto-max-bulk-len default of Redis (512MB)
+pub const MAX_BULK_LENGTH: RESPLength = 512 * 1024 * 1024;
+
+// Version of the protocol spoken on a connection,
+// selected by the client through HELLO
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Protocol {
+    #[default]
+    RESP2,
+    RESP3,
+}
+
+impl Protocol {
+    pub fn version(&self) -> i64 {
+        match self {
+            Protocol::RESP2 => 2,
+            Protocol::RESP3 => 3,
+        }
+    }
+}
+
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Debug, PartialEq)]
 pub enum RESP {
     Array(Vec<RESP>),
     SimpleString(String),
-    BulkString(String),
+    SimpleError(String),
+    Integer(i64),
+    BulkString(Vec<u8>),
     Null,
+    // Null reply of commands returning an array,
+    // which RESP2 encodes as an array of length -1
+    NullArray,
+    // Array of pairs, such as fields with their values, sent
+    // as arrays of two elements in RESP3 and flattened in RESP2
+    Pairs(Vec<(RESP, RESP)>),
+    // RESP3 types, downgraded to their closest
+    // RESP2 counterpart on RESP2 connections
+    Map(Vec<(RESP, RESP)>),
+    Set(Vec<RESP>),
+    Double(f64),
+    Boolean(bool),
+    BigNumber(String),
+    VerbatimString(String, Vec<u8>),
+    Push(Vec<RESP>),
+    Attribute(Vec<(RESP, RESP)>, Box<RESP>),
 }
 
-impl fmt::Display for RESP {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let data = match self {
-            Self::Array(data) => {
-                let mut output = String::from("*");
-                output.push_str(format!("{}\r\n", data.len()).as_str());
+// Doubles use the shortest representation that round trips,
+// switching to the exponent notation for very big or small values
+pub fn format_double(value: f64) -> String {
+    if value.is_nan() {
+        return String::from("nan");
+    }
+    if value.is_infinite() {
+        return String::from(if value > 0.0 { "inf" } else { "-inf" });
+    }
+    let absolute = value.abs();
+    if absolute != 0.0 && !(1e-5..1e17).contains(&absolute) {
+        format!("{:e}", value)
+    } else {
+        format!("{}", value)
+    }
+}
+
+// Simple strings and errors end at the first CRLF, so the line
+// breaks of a message, which may echo bytes sent by the client,
+// are replaced by spaces as Redis does
+fn encode_line(prefix: char, data: &str, output: &mut Vec<u8>) {
+    output
+        .extend_from_slice(format!("{}{}\r\n", prefix, data.replace(['\r', '\n'], " ")).as_bytes());
+}
+
+fn encode_aggregate_header(prefix: char, length: usize, output: &mut Vec<u8>) {
+    output.extend_from_slice(format!("{}{}\r\n", prefix, length).as_bytes());
+}
+
+impl RESP {
+    // Serialize the value in its wire format for the given protocol.
+    // Bulk strings are binary safe so the output is raw bytes.
+    pub fn encode(&self, protocol: Protocol, output: &mut Vec<u8>) {
+        match (self, protocol) {
+            (Self::Array(data), _) => {
+                encode_aggregate_header('*', data.len(), output);
                 for elem in data.iter() {
-                    output.push_str(elem.to_string().as_str());
+                    elem.encode(protocol, output);
                 }
-                output
             }
-            Self::SimpleString(data) => format!("+{}\r\n", data),
-            Self::BulkString(data) => format!("${}\r\n{}\r\n", data.len(), data),
-            Self::Null => String::from("$-1\r\n"),
-        };
-        write!(f, "{}", data)
+            (Self::SimpleString(data), _) => encode_line('+', data, output),
+            (Self::SimpleError(data), _) => encode_line('-', data, output),
+            (Self::Integer(data), _) => {
+                output.extend_from_slice(format!(":{}\r\n", data).as_bytes())
+            }
+            (Self::BulkString(data), _) => {
+                output.extend_from_slice(format!("${}\r\n", data.len()).as_bytes());
+                output.extend_from_slice(data);
+                output.extend_from_slice(b"\r\n");
+            }
+            (Self::Null, Protocol::RESP2) => output.extend_from_slice(b"$-1\r\n"),
+            (Self::Null, Protocol::RESP3) => output.extend_from_slice(b"_\r\n"),
+            (Self::NullArray, Protocol::RESP2) => output.extend_from_slice(b"*-1\r\n"),
+            (Self::NullArray, Protocol::RESP3) => output.extend_from_slice(b"_\r\n"),
+            (Self::Pairs(data), Protocol::RESP2) => {
+                encode_aggregate_header('*', data.len() * 2, output);
+                for (first, second) in data.iter() {
+                    first.encode(protocol, output);
+                    second.encode(protocol, output);
+                }
+            }
+            (Self::Pairs(data), Protocol::RESP3) => {
+                encode_aggregate_header('*', data.len(), output);
+                for (first, second) in data.iter() {
+                    encode_aggregate_header('*', 2, output);
+                    first.encode(protocol, output);
+                    second.encode(protocol, output);
+                }
+            }
+            (Self::Map(data), _) => {
+                // RESP2 has no maps, they are sent as flat arrays
+                match protocol {
+                    Protocol::RESP2 => encode_aggregate_header('*', data.len() * 2, output),
+                    Protocol::RESP3 => encode_aggregate_header('%', data.len(), output),
+                }
+                for (key, value) in data.iter() {
+                    key.encode(protocol, output);
+                    value.encode(protocol, output);
+                }
+            }
+            (Self::Set(data), _) | (Self::Push(data), _) => {
+                let prefix = match (self, protocol) {
+                    (_, Protocol::RESP2) => '*',
+                    (Self::Set(_), Protocol::RESP3) => '~',
+                    _ => '>',
+                };
+                encode_aggregate_header(prefix, data.len(), output);
+                for elem in data.iter() {
+                    elem.encode(protocol, output);
+                }
+            }
+            (Self::Double(data), Protocol::RESP2) => {
+                Self::BulkString(format_double(*data).into_bytes()).encode(protocol, output)
+            }
+            (Self::Double(data), Protocol::RESP3) => {
+                output.extend_from_slice(format!(",{}\r\n", format_double(*data)).as_bytes())
+            }
+            (Self::Boolean(data), Protocol::RESP2) => {
+                Self::Integer(*data as i64).encode(protocol, output)
+            }
+            (Self::Boolean(data), Protocol::RESP3) => {
+                output.extend_from_slice(if *data { b"#t\r\n" } else { b"#f\r\n" })
+            }
+            (Self::BigNumber(data), Protocol::RESP2) => {
+                Self::BulkString(data.clone().into_bytes()).encode(protocol, output)
+            }
+            (Self::BigNumber(data), Protocol::RESP3) => {
+                output.extend_from_slice(format!("({}\r\n", data).as_bytes())
+            }
+            (Self::VerbatimString(_, data), Protocol::RESP2) => {
+                output.extend_from_slice(format!("${}\r\n", data.len()).as_bytes());
+                output.extend_from_slice(data);
+                output.extend_from_slice(b"\r\n");
+            }
+            (Self::VerbatimString(format, data), Protocol::RESP3) => {
+                output.extend_from_slice(format!("={}\r\n{}:", data.len() + 4, format).as_bytes());
+                output.extend_from_slice(data);
+                output.extend_from_slice(b"\r\n");
+            }
+            // RESP2 clients can't receive attributes, only the value is sent
+            (Self::Attribute(_, value), Protocol::RESP2) => value.encode(protocol, output),
+            (Self::Attribute(attributes, value), Protocol::RESP3) => {
+                encode_aggregate_header('|', attributes.len(), output);
+                for (key, attribute) in attributes.iter() {
+                    key.encode(protocol, output);
+                    attribute.encode(protocol, output);
+                }
+                value.encode(protocol, output);
+            }
+        }
+    }
+
+    pub fn to_bytes(&self, protocol: Protocol) -> Vec<u8> {
+        let mut output = Vec::new();
+        self.encode(protocol, &mut output);
+        output
+    }
+}
+
+// Values are displayed with their RESP3 encoding,
+// which has a distinct representation for every type
+impl fmt::Display for RESP {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            String::from_utf8_lossy(&self.to_bytes(Protocol::RESP3))
+        )
     }
 }
 
@@ -39,11 +212,11 @@ fn binary_extract_line(buffer: &[u8], index: &mut usize) -> RESPResult<Vec<u8>>
 
     // if there is not enough space for \r\n
     // the buffer is definitely invalid
-    if buffer.len() - *index - 1 < 2 {
+    if buffer.len() - *index < 2 {
         *index = buffer.len();
         return Err(RESPError::OutOfBounds(*index));
     }
-    let mut previous_elem: u8 = buffer[*index].clone();
+    let mut previous_elem: u8 = buffer[*index];
     let mut separator_found: bool = false;
     let mut final_index: usize = *index;
 
@@ -54,7 +227,7 @@ fn binary_extract_line(buffer: &[u8], index: &mut usize) -> RESPResult<Vec<u8>>
             separator_found = true;
             break;
         }
-        previous_elem = elem.clone();
+        previous_elem = elem;
     }
     // If the previous element is not \n
     // we are out of bounds
@@ -79,7 +252,7 @@ fn binary_extract_bytes(buffer: &[u8], index: &mut usize, length: usize) -> RESP
     let mut output = Vec::new();
 
     // Check if we allowed to read length bytes
-    if *index + length >= buffer.len() {
+    if *index + length > buffer.len() {
         return Err(RESPError::OutOfBounds(buffer.len()));
     }
 
@@ -89,10 +262,13 @@ fn binary_extract_bytes(buffer: &[u8], index: &mut usize, length: usize) -> RESP
     // update the index
     *index += length;
 
-    return Ok(output);
+    Ok(output)
 }
 
 pub fn resp_remove_type(value: char, buffer: &[u8], index: &mut usize) -> RESPResult<()> {
+    if *index >= buffer.len() {
+        return Err(RESPError::OutOfBounds(*index));
+    }
     if buffer[*index] != value as u8 {
         return Err(RESPError::WrongType);
     }
@@ -104,7 +280,7 @@ pub fn resp_remove_type(value: char, buffer: &[u8], index: &mut usize) -> RESPRe
 pub fn resp_extract_length(buffer: &[u8], index: &mut usize) -> RESPResult<RESPLength> {
     let line = binary_extract_line_as_string(buffer, index)?;
     let length: RESPLength = line.parse()?;
-    return Ok(length);
+    Ok(length)
 }
 
 // Parse a simple string in the form `+VALUE\r\n`
@@ -115,6 +291,22 @@ fn parse_simple_string(buffer: &[u8], index: &mut usize) -> RESPResult<RESP> {
     Ok(RESP::SimpleString(line))
 }
 
+// Parse a simple error in the form `-ERR message\r\n`
+fn parse_simple_error(buffer: &[u8], index: &mut usize) -> RESPResult<RESP> {
+    resp_remove_type('-', buffer, index)?;
+
+    let line: String = binary_extract_line_as_string(buffer, index)?;
+    Ok(RESP::SimpleError(line))
+}
+
+// Parse an integer in the form `:[<+|->]VALUE\r\n`
+fn parse_integer(buffer: &[u8], index: &mut usize) -> RESPResult<RESP> {
+    resp_remove_type(':', buffer, index)?;
+
+    let line: String = binary_extract_line_as_string(buffer, index)?;
+    Ok(RESP::Integer(line.parse()?))
+}
+
 // $5\r\nhello\r\n
 fn parse_bulk_string(buffer: &[u8], index: &mut usize) -> RESPResult<RESP> {
     resp_remove_type('$', buffer, index)?;
@@ -124,16 +316,24 @@ fn parse_bulk_string(buffer: &[u8], index: &mut usize) -> RESPResult<RESP> {
         return Ok(RESP::Null);
     }
 
-    if length < -1 {
+    if !(-1..=MAX_BULK_LENGTH).contains(&length) {
         return Err(RESPError::IncorrectLength(length));
     }
 
-    let bytes = binary_extract_bytes(buffer, index, length as usize)?;
-    let data = String::from_utf8(bytes)?;
+    // The payload is only complete once the trailing \r\n has arrived too
+    let length = length as usize;
+    if *index + length + 2 > buffer.len() {
+        return Err(RESPError::OutOfBounds(buffer.len()));
+    }
+
+    let bytes = binary_extract_bytes(buffer, index, length)?;
+    if &buffer[*index..*index + 2] != b"\r\n" {
+        return Err(RESPError::MissingTerminator);
+    }
 
     //Increment the index to skip \r\n
     *index += 2;
-    Ok(RESP::BulkString(data))
+    Ok(RESP::BulkString(bytes))
 }
 
 fn parse_array(buffer: &[u8], index: &mut usize) -> RESPResult<RESP> {
@@ -148,6 +348,9 @@ fn parse_array(buffer: &[u8], index: &mut usize) -> RESPResult<RESP> {
     let mut data = Vec::new();
 
     for _ in 0..length {
+        if *index >= buffer.len() {
+            return Err(RESPError::OutOfBounds(*index));
+        }
         match parser_router(buffer, index) {
             Some(parse_func) => {
                 let array_element: RESP = parse_func(buffer, index)?;
@@ -159,21 +362,173 @@ fn parse_array(buffer: &[u8], index: &mut usize) -> RESPResult<RESP> {
     Ok(RESP::Array(data))
 }
 
-fn parser_router(
+// Parse the elements of an aggregate type after its length
+fn parse_elements(buffer: &[u8], index: &mut usize, length: RESPLength) -> RESPResult<Vec<RESP>> {
+    if length < 0 {
+        return Err(RESPError::IncorrectLength(length));
+    }
+    let mut data = Vec::new();
+    for _ in 0..length {
+        data.push(bytes_to_resp(buffer, index)?);
+    }
+    Ok(data)
+}
+
+// Parse the key value pairs of maps and attributes
+fn parse_pairs(
     buffer: &[u8],
     index: &mut usize,
-) -> Option<fn(&[u8], &mut usize) -> RESPResult<RESP>> {
+    length: RESPLength,
+) -> RESPResult<Vec<(RESP, RESP)>> {
+    if length < 0 {
+        return Err(RESPError::IncorrectLength(length));
+    }
+    let mut data = Vec::new();
+    for _ in 0..length {
+        let key = bytes_to_resp(buffer, index)?;
+        let value = bytes_to_resp(buffer, index)?;
+        data.push((key, value));
+    }
+    Ok(data)
+}
+
+// %2\r\n+first\r\n:1\r\n+second\r\n:2\r\n
+fn parse_map(buffer: &[u8], index: &mut usize) -> RESPResult<RESP> {
+    resp_remove_type('%', buffer, index)?;
+
+    let length = resp_extract_length(buffer, index)?;
+    Ok(RESP::Map(parse_pairs(buffer, index, length)?))
+}
+
+// ~2\r\n+a\r\n+b\r\n
+fn parse_set(buffer: &[u8], index: &mut usize) -> RESPResult<RESP> {
+    resp_remove_type('~', buffer, index)?;
+
+    let length = resp_extract_length(buffer, index)?;
+    Ok(RESP::Set(parse_elements(buffer, index, length)?))
+}
+
+// >2\r\n+message\r\n+hello\r\n
+fn parse_push(buffer: &[u8], index: &mut usize) -> RESPResult<RESP> {
+    resp_remove_type('>', buffer, index)?;
+
+    let length = resp_extract_length(buffer, index)?;
+    Ok(RESP::Push(parse_elements(buffer, index, length)?))
+}
+
+// |1\r\n+ttl\r\n:3600\r\n$5\r\nvalue\r\n
+// The attributes are attached to the value that follows them
+fn parse_attribute(buffer: &[u8], index: &mut usize) -> RESPResult<RESP> {
+    resp_remove_type('|', buffer, index)?;
+
+    let length = resp_extract_length(buffer, index)?;
+    let attributes = parse_pairs(buffer, index, length)?;
+    let value = bytes_to_resp(buffer, index)?;
+    Ok(RESP::Attribute(attributes, Box::new(value)))
+}
+
+// ,3.14\r\n
+fn parse_double(buffer: &[u8], index: &mut usize) -> RESPResult<RESP> {
+    resp_remove_type(',', buffer, index)?;
+
+    let line: String = binary_extract_line_as_string(buffer, index)?;
+    Ok(RESP::Double(line.parse()?))
+}
+
+// #t\r\n
+fn parse_boolean(buffer: &[u8], index: &mut usize) -> RESPResult<RESP> {
+    resp_remove_type('#', buffer, index)?;
+
+    match binary_extract_line(buffer, index)?.as_slice() {
+        b"t" => Ok(RESP::Boolean(true)),
+        b"f" => Ok(RESP::Boolean(false)),
+        _ => Err(RESPError::Unknown),
+    }
+}
+
+// _\r\n
+fn parse_null(buffer: &[u8], index: &mut usize) -> RESPResult<RESP> {
+    resp_remove_type('_', buffer, index)?;
+
+    if !binary_extract_line(buffer, index)?.is_empty() {
+        return Err(RESPError::Unknown);
+    }
+    Ok(RESP::Null)
+}
+
+// (3492890328409238509324850943850943825024385\r\n
+fn parse_big_number(buffer: &[u8], index: &mut usize) -> RESPResult<RESP> {
+    resp_remove_type('(', buffer, index)?;
+
+    let line: String = binary_extract_line_as_string(buffer, index)?;
+    let digits = line.strip_prefix(['-', '+']).unwrap_or(&line);
+    if digits.is_empty() || !digits.bytes().all(|c| c.is_ascii_digit()) {
+        return Err(RESPError::ParseInt);
+    }
+    Ok(RESP::BigNumber(line))
+}
+
+// =15\r\ntxt:Some string\r\n
+fn parse_verbatim_string(buffer: &[u8], index: &mut usize) -> RESPResult<RESP> {
+    resp_remove_type('=', buffer, index)?;
+
+    let length = resp_extract_length(buffer, index)?;
+    if !(4..=MAX_BULK_LENGTH).contains(&length) {
+        return Err(RESPError::IncorrectLength(length));
+    }
+
+    let length = length as usize;
+    if *index + length + 2 > buffer.len() {
+        return Err(RESPError::OutOfBounds(buffer.len()));
+    }
+
+    let bytes = binary_extract_bytes(buffer, index, length)?;
+    if &buffer[*index..*index + 2] != b"\r\n" {
+        return Err(RESPError::MissingTerminator);
+    }
+    *index += 2;
+
+    // the payload starts with a three characters format and a colon
+    if bytes[3] != b':' {
+        return Err(RESPError::Unknown);
+    }
+    let format = String::from_utf8(bytes[..3].to_vec())?;
+    Ok(RESP::VerbatimString(format, bytes[4..].to_vec()))
+}
+
+type ParseFn = fn(&[u8], &mut usize) -> RESPResult<RESP>;
+
+fn parser_router(buffer: &[u8], index: &mut usize) -> Option<ParseFn> {
     match buffer[*index] {
         b'+' => Some(parse_simple_string),
+        b'-' => Some(parse_simple_error),
+        b':' => Some(parse_integer),
         b'$' => Some(parse_bulk_string),
         b'*' => Some(parse_array),
+        b'%' => Some(parse_map),
+        b'~' => Some(parse_set),
+        b'>' => Some(parse_push),
+        b'|' => Some(parse_attribute),
+        b',' => Some(parse_double),
+        b'#' => Some(parse_boolean),
+        b'_' => Some(parse_null),
+        b'(' => Some(parse_big_number),
+        b'=' => Some(parse_verbatim_string),
         _ => None,
     }
 }
 
+// Whether the byte starts one of the RESP types
+pub fn is_type_prefix(byte: u8) -> bool {
+    parser_router(&[byte], &mut 0).is_some()
+}
+
 pub fn bytes_to_resp(buffer: &[u8], index: &mut usize) -> RESPResult<RESP> {
+    if *index >= buffer.len() {
+        return Err(RESPError::OutOfBounds(*index));
+    }
     match parser_router(buffer, index) {
-        Some(parse_func) => return parse_func(buffer, index),
+        Some(parse_func) => parse_func(buffer, index),
         _ => Err(RESPError::Unknown),
     }
 }
@@ -181,6 +536,7 @@ pub fn bytes_to_resp(buffer: &[u8], index: &mut usize) -> RESPResult<RESP> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::server_result::ServerError;
 
     #[test]
     fn test_binary_extract_line_empty_buffer() {
@@ -336,7 +692,7 @@ mod tests {
         let buffer = "$2\r\nOK\r\n".as_bytes();
         let mut index: usize = 0;
         let output = bytes_to_resp(buffer, &mut index).unwrap();
-        assert_eq!(output, RESP::BulkString(String::from("OK")));
+        assert_eq!(output, RESP::BulkString(b"OK".to_vec()));
         assert_eq!(index, 8);
     }
 
@@ -349,7 +705,7 @@ mod tests {
             output,
             RESP::Array(vec![
                 RESP::SimpleString(String::from("OK")),
-                RESP::BulkString(String::from("VALUE"))
+                RESP::BulkString(b"VALUE".to_vec())
             ])
         );
         assert_eq!(index, 20);
@@ -373,9 +729,408 @@ mod tests {
             output,
             RESP::Array(vec![
                 RESP::SimpleString(String::from("OK")),
-                RESP::BulkString(String::from("VALUE"))
+                RESP::BulkString(b"VALUE".to_vec())
             ])
         );
         assert_eq!(index, 20);
     }
+
+    #[test]
+    fn test_bytes_to_resp_bulk_string_incomplete() {
+        let buffer = "$5\r\nVAL".as_bytes();
+        let mut index: usize = 0;
+        let error = bytes_to_resp(buffer, &mut index).unwrap_err();
+        assert_eq!(error, RESPError::OutOfBounds(7));
+    }
+
+    #[test]
+    fn test_bytes_to_resp_bulk_string_missing_terminator() {
+        let buffer = "$2\r\nOKOK".as_bytes();
+        let mut index: usize = 0;
+        let error = bytes_to_resp(buffer, &mut index).unwrap_err();
+        assert_eq!(error, RESPError::MissingTerminator);
+    }
+
+    #[test]
+    fn test_parse_array_incomplete() {
+        let buffer = "*2\r\n+OK\r\n".as_bytes();
+        let mut index: usize = 0;
+        let error = parse_array(buffer, &mut index).unwrap_err();
+        assert_eq!(error, RESPError::OutOfBounds(9));
+    }
+
+    #[test]
+    fn test_parse_simple_string_empty() {
+        let buffer = "+\r\n".as_bytes();
+        let mut index: usize = 0;
+        let output = parse_simple_string(buffer, &mut index).unwrap();
+        assert_eq!(output, RESP::SimpleString(String::new()));
+        assert_eq!(index, 3);
+    }
+
+    #[test]
+    fn test_bytes_to_resp_binary_bulk_string() {
+        let buffer = b"$4\r\n\x00\xff\r\n\r\n";
+        let mut index: usize = 0;
+        let output = bytes_to_resp(buffer, &mut index).unwrap();
+        assert_eq!(output, RESP::BulkString(vec![0x00, 0xff, b'\r', b'\n']));
+        assert_eq!(index, 10);
+    }
+
+    #[test]
+    fn test_encode_binary_bulk_string() {
+        let value = RESP::BulkString(vec![0x00, 0xff, 0xfe]);
+        assert_eq!(
+            value.to_bytes(Protocol::RESP2),
+            b"$3\r\n\x00\xff\xfe\r\n".to_vec()
+        );
+    }
+
+    #[test]
+    fn test_encode_array() {
+        let value = RESP::Array(vec![
+            RESP::SimpleString(String::from("OK")),
+            RESP::BulkString(b"VALUE".to_vec()),
+            RESP::Null,
+        ]);
+        assert_eq!(
+            value.to_bytes(Protocol::RESP2),
+            b"*3\r\n+OK\r\n$5\r\nVALUE\r\n$-1\r\n".to_vec()
+        );
+        assert_eq!(value.to_string(), "*3\r\n+OK\r\n$5\r\nVALUE\r\n_\r\n");
+    }
+
+    #[test]
+    fn test_parse_simple_error() {
+        let buffer = "-ERR unknown command\r\n".as_bytes();
+        let mut index: usize = 0;
+        let output = parse_simple_error(buffer, &mut index).unwrap();
+        assert_eq!(
+            output,
+            RESP::SimpleError(String::from("ERR unknown command"))
+        );
+        assert_eq!(index, 22);
+    }
+
+    #[test]
+    fn test_bytes_to_resp_simple_error() {
+        let buffer = "-WRONGTYPE Operation\r\n".as_bytes();
+        let mut index: usize = 0;
+        let output = bytes_to_resp(buffer, &mut index).unwrap();
+        assert_eq!(
+            output,
+            RESP::SimpleError(String::from("WRONGTYPE Operation"))
+        );
+    }
+
+    #[test]
+    fn test_parse_integer() {
+        let buffer = ":1000\r\n".as_bytes();
+        let mut index: usize = 0;
+        let output = parse_integer(buffer, &mut index).unwrap();
+        assert_eq!(output, RESP::Integer(1000));
+        assert_eq!(index, 7);
+    }
+
+    #[test]
+    fn test_parse_integer_signed() {
+        let buffer = ":-42\r\n:+7\r\n".as_bytes();
+        let mut index: usize = 0;
+        assert_eq!(
+            bytes_to_resp(buffer, &mut index).unwrap(),
+            RESP::Integer(-42)
+        );
+        assert_eq!(bytes_to_resp(buffer, &mut index).unwrap(), RESP::Integer(7));
+        assert_eq!(index, buffer.len());
+    }
+
+    #[test]
+    fn test_parse_integer_invalid() {
+        let buffer = ":12a\r\n".as_bytes();
+        let mut index: usize = 0;
+        let error = parse_integer(buffer, &mut index).unwrap_err();
+        assert_eq!(error, RESPError::ParseInt);
+    }
+
+    #[test]
+    fn test_parse_integer_incomplete() {
+        let buffer = ":12".as_bytes();
+        let mut index: usize = 0;
+        let error = bytes_to_resp(buffer, &mut index).unwrap_err();
+        assert_eq!(error, RESPError::OutOfBounds(3));
+    }
+
+    #[test]
+    fn test_encode_simple_error() {
+        let value = RESP::SimpleError(String::from("ERR syntax error"));
+        assert_eq!(
+            value.to_bytes(Protocol::RESP2),
+            b"-ERR syntax error\r\n".to_vec()
+        );
+    }
+
+    #[test]
+    fn test_encode_error_with_line_breaks() {
+        // a command name sent as a bulk string may hold CRLF
+        let buffer = "*1\r\n$8\r\nfoo\r\n+OK\r\n".as_bytes();
+        let mut index: usize = 0;
+        let name = match bytes_to_resp(buffer, &mut index).unwrap() {
+            RESP::Array(mut elements) => match elements.remove(0) {
+                RESP::BulkString(name) => String::from_utf8(name).unwrap(),
+                other => panic!("unexpected {:?}", other),
+            },
+            other => panic!("unexpected {:?}", other),
+        };
+        let value = RESP::from(ServerError::CommandNotAvailable(name));
+        assert_eq!(
+            value.to_bytes(Protocol::RESP2),
+            b"-ERR unknown command 'foo  +OK'\r\n".to_vec()
+        );
+        assert_eq!(
+            RESP::SimpleString(String::from("a\nb")).to_bytes(Protocol::RESP3),
+            b"+a b\r\n".to_vec()
+        );
+    }
+
+    #[test]
+    fn test_encode_integer() {
+        assert_eq!(
+            RESP::Integer(0).to_bytes(Protocol::RESP2),
+            b":0\r\n".to_vec()
+        );
+        assert_eq!(
+            RESP::Integer(-12).to_bytes(Protocol::RESP2),
+            b":-12\r\n".to_vec()
+        );
+        assert_eq!(
+            RESP::Integer(i64::MAX).to_bytes(Protocol::RESP2),
+            b":9223372036854775807\r\n".to_vec()
+        );
+    }
+
+    #[test]
+    fn test_parse_array_mixed_types() {
+        let buffer = "*3\r\n:1\r\n-ERR no\r\n$1\r\na\r\n".as_bytes();
+        let mut index: usize = 0;
+        let output = parse_array(buffer, &mut index).unwrap();
+        assert_eq!(
+            output,
+            RESP::Array(vec![
+                RESP::Integer(1),
+                RESP::SimpleError(String::from("ERR no")),
+                RESP::BulkString(b"a".to_vec())
+            ])
+        );
+    }
+
+    #[test]
+    fn test_bytes_to_resp_map() {
+        let buffer = "%2\r\n+first\r\n:1\r\n+second\r\n:2\r\n".as_bytes();
+        let mut index: usize = 0;
+        let output = bytes_to_resp(buffer, &mut index).unwrap();
+        assert_eq!(
+            output,
+            RESP::Map(vec![
+                (RESP::SimpleString(String::from("first")), RESP::Integer(1)),
+                (RESP::SimpleString(String::from("second")), RESP::Integer(2))
+            ])
+        );
+        assert_eq!(index, buffer.len());
+    }
+
+    #[test]
+    fn test_bytes_to_resp_set_and_push() {
+        let buffer = "~2\r\n+a\r\n+b\r\n>1\r\n+message\r\n".as_bytes();
+        let mut index: usize = 0;
+        assert_eq!(
+            bytes_to_resp(buffer, &mut index).unwrap(),
+            RESP::Set(vec![
+                RESP::SimpleString(String::from("a")),
+                RESP::SimpleString(String::from("b"))
+            ])
+        );
+        assert_eq!(
+            bytes_to_resp(buffer, &mut index).unwrap(),
+            RESP::Push(vec![RESP::SimpleString(String::from("message"))])
+        );
+    }
+
+    #[test]
+    fn test_bytes_to_resp_scalars() {
+        let buffer = ",2.5\r\n,-inf\r\n#t\r\n#f\r\n_\r\n(-12345678901234567890123\r\n".as_bytes();
+        let mut index: usize = 0;
+        assert_eq!(
+            bytes_to_resp(buffer, &mut index).unwrap(),
+            RESP::Double(2.5)
+        );
+        assert_eq!(
+            bytes_to_resp(buffer, &mut index).unwrap(),
+            RESP::Double(f64::NEG_INFINITY)
+        );
+        assert_eq!(
+            bytes_to_resp(buffer, &mut index).unwrap(),
+            RESP::Boolean(true)
+        );
+        assert_eq!(
+            bytes_to_resp(buffer, &mut index).unwrap(),
+            RESP::Boolean(false)
+        );
+        assert_eq!(bytes_to_resp(buffer, &mut index).unwrap(), RESP::Null);
+        assert_eq!(
+            bytes_to_resp(buffer, &mut index).unwrap(),
+            RESP::BigNumber(String::from("-12345678901234567890123"))
+        );
+        assert_eq!(index, buffer.len());
+    }
+
+    #[test]
+    fn test_bytes_to_resp_invalid_scalars() {
+        let mut index: usize = 0;
+        assert_eq!(
+            bytes_to_resp(",abc\r\n".as_bytes(), &mut index).unwrap_err(),
+            RESPError::ParseFloat
+        );
+        let mut index: usize = 0;
+        assert_eq!(
+            bytes_to_resp("#x\r\n".as_bytes(), &mut index).unwrap_err(),
+            RESPError::Unknown
+        );
+        let mut index: usize = 0;
+        assert_eq!(
+            bytes_to_resp("(12a\r\n".as_bytes(), &mut index).unwrap_err(),
+            RESPError::ParseInt
+        );
+    }
+
+    #[test]
+    fn test_bytes_to_resp_verbatim_string() {
+        let buffer = "=15\r\ntxt:Some string\r\n".as_bytes();
+        let mut index: usize = 0;
+        assert_eq!(
+            bytes_to_resp(buffer, &mut index).unwrap(),
+            RESP::VerbatimString(String::from("txt"), b"Some string".to_vec())
+        );
+        assert_eq!(index, buffer.len());
+    }
+
+    #[test]
+    fn test_bytes_to_resp_attribute() {
+        let buffer = "|1\r\n+ttl\r\n:3600\r\n$5\r\nvalue\r\n".as_bytes();
+        let mut index: usize = 0;
+        assert_eq!(
+            bytes_to_resp(buffer, &mut index).unwrap(),
+            RESP::Attribute(
+                vec![(RESP::SimpleString(String::from("ttl")), RESP::Integer(3600))],
+                Box::new(RESP::BulkString(b"value".to_vec()))
+            )
+        );
+        assert_eq!(index, buffer.len());
+    }
+
+    #[test]
+    fn test_bytes_to_resp_map_incomplete() {
+        let buffer = "%1\r\n+key\r\n".as_bytes();
+        let mut index: usize = 0;
+        assert_eq!(
+            bytes_to_resp(buffer, &mut index).unwrap_err(),
+            RESPError::OutOfBounds(10)
+        );
+    }
+
+    #[test]
+    fn test_encode_resp3() {
+        let value = RESP::Map(vec![
+            (RESP::BulkString(b"a".to_vec()), RESP::Double(1.5)),
+            (RESP::BulkString(b"b".to_vec()), RESP::Boolean(true)),
+            (RESP::BulkString(b"c".to_vec()), RESP::Null),
+        ]);
+        assert_eq!(
+            value.to_bytes(Protocol::RESP3),
+            b"%3\r\n$1\r\na\r\n,1.5\r\n$1\r\nb\r\n#t\r\n$1\r\nc\r\n_\r\n".to_vec()
+        );
+        assert_eq!(
+            value.to_bytes(Protocol::RESP2),
+            b"*6\r\n$1\r\na\r\n$3\r\n1.5\r\n$1\r\nb\r\n:1\r\n$1\r\nc\r\n$-1\r\n".to_vec()
+        );
+    }
+
+    #[test]
+    fn test_encode_null_array() {
+        assert_eq!(
+            RESP::NullArray.to_bytes(Protocol::RESP2),
+            b"*-1\r\n".to_vec()
+        );
+        assert_eq!(RESP::NullArray.to_bytes(Protocol::RESP3), b"_\r\n".to_vec());
+    }
+
+    #[test]
+    fn test_encode_pairs() {
+        let pairs = RESP::Pairs(vec![
+            (RESP::BulkString(b"a".to_vec()), RESP::Double(1.5)),
+            (RESP::BulkString(b"b".to_vec()), RESP::Double(2.0)),
+        ]);
+        assert_eq!(
+            pairs.to_bytes(Protocol::RESP3),
+            b"*2\r\n*2\r\n$1\r\na\r\n,1.5\r\n*2\r\n$1\r\nb\r\n,2\r\n".to_vec()
+        );
+        assert_eq!(
+            pairs.to_bytes(Protocol::RESP2),
+            b"*4\r\n$1\r\na\r\n$3\r\n1.5\r\n$1\r\nb\r\n$1\r\n2\r\n".to_vec()
+        );
+    }
+
+    #[test]
+    fn test_encode_resp3_aggregates() {
+        let set = RESP::Set(vec![RESP::Integer(1)]);
+        assert_eq!(set.to_bytes(Protocol::RESP3), b"~1\r\n:1\r\n".to_vec());
+        assert_eq!(set.to_bytes(Protocol::RESP2), b"*1\r\n:1\r\n".to_vec());
+
+        let push = RESP::Push(vec![RESP::Integer(1)]);
+        assert_eq!(push.to_bytes(Protocol::RESP3), b">1\r\n:1\r\n".to_vec());
+        assert_eq!(push.to_bytes(Protocol::RESP2), b"*1\r\n:1\r\n".to_vec());
+
+        let attribute = RESP::Attribute(
+            vec![(RESP::SimpleString(String::from("ttl")), RESP::Integer(5))],
+            Box::new(RESP::Integer(1)),
+        );
+        assert_eq!(
+            attribute.to_bytes(Protocol::RESP3),
+            b"|1\r\n+ttl\r\n:5\r\n:1\r\n".to_vec()
+        );
+        assert_eq!(attribute.to_bytes(Protocol::RESP2), b":1\r\n".to_vec());
+    }
+
+    #[test]
+    fn test_encode_resp3_strings() {
+        let verbatim = RESP::VerbatimString(String::from("txt"), b"hello".to_vec());
+        assert_eq!(
+            verbatim.to_bytes(Protocol::RESP3),
+            b"=9\r\ntxt:hello\r\n".to_vec()
+        );
+        assert_eq!(
+            verbatim.to_bytes(Protocol::RESP2),
+            b"$5\r\nhello\r\n".to_vec()
+        );
+
+        let number = RESP::BigNumber(String::from("123456789012345678901234567890"));
+        assert_eq!(
+            number.to_bytes(Protocol::RESP3),
+            b"(123456789012345678901234567890\r\n".to_vec()
+        );
+        assert_eq!(
+            number.to_bytes(Protocol::RESP2),
+            b"$30\r\n123456789012345678901234567890\r\n".to_vec()
+        );
+    }
+
+    #[test]
+    fn test_format_double() {
+        assert_eq!(format_double(1.0), "1");
+        assert_eq!(format_double(-0.25), "-0.25");
+        assert_eq!(format_double(1e300), "1e300");
+        assert_eq!(format_double(1.5e-7), "1.5e-7");
+        assert_eq!(format_double(f64::INFINITY), "inf");
+        assert_eq!(format_double(f64::NEG_INFINITY), "-inf");
+        assert_eq!(format_double(f64::NAN), "nan");
+    }
 }
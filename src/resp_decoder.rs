@@ -0,0 +1,439 @@
+use crate::{
+    resp::{bytes_to_resp, is_type_prefix, MAX_BULK_LENGTH, RESP},
+    resp_inline::parse_inline,
+    resp_result::{RESPError, RESPLength, RESPResult},
+};
+
+// Consumed bytes are only dropped from the front of the buffer
+// once they are at least this big, to avoid shifting the buffer
+// after every single frame
+const COMPACT_THRESHOLD: usize = 4096;
+
+// Aggregates are parsed recursively, frames nested deeper than
+// this are rejected before they can exhaust the stack.
+// Requests are flat arrays so no client needs more than a few levels.
+const MAX_NESTING_DEPTH: usize = 64;
+
+// Streaming decoder that accumulates the bytes read from a
+// connection and extracts complete RESP frames from them.
+// Partial frames stay in the buffer until the rest arrives.
+// Anything that doesn't start with a RESP type is read as
+// an inline command, as typed in telnet or netcat.
+#[derive(Debug)]
+pub struct RESPDecoder {
+    buffer: Vec<u8>,
+    position: usize,
+    scan: FrameScan,
+}
+
+// Progress of the check that the frame at the front of the buffer
+// is complete. It is kept between reads, so the bytes of a frame
+// that arrives in many reads are only scanned once, and the frame
+// is parsed a single time when it is known to be complete.
+#[derive(Debug)]
+struct FrameScan {
+    // offset of the next element to scan from the start of the frame
+    offset: usize,
+    // number of elements still expected by each open aggregate
+    remaining: Vec<usize>,
+}
+
+impl FrameScan {
+    fn new() -> Self {
+        Self {
+            offset: 0,
+            remaining: vec![1],
+        }
+    }
+}
+
+// Outcome of scanning the header of a single element
+enum ScanStep {
+    // the element ends at the given offset and opens
+    // an aggregate of the given number of elements
+    Element(usize, usize),
+    Incomplete,
+    // the element is malformed, the parser reports the error
+    Invalid,
+}
+
+// Offset right after the next \r\n starting from `from`
+fn line_end(buffer: &[u8], from: usize) -> Option<usize> {
+    buffer[from..]
+        .windows(2)
+        .position(|w| w == b"\r\n")
+        .map(|p| from + p + 2)
+}
+
+fn scan_element(buffer: &[u8], offset: usize) -> ScanStep {
+    let end = match line_end(buffer, offset) {
+        Some(end) => end,
+        None => return ScanStep::Incomplete,
+    };
+    let prefix = buffer[offset];
+    if !is_type_prefix(prefix) {
+        return ScanStep::Invalid;
+    }
+    if !matches!(prefix, b'$' | b'=' | b'*' | b'~' | b'>' | b'%' | b'|') {
+        return ScanStep::Element(end, 0);
+    }
+
+    let length: RESPLength = match std::str::from_utf8(&buffer[offset + 1..end - 2])
+        .ok()
+        .and_then(|line| line.parse().ok())
+    {
+        Some(length) => length,
+        None => return ScanStep::Invalid,
+    };
+    match prefix {
+        b'$' if length == -1 => ScanStep::Element(end, 0),
+        b'$' | b'=' => {
+            if !(0..=MAX_BULK_LENGTH).contains(&length) {
+                return ScanStep::Invalid;
+            }
+            let element_end = end + length as usize + 2;
+            if element_end > buffer.len() {
+                ScanStep::Incomplete
+            } else {
+                ScanStep::Element(element_end, 0)
+            }
+        }
+        _ if length < 0 => ScanStep::Invalid,
+        b'%' => ScanStep::Element(end, 2 * length as usize),
+        // the attributes are followed by the value they describe
+        b'|' => ScanStep::Element(end, 2 * length as usize + 1),
+        _ => ScanStep::Element(end, length as usize),
+    }
+}
+
+impl RESPDecoder {
+    pub fn new() -> Self {
+        Self {
+            buffer: Vec::new(),
+            position: 0,
+            scan: FrameScan::new(),
+        }
+    }
+
+    // Resume the scan of the frame at the front of the buffer.
+    // Returns true once the frame can be handed to the parser.
+    fn frame_ready(&mut self) -> RESPResult<bool> {
+        let buffer = &self.buffer[self.position..];
+        let scan = &mut self.scan;
+        while let Some(remaining) = scan.remaining.last_mut() {
+            if *remaining == 0 {
+                scan.remaining.pop();
+                continue;
+            }
+            match scan_element(buffer, scan.offset) {
+                ScanStep::Element(end, elements) => {
+                    *remaining -= 1;
+                    scan.offset = end;
+                    if elements > 0 {
+                        // the first level holds the frame itself
+                        if scan.remaining.len() > MAX_NESTING_DEPTH {
+                            return Err(RESPError::NestingTooDeep);
+                        }
+                        scan.remaining.push(elements);
+                    }
+                }
+                ScanStep::Incomplete => return Ok(false),
+                ScanStep::Invalid => return Ok(true),
+            }
+        }
+        Ok(true)
+    }
+
+    // Append the bytes received from the stream
+    pub fn extend(&mut self, data: &[u8]) {
+        if self.position >= COMPACT_THRESHOLD {
+            self.buffer.drain(..self.position);
+            self.position = 0;
+        }
+        self.buffer.extend_from_slice(data);
+    }
+
+    // Number of bytes received but not decoded yet
+    pub fn pending(&self) -> usize {
+        self.buffer.len() - self.position
+    }
+
+    // Decode the next complete frame.
+    // Returns Ok(None) when the buffer holds only part of a frame.
+    pub fn decode(&mut self) -> RESPResult<Option<RESP>> {
+        if self.pending() == 0 {
+            return Ok(None);
+        }
+
+        let mut index: usize = 0;
+        let result = if is_type_prefix(self.buffer[self.position]) {
+            if !self.frame_ready()? {
+                return Ok(None);
+            }
+            bytes_to_resp(&self.buffer[self.position..], &mut index).map(Some)
+        } else {
+            parse_inline(&self.buffer[self.position..], &mut index)
+        };
+        match result {
+            Ok(None) => Ok(None),
+            Ok(Some(resp)) => {
+                self.scan = FrameScan::new();
+                self.position += index;
+                if self.position == self.buffer.len() {
+                    self.buffer.clear();
+                    self.position = 0;
+                }
+                Ok(Some(resp))
+            }
+            Err(RESPError::OutOfBounds(_)) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_empty() {
+        let mut decoder = RESPDecoder::new();
+        assert_eq!(decoder.decode().unwrap(), None);
+        assert_eq!(decoder.pending(), 0);
+    }
+
+    #[test]
+    fn test_decode_complete_frame() {
+        let mut decoder = RESPDecoder::new();
+        decoder.extend("*1\r\n$4\r\nPING\r\n".as_bytes());
+        assert_eq!(
+            decoder.decode().unwrap(),
+            Some(RESP::Array(vec![RESP::BulkString(b"PING".to_vec())]))
+        );
+        assert_eq!(decoder.pending(), 0);
+    }
+
+    #[test]
+    fn test_decode_frame_split_across_reads() {
+        let mut decoder = RESPDecoder::new();
+        decoder.extend("*2\r\n$4\r\nECHO\r\n$5\r\nhe".as_bytes());
+        assert_eq!(decoder.decode().unwrap(), None);
+
+        decoder.extend("llo\r".as_bytes());
+        assert_eq!(decoder.decode().unwrap(), None);
+
+        decoder.extend("\n".as_bytes());
+        assert_eq!(
+            decoder.decode().unwrap(),
+            Some(RESP::Array(vec![
+                RESP::BulkString(b"ECHO".to_vec()),
+                RESP::BulkString(b"hello".to_vec())
+            ]))
+        );
+        assert_eq!(decoder.pending(), 0);
+    }
+
+    #[test]
+    fn test_decode_byte_by_byte() {
+        let frame = "*3\r\n$3\r\nSET\r\n$3\r\nkey\r\n$5\r\nvalue\r\n".as_bytes();
+        let mut decoder = RESPDecoder::new();
+        for (i, byte) in frame.iter().enumerate() {
+            decoder.extend(&[*byte]);
+            let output = decoder.decode().unwrap();
+            if i < frame.len() - 1 {
+                assert_eq!(output, None);
+            } else {
+                assert_eq!(
+                    output,
+                    Some(RESP::Array(vec![
+                        RESP::BulkString(b"SET".to_vec()),
+                        RESP::BulkString(b"key".to_vec()),
+                        RESP::BulkString(b"value".to_vec())
+                    ]))
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_decode_large_bulk_string() {
+        let value = "x".repeat(20000);
+        let frame = format!("*2\r\n$4\r\nECHO\r\n${}\r\n{}\r\n", value.len(), value);
+        let mut decoder = RESPDecoder::new();
+        for chunk in frame.as_bytes().chunks(512) {
+            decoder.extend(chunk);
+        }
+        assert_eq!(
+            decoder.decode().unwrap(),
+            Some(RESP::Array(vec![
+                RESP::BulkString(b"ECHO".to_vec()),
+                RESP::BulkString(value.into_bytes())
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_decode_large_array_in_small_reads() {
+        let mut frame = String::from("*10001\r\n$4\r\nMSET\r\n");
+        for i in 0..5000 {
+            frame.push_str(&format!("$5\r\nk{:04}\r\n$5\r\nvalue\r\n", i));
+        }
+        let mut decoder = RESPDecoder::new();
+        for chunk in frame.as_bytes().chunks(64) {
+            assert_eq!(decoder.decode().unwrap(), None);
+            decoder.extend(chunk);
+        }
+        // the scan resumes where the previous read stopped
+        assert!(decoder.scan.offset > frame.len() - 64);
+        match decoder.decode().unwrap() {
+            Some(RESP::Array(elements)) => assert_eq!(elements.len(), 10001),
+            other => panic!("unexpected {:?}", other),
+        }
+        assert_eq!(decoder.pending(), 0);
+    }
+
+    #[test]
+    fn test_decode_nested_frame_split_across_reads() {
+        let frame = "*2\r\n%1\r\n+key\r\n*1\r\n:1\r\n|1\r\n+a\r\n+b\r\n$1\r\nv\r\n+NEXT\r\n";
+        let mut decoder = RESPDecoder::new();
+        for byte in frame.as_bytes().iter().take(frame.len() - 7) {
+            assert_eq!(decoder.decode().unwrap(), None);
+            decoder.extend(&[*byte]);
+        }
+        decoder.extend(&frame.as_bytes()[frame.len() - 7..]);
+        assert_eq!(
+            decoder.decode().unwrap(),
+            Some(RESP::Array(vec![
+                RESP::Map(vec![(
+                    RESP::SimpleString(String::from("key")),
+                    RESP::Array(vec![RESP::Integer(1)])
+                )]),
+                RESP::Attribute(
+                    vec![(
+                        RESP::SimpleString(String::from("a")),
+                        RESP::SimpleString(String::from("b"))
+                    )],
+                    Box::new(RESP::BulkString(b"v".to_vec()))
+                )
+            ]))
+        );
+        assert_eq!(
+            decoder.decode().unwrap(),
+            Some(RESP::SimpleString(String::from("NEXT")))
+        );
+    }
+
+    #[test]
+    fn test_decode_keeps_leftover_bytes() {
+        let mut decoder = RESPDecoder::new();
+        decoder.extend("+OK\r\n+PA".as_bytes());
+        assert_eq!(
+            decoder.decode().unwrap(),
+            Some(RESP::SimpleString(String::from("OK")))
+        );
+        assert_eq!(decoder.pending(), 3);
+        decoder.extend("RTIAL\r\n".as_bytes());
+        assert_eq!(
+            decoder.decode().unwrap(),
+            Some(RESP::SimpleString(String::from("PARTIAL")))
+        );
+        assert_eq!(decoder.pending(), 0);
+    }
+
+    #[test]
+    fn test_decode_multiple_frames() {
+        let mut decoder = RESPDecoder::new();
+        decoder.extend("+ONE\r\n+TWO\r\n+THR".as_bytes());
+        assert_eq!(
+            decoder.decode().unwrap(),
+            Some(RESP::SimpleString(String::from("ONE")))
+        );
+        assert_eq!(
+            decoder.decode().unwrap(),
+            Some(RESP::SimpleString(String::from("TWO")))
+        );
+        assert_eq!(decoder.decode().unwrap(), None);
+        assert_eq!(decoder.pending(), 4);
+    }
+
+    #[test]
+    fn test_decode_protocol_error() {
+        let mut decoder = RESPDecoder::new();
+        decoder.extend("$3\r\nabcde\r\n".as_bytes());
+        assert_eq!(decoder.decode().unwrap_err(), RESPError::MissingTerminator);
+    }
+
+    #[test]
+    fn test_decode_nesting_too_deep() {
+        let mut decoder = RESPDecoder::new();
+        decoder.extend("*1\r\n".repeat(100000).as_bytes());
+        assert_eq!(decoder.decode().unwrap_err(), RESPError::NestingTooDeep);
+    }
+
+    #[test]
+    fn test_decode_nesting_at_limit() {
+        let mut decoder = RESPDecoder::new();
+        let frame = format!("{}:1\r\n", "*1\r\n".repeat(MAX_NESTING_DEPTH));
+        decoder.extend(frame.as_bytes());
+        let mut value = decoder.decode().unwrap().unwrap();
+        for _ in 0..MAX_NESTING_DEPTH {
+            value = match value {
+                RESP::Array(mut elements) => elements.pop().unwrap(),
+                other => panic!("unexpected {:?}", other),
+            };
+        }
+        assert_eq!(value, RESP::Integer(1));
+
+        decoder.extend(format!("*1\r\n{}", frame).as_bytes());
+        assert_eq!(decoder.decode().unwrap_err(), RESPError::NestingTooDeep);
+    }
+
+    #[test]
+    fn test_decode_inline_commands() {
+        let mut decoder = RESPDecoder::new();
+        decoder.extend("PING\r\nSET key \"a value\"\nGE".as_bytes());
+        assert_eq!(
+            decoder.decode().unwrap(),
+            Some(RESP::Array(vec![RESP::BulkString(b"PING".to_vec())]))
+        );
+        assert_eq!(
+            decoder.decode().unwrap(),
+            Some(RESP::Array(vec![
+                RESP::BulkString(b"SET".to_vec()),
+                RESP::BulkString(b"key".to_vec()),
+                RESP::BulkString(b"a value".to_vec())
+            ]))
+        );
+        assert_eq!(decoder.decode().unwrap(), None);
+        decoder.extend("T key\r\n".as_bytes());
+        assert_eq!(
+            decoder.decode().unwrap(),
+            Some(RESP::Array(vec![
+                RESP::BulkString(b"GET".to_vec()),
+                RESP::BulkString(b"key".to_vec())
+            ]))
+        );
+        assert_eq!(decoder.pending(), 0);
+    }
+
+    #[test]
+    fn test_decode_inline_mixed_with_resp() {
+        let mut decoder = RESPDecoder::new();
+        decoder.extend("ping\n*1\r\n$4\r\nPING\r\n".as_bytes());
+        assert_eq!(
+            decoder.decode().unwrap(),
+            Some(RESP::Array(vec![RESP::BulkString(b"ping".to_vec())]))
+        );
+        assert_eq!(
+            decoder.decode().unwrap(),
+            Some(RESP::Array(vec![RESP::BulkString(b"PING".to_vec())]))
+        );
+    }
+
+    #[test]
+    fn test_decode_inline_unbalanced_quotes() {
+        let mut decoder = RESPDecoder::new();
+        decoder.extend("ECHO \"hello\r\n".as_bytes());
+        assert_eq!(decoder.decode().unwrap_err(), RESPError::UnbalancedQuotes);
+    }
+}
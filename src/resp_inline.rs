@@ -0,0 +1,251 @@
+use crate::{
+    resp::RESP,
+    resp_result::{RESPError, RESPResult},
+};
+
+// Same limit as PROTO_INLINE_MAX_SIZE in Redis
+pub const MAX_INLINE_LENGTH: usize = 64 * 1024;
+
+fn is_space(c: u8) -> bool {
+    matches!(c, b' ' | b'\t' | b'\n' | b'\r' | 0x0b | 0x0c)
+}
+
+fn hex_digit(c: u8) -> Option<u8> {
+    (c as char).to_digit(16).map(|d| d as u8)
+}
+
+// Split a line into arguments following the rules of sdssplitargs:
+// arguments are separated by spaces and can be quoted.
+// Double quotes accept escapes like \n, \t or \xff,
+// single quotes only accept the escaped quote \'.
+// A closing quote must be followed by a space or the end of the line.
+pub fn split_arguments(line: &[u8]) -> RESPResult<Vec<Vec<u8>>> {
+    let mut arguments = Vec::new();
+    let mut index: usize = 0;
+
+    loop {
+        while index < line.len() && is_space(line[index]) {
+            index += 1;
+        }
+        if index == line.len() {
+            return Ok(arguments);
+        }
+
+        let mut current = Vec::new();
+        let mut in_double_quotes = false;
+        let mut in_single_quotes = false;
+        loop {
+            if in_double_quotes {
+                if index >= line.len() {
+                    return Err(RESPError::UnbalancedQuotes);
+                }
+                let c = line[index];
+                if c == b'\\' && index + 3 < line.len() && line[index + 1] == b'x' {
+                    if let (Some(high), Some(low)) =
+                        (hex_digit(line[index + 2]), hex_digit(line[index + 3]))
+                    {
+                        current.push(high * 16 + low);
+                        index += 4;
+                        continue;
+                    }
+                }
+                if c == b'\\' && index + 1 < line.len() {
+                    index += 1;
+                    current.push(match line[index] {
+                        b'n' => b'\n',
+                        b'r' => b'\r',
+                        b't' => b'\t',
+                        b'b' => 0x08,
+                        b'a' => 0x07,
+                        other => other,
+                    });
+                } else if c == b'"' {
+                    if index + 1 < line.len() && !is_space(line[index + 1]) {
+                        return Err(RESPError::UnbalancedQuotes);
+                    }
+                    index += 1;
+                    break;
+                } else {
+                    current.push(c);
+                }
+            } else if in_single_quotes {
+                if index >= line.len() {
+                    return Err(RESPError::UnbalancedQuotes);
+                }
+                let c = line[index];
+                if c == b'\\' && index + 1 < line.len() && line[index + 1] == b'\'' {
+                    index += 1;
+                    current.push(b'\'');
+                } else if c == b'\'' {
+                    if index + 1 < line.len() && !is_space(line[index + 1]) {
+                        return Err(RESPError::UnbalancedQuotes);
+                    }
+                    index += 1;
+                    break;
+                } else {
+                    current.push(c);
+                }
+            } else {
+                if index >= line.len() {
+                    break;
+                }
+                match line[index] {
+                    c if is_space(c) => break,
+                    b'"' => in_double_quotes = true,
+                    b'\'' => in_single_quotes = true,
+                    c => current.push(c),
+                }
+            }
+            index += 1;
+        }
+        arguments.push(current);
+    }
+}
+
+// Parse an inline command, a line of space separated arguments
+// terminated by \n or \r\n as typed in telnet or netcat.
+// Returns Ok(None) while the line is incomplete and an empty
+// array for a blank line, which carries no command.
+pub fn parse_inline(buffer: &[u8], index: &mut usize) -> RESPResult<Option<RESP>> {
+    let end = match buffer[*index..].iter().position(|&c| c == b'\n') {
+        Some(position) => *index + position,
+        None => {
+            if buffer.len() - *index > MAX_INLINE_LENGTH {
+                return Err(RESPError::InlineTooBig);
+            }
+            return Ok(None);
+        }
+    };
+    if end - *index > MAX_INLINE_LENGTH {
+        return Err(RESPError::InlineTooBig);
+    }
+
+    let mut line = &buffer[*index..end];
+    if let Some(stripped) = line.strip_suffix(b"\r") {
+        line = stripped;
+    }
+    let arguments = split_arguments(line)?;
+    *index = end + 1;
+
+    Ok(Some(RESP::Array(
+        arguments.into_iter().map(RESP::BulkString).collect(),
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split(line: &str) -> Vec<String> {
+        split_arguments(line.as_bytes())
+            .unwrap()
+            .into_iter()
+            .map(|a| String::from_utf8(a).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_split_arguments() {
+        assert_eq!(split("PING"), vec!["PING"]);
+        assert_eq!(split("  set   key  value "), vec!["set", "key", "value"]);
+        assert_eq!(split("\tget\tkey"), vec!["get", "key"]);
+        assert_eq!(split(""), Vec::<String>::new());
+        assert_eq!(split("   "), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_split_arguments_double_quotes() {
+        assert_eq!(
+            split("set key \"hello world\""),
+            vec!["set", "key", "hello world"]
+        );
+        assert_eq!(
+            split("echo \"a\\nb\\t\\\"c\\\"\""),
+            vec!["echo", "a\nb\t\"c\""]
+        );
+        assert_eq!(split("echo \"\""), vec!["echo", ""]);
+        assert_eq!(
+            split_arguments(b"echo \"\\x41\\xff\\xzz\"").unwrap(),
+            vec![b"echo".to_vec(), vec![b'A', 0xff, b'x', b'z', b'z']]
+        );
+    }
+
+    #[test]
+    fn test_split_arguments_single_quotes() {
+        assert_eq!(split("echo 'it\\'s \\n'"), vec!["echo", "it's \\n"]);
+        assert_eq!(split("echo ''"), vec!["echo", ""]);
+    }
+
+    #[test]
+    fn test_split_arguments_quote_inside_word() {
+        assert_eq!(split("echo foo\"bar baz\""), vec!["echo", "foobar baz"]);
+    }
+
+    #[test]
+    fn test_split_arguments_unbalanced_quotes() {
+        assert_eq!(
+            split_arguments(b"echo \"hello").unwrap_err(),
+            RESPError::UnbalancedQuotes
+        );
+        assert_eq!(
+            split_arguments(b"echo 'hello").unwrap_err(),
+            RESPError::UnbalancedQuotes
+        );
+        assert_eq!(
+            split_arguments(b"echo \"hello\"world").unwrap_err(),
+            RESPError::UnbalancedQuotes
+        );
+    }
+
+    #[test]
+    fn test_parse_inline() {
+        let buffer = "SET key value\r\nGET key\n".as_bytes();
+        let mut index: usize = 0;
+        assert_eq!(
+            parse_inline(buffer, &mut index).unwrap(),
+            Some(RESP::Array(vec![
+                RESP::BulkString(b"SET".to_vec()),
+                RESP::BulkString(b"key".to_vec()),
+                RESP::BulkString(b"value".to_vec())
+            ]))
+        );
+        assert_eq!(index, 15);
+        assert_eq!(
+            parse_inline(buffer, &mut index).unwrap(),
+            Some(RESP::Array(vec![
+                RESP::BulkString(b"GET".to_vec()),
+                RESP::BulkString(b"key".to_vec())
+            ]))
+        );
+        assert_eq!(index, buffer.len());
+    }
+
+    #[test]
+    fn test_parse_inline_incomplete() {
+        let buffer = "PIN".as_bytes();
+        let mut index: usize = 0;
+        assert_eq!(parse_inline(buffer, &mut index).unwrap(), None);
+        assert_eq!(index, 0);
+    }
+
+    #[test]
+    fn test_parse_inline_empty_line() {
+        let buffer = "\r\n".as_bytes();
+        let mut index: usize = 0;
+        assert_eq!(
+            parse_inline(buffer, &mut index).unwrap(),
+            Some(RESP::Array(vec![]))
+        );
+        assert_eq!(index, 2);
+    }
+
+    #[test]
+    fn test_parse_inline_too_big() {
+        let buffer = "a".repeat(MAX_INLINE_LENGTH + 1);
+        let mut index: usize = 0;
+        assert_eq!(
+            parse_inline(buffer.as_bytes(), &mut index).unwrap_err(),
+            RESPError::InlineTooBig
+        );
+    }
+}
@@ -1,5 +1,8 @@
 use core::fmt;
-use std::{num::ParseIntError, string::FromUtf8Error};
+use std::{
+    num::{ParseFloatError, ParseIntError},
+    string::FromUtf8Error,
+};
 
 #[derive(Debug, PartialEq)]
 pub enum RESPError {
@@ -7,8 +10,13 @@ pub enum RESPError {
     Unknown,
     WrongType,
     ParseInt,
+    ParseFloat,
     IncorrectLength(i32),
     OutOfBounds(usize),
+    MissingTerminator,
+    UnbalancedQuotes,
+    InlineTooBig,
+    NestingTooDeep,
 }
 
 pub type RESPResult<T> = Result<T, RESPError>;
@@ -22,7 +30,12 @@ impl fmt::Display for RESPError {
             RESPError::WrongType => write!(f, "Wrong prefix for RESP type"),
             RESPError::Unknown => write!(f, "Unknown format of RESP string"),
             RESPError::ParseInt => write!(f, "Cannot parse string to integer"),
+            RESPError::ParseFloat => write!(f, "Cannot parse string to double"),
             RESPError::IncorrectLength(_) => write!(f, "incorrect length of buk string"),
+            RESPError::MissingTerminator => write!(f, "Missing \\r\\n after bulk string"),
+            RESPError::UnbalancedQuotes => write!(f, "unbalanced quotes in request"),
+            RESPError::InlineTooBig => write!(f, "too big inline request"),
+            RESPError::NestingTooDeep => write!(f, "too deep nesting of aggregate types"),
         }
     }
 }
@@ -38,3 +51,9 @@ impl From<ParseIntError> for RESPError {
         Self::ParseInt
     }
 }
+
+impl From<ParseFloatError> for RESPError {
+    fn from(_err: ParseFloatError) -> Self {
+        Self::ParseFloat
+    }
+}
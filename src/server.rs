@@ -1,31 +1,48 @@
-use std::time::Duration;
+use std::collections::VecDeque;
 
 use tokio::sync::mpsc;
 
 use crate::{
-    commands::{echo, get, ping, set},
+    active_expire::CYCLE_PERIOD,
+    blocking::BlockedClients,
+    commands::{
+        append, blmove, blpop, bzpopmin, copy, del, echo, exists, expire, get, getdel, getex,
+        getrange, getset, hdel, hello, hexists, hexpire, hget, hgetall, hincrby, hincrbyfloat,
+        hlen, hmget, hpersist, hrandfield, hscan, hset, hsetnx, hstrlen, httl, incr, incrbyfloat,
+        keys, keytype, lindex, linsert, llen, lmove, lmpop, lpop, lpos, lpush, lrange, lrem, lset,
+        ltrim, mget, mset, persist, ping, rename, sadd, scan, scard, set, setex, setnx, setrange,
+        sinter, sintercard, sismember, smembers, smove, spop, srandmember, srem, strlen, ttl, zadd,
+        zcard, zincrby, zmpop, zrange, zrank, zrem, zscore, zunionstore,
+    },
     connection::ConnectionMessage,
     request::Request,
     resp::RESP,
     server_result::{ServerError, ServerValue},
-    storage::{self, Storage},
+    storage::Storage,
 };
 
 pub struct Server {
     pub storage: Option<Storage>,
+    pub blocked: BlockedClients,
 }
 
 impl Server {
+    #[cfg(test)]
     pub fn new() -> Self {
-        Self { storage: None }
+        Self {
+            storage: None,
+            blocked: BlockedClients::new(),
+        }
     }
 
     pub fn with_new(storage: Storage) -> Self {
         Self {
             storage: Some(storage),
+            blocked: BlockedClients::new(),
         }
     }
 
+    #[cfg(test)]
     pub fn set_storage(&mut self, storage: Storage) {
         self.storage = Some(storage);
     }
@@ -37,10 +54,16 @@ impl Server {
         };
         storage.expire_keys();
     }
+
+    pub fn rehash(&mut self) {
+        if let Some(storage) = self.storage.as_mut() {
+            storage.rehash();
+        }
+    }
 }
 
 pub async fn run_server(mut server: Server, mut crx: mpsc::Receiver<ConnectionMessage>) {
-    let mut internal_timer = tokio::time::interval(Duration::from_millis(10));
+    let mut internal_timer = tokio::time::interval(CYCLE_PERIOD);
 
     loop {
         tokio::select! {
@@ -53,12 +76,102 @@ pub async fn run_server(mut server: Server, mut crx: mpsc::Receiver<ConnectionMe
             }
             _ = internal_timer.tick() =>{
                 server.expire_keys();
+                server.rehash();
+                unblock_timed_out(&mut server).await;
             }
         }
     }
 }
 
+// Requests of a blocked client wait for it to be unblocked,
+// so that the replies keep the order of the requests
 pub async fn process_request(request: Request, server: &mut Server) {
+    if let Some(request) = server.blocked.queue_if_blocked(request) {
+        execute_request(request, server).await;
+        serve_blocked_clients(server).await;
+    }
+}
+
+// Process the requests a client sent while it was blocked,
+// until one of them blocks it again
+async fn resume(queued: VecDeque<Request>, server: &mut Server) {
+    for request in queued {
+        if let Some(request) = server.blocked.queue_if_blocked(request) {
+            execute_request(request, server).await;
+        }
+    }
+}
+
+// Serve the clients blocked on the keys which received values,
+// in the order they blocked and while there are values left
+async fn serve_blocked_clients(server: &mut Server) {
+    loop {
+        let keys = match server.storage.as_mut() {
+            Some(storage) => storage.take_ready_keys(),
+            None => return,
+        };
+        if keys.is_empty() {
+            return;
+        }
+        for key in keys {
+            for id in server.blocked.waiting_on(&key) {
+                let (Some(storage), Some(client)) =
+                    (server.storage.as_mut(), server.blocked.get(id))
+                else {
+                    continue;
+                };
+                // a client which disconnected while blocked
+                // must not take the values of the next ones
+                if client.request.sender.is_closed() {
+                    server.blocked.unblock(id);
+                    continue;
+                }
+                if storage
+                    .key_type(&key)
+                    .is_some_and(|key_type| key_type != client.command.key_type())
+                {
+                    continue;
+                }
+                let result = client.command.attempt(storage, Some(&key));
+                // the values are gone, the next clients would not get any either
+                if let Ok(None) = result {
+                    break;
+                }
+                let Some(client) = server.blocked.unblock(id) else {
+                    continue;
+                };
+                match result {
+                    Ok(Some(reply)) => client.request.data(ServerValue::RESP(reply)).await,
+                    Ok(None) => {}
+                    Err(e) => client.request.error(ServerError::from(e)).await,
+                }
+                resume(client.queued, server).await;
+            }
+        }
+    }
+}
+
+// Reply to the blocked clients whose timeout expired
+async fn unblock_timed_out(server: &mut Server) {
+    if server.blocked.is_empty() {
+        return;
+    }
+    server.blocked.remove_disconnected();
+    let now = match server.storage.as_ref() {
+        Some(storage) => storage.now(),
+        None => return,
+    };
+    for client in server.blocked.timed_out(now) {
+        client
+            .request
+            .data(ServerValue::RESP(client.command.timeout_reply()))
+            .await;
+        resume(client.queued, server).await;
+    }
+    serve_blocked_clients(server).await;
+}
+
+pub(crate) async fn execute_request(request: Request, server: &mut Server) {
     let elements = match &request.value {
         RESP::Array(v) => v,
         _ => {
@@ -76,21 +189,113 @@ pub async fn process_request(request: Request, server: &mut Server) {
             }
         }
     }
-    let command_name = command[0].to_lowercase();
+    // an empty command is silently ignored, as Redis does
+    if command.is_empty() {
+        return;
+    }
+    let command_name = String::from_utf8_lossy(&command[0]).to_lowercase();
     match command_name.as_str() {
+        "append" => append::command(server, &request, &command).await,
+        "blmove" | "brpoplpush" => blmove::command(server, &request, &command).await,
+        "blpop" | "brpop" => blpop::command(server, &request, &command).await,
+        "bzpopmin" | "bzpopmax" => bzpopmin::command(server, &request, &command).await,
+        "copy" => copy::command(server, &request, &command).await,
+        "del" | "unlink" => del::command(server, &request, &command).await,
         "echo" => {
             echo::command(server, &request, &command).await;
         }
+        "exists" | "touch" => exists::command(server, &request, &command).await,
+        "expire" | "pexpire" | "expireat" | "pexpireat" => {
+            expire::command(server, &request, &command).await
+        }
         "get" => {
             get::command(server, &request, &command).await;
         }
+        "getdel" => getdel::command(server, &request, &command).await,
+        "getex" => getex::command(server, &request, &command).await,
+        "getrange" => getrange::command(server, &request, &command).await,
+        "getset" => getset::command(server, &request, &command).await,
+        "hdel" => hdel::command(server, &request, &command).await,
+        "hello" => hello::command(server, &request, &command).await,
+        "hexists" => hexists::command(server, &request, &command).await,
+        "hexpire" | "hpexpire" | "hexpireat" | "hpexpireat" => {
+            hexpire::command(server, &request, &command).await
+        }
+        "hget" => hget::command(server, &request, &command).await,
+        "hgetall" | "hkeys" | "hvals" => hgetall::command(server, &request, &command).await,
+        "hincrby" => hincrby::command(server, &request, &command).await,
+        "hincrbyfloat" => hincrbyfloat::command(server, &request, &command).await,
+        "hlen" => hlen::command(server, &request, &command).await,
+        "hmget" => hmget::command(server, &request, &command).await,
+        "hpersist" => hpersist::command(server, &request, &command).await,
+        "hrandfield" => hrandfield::command(server, &request, &command).await,
+        "hscan" => hscan::command(server, &request, &command).await,
+        "hset" | "hmset" => hset::command(server, &request, &command).await,
+        "hsetnx" => hsetnx::command(server, &request, &command).await,
+        "hstrlen" => hstrlen::command(server, &request, &command).await,
+        "httl" | "hpttl" | "hexpiretime" | "hpexpiretime" => {
+            httl::command(server, &request, &command).await
+        }
+        "incr" | "decr" | "incrby" | "decrby" => incr::command(server, &request, &command).await,
+        "incrbyfloat" => incrbyfloat::command(server, &request, &command).await,
+        "keys" => keys::command(server, &request, &command).await,
+        "lindex" => lindex::command(server, &request, &command).await,
+        "linsert" => linsert::command(server, &request, &command).await,
+        "llen" => llen::command(server, &request, &command).await,
+        "lmove" | "rpoplpush" => lmove::command(server, &request, &command).await,
+        "lmpop" | "blmpop" => lmpop::command(server, &request, &command).await,
+        "lpop" | "rpop" => lpop::command(server, &request, &command).await,
+        "lpos" => lpos::command(server, &request, &command).await,
+        "lpush" | "rpush" | "lpushx" | "rpushx" => lpush::command(server, &request, &command).await,
+        "lrange" => lrange::command(server, &request, &command).await,
+        "lrem" => lrem::command(server, &request, &command).await,
+        "lset" => lset::command(server, &request, &command).await,
+        "ltrim" => ltrim::command(server, &request, &command).await,
+        "mget" => mget::command(server, &request, &command).await,
+        "mset" | "msetnx" => mset::command(server, &request, &command).await,
+        "persist" => persist::command(server, &request, &command).await,
         "ping" => {
             ping::command(server, &request, &command).await;
         }
+        "rename" | "renamenx" => rename::command(server, &request, &command).await,
+        "sadd" => sadd::command(server, &request, &command).await,
+        "scan" => scan::command(server, &request, &command).await,
+        "scard" => scard::command(server, &request, &command).await,
+        "sdiff" | "sdiffstore" | "sinter" | "sinterstore" | "sunion" | "sunionstore" => {
+            sinter::command(server, &request, &command).await
+        }
         "set" => set::command(server, &request, &command).await,
+        "setex" | "psetex" => setex::command(server, &request, &command).await,
+        "setnx" => setnx::command(server, &request, &command).await,
+        "setrange" => setrange::command(server, &request, &command).await,
+        "sintercard" => sintercard::command(server, &request, &command).await,
+        "sismember" => sismember::command(server, &request, &command).await,
+        "smembers" => smembers::command(server, &request, &command).await,
+        "smove" => smove::command(server, &request, &command).await,
+        "spop" => spop::command(server, &request, &command).await,
+        "srandmember" => srandmember::command(server, &request, &command).await,
+        "srem" => srem::command(server, &request, &command).await,
+        "strlen" => strlen::command(server, &request, &command).await,
+        "type" => keytype::command(server, &request, &command).await,
+        "ttl" | "pttl" | "expiretime" | "pexpiretime" => {
+            ttl::command(server, &request, &command).await
+        }
+        "zadd" => zadd::command(server, &request, &command).await,
+        "zcard" => zcard::command(server, &request, &command).await,
+        "zincrby" => zincrby::command(server, &request, &command).await,
+        "zmpop" | "bzmpop" => zmpop::command(server, &request, &command).await,
+        "zrange" | "zrangestore" => zrange::command(server, &request, &command).await,
+        "zrank" | "zrevrank" => zrank::command(server, &request, &command).await,
+        "zrem" => zrem::command(server, &request, &command).await,
+        "zscore" => zscore::command(server, &request, &command).await,
+        "zunionstore" | "zinterstore" | "zdiffstore" => {
+            zunionstore::command(server, &request, &command).await
+        }
         _ => {
             request
-                .error(ServerError::CommandNotAvailable(command[0].clone()))
+                .error(ServerError::CommandNotAvailable(
+                    String::from_utf8_lossy(&command[0]).into_owned(),
+                ))
                 .await;
         }
     }
@@ -98,7 +303,9 @@ pub async fn process_request(request: Request, server: &mut Server) {
 
 #[cfg(test)]
 mod tests {
-    use crate::server_result::ServerMessage;
+    use crate::clock::ManualClock;
+    use crate::server_result::{ServerMessage, ServerValue};
+    use std::time::{Duration, UNIX_EPOCH};
     use tokio::sync::mpsc;
 
     use super::*;
@@ -106,8 +313,9 @@ mod tests {
     async fn test_process_request_ping() {
         let (connection_sender, mut connection_receiver) = mpsc::channel::<ServerMessage>(32);
         let request = Request {
-            value: RESP::Array(vec![RESP::BulkString(String::from("PING"))]),
+            value: RESP::Array(vec![RESP::BulkString(b"PING".to_vec())]),
             sender: connection_sender,
+            client_id: 0,
         };
         let mut server = Server::with_new(Storage::new());
         process_request(request, &mut server).await;
@@ -120,8 +328,9 @@ mod tests {
     async fn test_process_request_not_array() {
         let (connection_sender, mut connection_receiver) = mpsc::channel::<ServerMessage>(32);
         let request = Request {
-            value: RESP::BulkString(String::from("PING")),
+            value: RESP::BulkString(b"PING".to_vec()),
             sender: connection_sender,
+            client_id: 0,
         };
         let mut server = Server::with_new(Storage::new());
         process_request(request, &mut server).await;
@@ -137,6 +346,7 @@ mod tests {
         let request = Request {
             value: RESP::Array(vec![RESP::SimpleString(String::from("PING"))]),
             sender: connection_sender,
+            client_id: 0,
         };
         let mut server = Server::with_new(Storage::new());
         process_request(request, &mut server).await;
@@ -151,25 +361,320 @@ mod tests {
         let (connection_sender, mut connection_receiver) = mpsc::channel::<ServerMessage>(32);
         let request = Request {
             value: RESP::Array(vec![
-                RESP::BulkString(String::from("ECHO")),
-                RESP::BulkString(String::from("42")),
+                RESP::BulkString(b"ECHO".to_vec()),
+                RESP::BulkString(b"42".to_vec()),
             ]),
             sender: connection_sender,
+            client_id: 0,
         };
         let mut server = Server::with_new(Storage::new());
         process_request(request, &mut server).await;
         assert_eq!(
             connection_receiver.try_recv().unwrap(),
-            ServerMessage::Data(ServerValue::RESP(RESP::BulkString(String::from("42"))))
+            ServerMessage::Data(ServerValue::RESP(RESP::BulkString(b"42".to_vec())))
         );
     }
 
+    fn command_request(cmd: &str, client_id: u64, sender: &mpsc::Sender<ServerMessage>) -> Request {
+        Request {
+            value: RESP::Array(
+                cmd.split(' ')
+                    .map(|a| RESP::BulkString(a.as_bytes().to_vec()))
+                    .collect(),
+            ),
+            sender: sender.clone(),
+            client_id,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_push_unblocks_in_order() {
+        let (first, mut first_receiver) = mpsc::channel::<ServerMessage>(32);
+        let (second, mut second_receiver) = mpsc::channel::<ServerMessage>(32);
+        let (pusher, mut pusher_receiver) = mpsc::channel::<ServerMessage>(32);
+        let mut server = Server::with_new(Storage::new());
+        process_request(command_request("BRPOP list 0", 1, &first), &mut server).await;
+        process_request(
+            command_request("BLPOP other list 0", 2, &second),
+            &mut server,
+        )
+        .await;
+        assert!(first_receiver.try_recv().is_err());
+        assert!(second_receiver.try_recv().is_err());
+
+        process_request(command_request("RPUSH list a b c", 3, &pusher), &mut server).await;
+        assert_eq!(
+            pusher_receiver.try_recv().unwrap(),
+            ServerMessage::Data(ServerValue::RESP(RESP::Integer(3)))
+        );
+        assert_eq!(
+            first_receiver.try_recv().unwrap(),
+            ServerMessage::Data(ServerValue::RESP(RESP::Array(vec![
+                RESP::BulkString(b"list".to_vec()),
+                RESP::BulkString(b"c".to_vec()),
+            ])))
+        );
+        assert_eq!(
+            second_receiver.try_recv().unwrap(),
+            ServerMessage::Data(ServerValue::RESP(RESP::Array(vec![
+                RESP::BulkString(b"list".to_vec()),
+                RESP::BulkString(b"a".to_vec()),
+            ])))
+        );
+        assert!(server.blocked.is_empty());
+        let storage = server.storage.as_mut().unwrap();
+        assert_eq!(
+            storage.list_range(b"list", 0, -1).unwrap(),
+            vec![b"b".to_vec()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_push_skips_disconnected_clients() {
+        let (first, first_receiver) = mpsc::channel::<ServerMessage>(32);
+        let (second, mut second_receiver) = mpsc::channel::<ServerMessage>(32);
+        let (pusher, _pusher_receiver) = mpsc::channel::<ServerMessage>(32);
+        let mut server = Server::with_new(Storage::new());
+        process_request(command_request("BLPOP list 0", 1, &first), &mut server).await;
+        process_request(command_request("BLPOP list 0", 2, &second), &mut server).await;
+        drop(first_receiver);
+
+        process_request(command_request("RPUSH list a", 3, &pusher), &mut server).await;
+        assert_eq!(
+            second_receiver.try_recv().unwrap(),
+            ServerMessage::Data(ServerValue::RESP(RESP::Array(vec![
+                RESP::BulkString(b"list".to_vec()),
+                RESP::BulkString(b"a".to_vec()),
+            ])))
+        );
+        assert!(server.blocked.is_empty());
+        let storage = server.storage.as_mut().unwrap();
+        assert_eq!(storage.list_len(b"list").unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_move_unblocks_chain() {
+        let (mover, mut mover_receiver) = mpsc::channel::<ServerMessage>(32);
+        let (popper, mut popper_receiver) = mpsc::channel::<ServerMessage>(32);
+        let (pusher, _pusher_receiver) = mpsc::channel::<ServerMessage>(32);
+        let mut server = Server::with_new(Storage::new());
+        process_request(
+            command_request("BLPOP destination 0", 5, &popper),
+            &mut server,
+        )
+        .await;
+        process_request(
+            command_request("BRPOPLPUSH source destination 0", 4, &mover),
+            &mut server,
+        )
+        .await;
+
+        process_request(command_request("LPUSH source x", 3, &pusher), &mut server).await;
+        assert_eq!(
+            mover_receiver.try_recv().unwrap(),
+            ServerMessage::Data(ServerValue::RESP(RESP::BulkString(b"x".to_vec())))
+        );
+        assert_eq!(
+            popper_receiver.try_recv().unwrap(),
+            ServerMessage::Data(ServerValue::RESP(RESP::Array(vec![
+                RESP::BulkString(b"destination".to_vec()),
+                RESP::BulkString(b"x".to_vec()),
+            ])))
+        );
+        assert!(server.blocked.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_zset_writes_unblock_pops() {
+        let (min, mut min_receiver) = mpsc::channel::<ServerMessage>(32);
+        let (multi, mut multi_receiver) = mpsc::channel::<ServerMessage>(32);
+        let (writer, _writer_receiver) = mpsc::channel::<ServerMessage>(32);
+        let mut server = Server::with_new(Storage::new());
+        process_request(command_request("BZPOPMIN zset 0", 6, &min), &mut server).await;
+        process_request(
+            command_request("BZMPOP 0 2 other out MAX COUNT 5", 7, &multi),
+            &mut server,
+        )
+        .await;
+
+        process_request(
+            command_request("ZADD zset 1 a 2 b", 8, &writer),
+            &mut server,
+        )
+        .await;
+        assert_eq!(
+            min_receiver.try_recv().unwrap(),
+            ServerMessage::Data(ServerValue::RESP(RESP::Array(vec![
+                RESP::BulkString(b"zset".to_vec()),
+                RESP::BulkString(b"a".to_vec()),
+                RESP::Double(1.0),
+            ])))
+        );
+        assert!(multi_receiver.try_recv().is_err());
+
+        // storing an aggregation signals the destination
+        process_request(
+            command_request("ZUNIONSTORE out 1 zset", 8, &writer),
+            &mut server,
+        )
+        .await;
+        assert_eq!(
+            multi_receiver.try_recv().unwrap(),
+            ServerMessage::Data(ServerValue::RESP(RESP::Array(vec![
+                RESP::BulkString(b"out".to_vec()),
+                RESP::Array(vec![RESP::Array(vec![
+                    RESP::BulkString(b"b".to_vec()),
+                    RESP::Double(2.0),
+                ])]),
+            ])))
+        );
+        assert!(server.blocked.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_blocked_client_times_out() {
+        let clock = ManualClock::new(UNIX_EPOCH + Duration::from_secs(1700000000));
+        let mut server = Server::with_new(Storage::with_clock(clock.clone()));
+        let (sender, mut receiver) = mpsc::channel::<ServerMessage>(32);
+        process_request(command_request("BLPOP list 0.5", 1, &sender), &mut server).await;
+        process_request(
+            command_request("BLMOVE list other LEFT LEFT 2", 1, &sender),
+            &mut server,
+        )
+        .await;
+        process_request(command_request("PING", 1, &sender), &mut server).await;
+
+        clock.advance(Duration::from_millis(400));
+        unblock_timed_out(&mut server).await;
+        assert!(receiver.try_recv().is_err());
+
+        // the requests sent while blocked run in order, until one blocks again
+        clock.advance(Duration::from_millis(100));
+        unblock_timed_out(&mut server).await;
+        assert_eq!(
+            receiver.try_recv().unwrap(),
+            ServerMessage::Data(ServerValue::RESP(RESP::NullArray))
+        );
+        assert!(receiver.try_recv().is_err());
+        assert_eq!(server.blocked.len(), 1);
+
+        clock.advance(Duration::from_secs(2));
+        unblock_timed_out(&mut server).await;
+        assert_eq!(
+            receiver.try_recv().unwrap(),
+            ServerMessage::Data(ServerValue::RESP(RESP::Null))
+        );
+        assert_eq!(
+            receiver.try_recv().unwrap(),
+            ServerMessage::Data(ServerValue::RESP(RESP::SimpleString(String::from("PONG"))))
+        );
+        assert!(server.blocked.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_list_pop_stays_blocked_on_other_type() {
+        let (sender, mut receiver) = mpsc::channel::<ServerMessage>(32);
+        let (other, _other_receiver) = mpsc::channel::<ServerMessage>(32);
+        let mut server = Server::with_new(Storage::new());
+        process_request(command_request("BLPOP list 0", 1, &sender), &mut server).await;
+        process_request(command_request("ZADD zset 1 a", 2, &other), &mut server).await;
+        process_request(command_request("RENAME zset list", 2, &other), &mut server).await;
+        assert!(receiver.try_recv().is_err());
+        assert_eq!(server.blocked.len(), 1);
+
+        process_request(command_request("DEL list", 2, &other), &mut server).await;
+        process_request(command_request("RPUSH list x", 2, &other), &mut server).await;
+        assert_eq!(
+            receiver.try_recv().unwrap(),
+            ServerMessage::Data(ServerValue::RESP(RESP::Array(vec![
+                RESP::BulkString(b"list".to_vec()),
+                RESP::BulkString(b"x".to_vec()),
+            ])))
+        );
+        assert!(server.blocked.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_pop_from_ready_key_only() {
+        let (sender, mut receiver) = mpsc::channel::<ServerMessage>(32);
+        let (other, _other_receiver) = mpsc::channel::<ServerMessage>(32);
+        let mut server = Server::with_new(Storage::new());
+        process_request(command_request("BLPOP ka kb 0", 1, &sender), &mut server).await;
+        process_request(command_request("SET ka str", 2, &other), &mut server).await;
+        process_request(command_request("RPUSH kb x", 2, &other), &mut server).await;
+        assert_eq!(
+            receiver.try_recv().unwrap(),
+            ServerMessage::Data(ServerValue::RESP(RESP::Array(vec![
+                RESP::BulkString(b"kb".to_vec()),
+                RESP::BulkString(b"x".to_vec()),
+            ])))
+        );
+        assert!(server.blocked.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_zset_pop_stays_blocked_on_other_type() {
+        let (sender, mut receiver) = mpsc::channel::<ServerMessage>(32);
+        let (other, _other_receiver) = mpsc::channel::<ServerMessage>(32);
+        let (popper, mut popper_receiver) = mpsc::channel::<ServerMessage>(32);
+        let mut server = Server::with_new(Storage::new());
+        process_request(command_request("BZPOPMIN key 0", 1, &sender), &mut server).await;
+        process_request(command_request("BLPOP key 0", 3, &popper), &mut server).await;
+
+        // the list waiter behind the zset one is still served
+        process_request(command_request("RPUSH key x", 2, &other), &mut server).await;
+        assert!(receiver.try_recv().is_err());
+        assert_eq!(
+            popper_receiver.try_recv().unwrap(),
+            ServerMessage::Data(ServerValue::RESP(RESP::Array(vec![
+                RESP::BulkString(b"key".to_vec()),
+                RESP::BulkString(b"x".to_vec()),
+            ])))
+        );
+        assert_eq!(server.blocked.len(), 1);
+
+        process_request(command_request("ZADD key 1 a", 2, &other), &mut server).await;
+        assert_eq!(
+            receiver.try_recv().unwrap(),
+            ServerMessage::Data(ServerValue::RESP(RESP::Array(vec![
+                RESP::BulkString(b"key".to_vec()),
+                RESP::BulkString(b"a".to_vec()),
+                RESP::Double(1.0),
+            ])))
+        );
+        assert!(server.blocked.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_wrong_type_unblocks_with_error() {
+        let (sender, mut receiver) = mpsc::channel::<ServerMessage>(32);
+        let (other, _other_receiver) = mpsc::channel::<ServerMessage>(32);
+        let mut server = Server::with_new(Storage::new());
+        process_request(
+            command_request("BLMOVE source destination LEFT LEFT 0", 1, &sender),
+            &mut server,
+        )
+        .await;
+        process_request(
+            command_request("SET destination string", 2, &other),
+            &mut server,
+        )
+        .await;
+        process_request(command_request("RPUSH source a", 2, &other), &mut server).await;
+        assert_eq!(
+            receiver.try_recv().unwrap(),
+            ServerMessage::Error(ServerError::WrongType)
+        );
+        assert!(server.blocked.is_empty());
+        let storage = server.storage.as_mut().unwrap();
+        assert_eq!(storage.list_len(b"source").unwrap(), 1);
+    }
+
     #[test]
     fn test_create_new() {
         let server: Server = Server::new();
-        match server.storage {
-            Some(_) => panic!(),
-            None => (),
+        if server.storage.is_some() {
+            panic!()
         };
     }
     #[test]
@@ -1,19 +1,72 @@
 use std::fmt;
 
-use crate::resp::RESP;
+use crate::{
+    resp::{Protocol, RESP},
+    storage_result::StorageError,
+};
 
 #[derive(Debug, PartialEq)]
 pub enum ServerError {
     CommandInternalError(String),
     CommandSyntaxError(String),
     CommandNotAvailable(String),
+    WrongNumberOfArguments(String),
+    InvalidProtocolVersion,
+    UnsupportedProtocol,
+    WrongPass,
+    InvalidClientName,
+    NotAnInteger,
+    NotAFloat,
+    HashValueNotAnInteger,
+    HashValueNotAFloat,
+    Overflow,
+    DecrementOverflow,
+    NaNOrInfinity,
+    ScoreNotANumber,
+    ZAddIncrPairs,
+    MinOrMaxNotAFloat,
+    MinOrMaxNotAStringRange,
+    LimitWithoutBy,
+    WithScoresByLex,
+    OffsetOutOfRange,
+    StringTooLong,
+    NoSuchKey,
+    WrongType,
+    IndexOutOfRange,
+    ValueOutOfRange,
+    ValueMustBePositive,
+    NegativeArgument(String),
+    ZeroRank,
+    SameObject,
+    DBIndexOutOfRange,
+    InvalidCursor,
+    TimeoutNotAFloat,
+    NegativeTimeout,
+    MustBePositive(String),
+    NumKeysTooLarge,
+    UnknownTypeName(String),
+    InvalidExpireTime(String),
+    NegativeExpireTime,
+    MissingFieldsArgument,
+    NumFieldsMustBePositive,
+    NumFieldsMismatch,
+    IncompatibleOptions(String),
+    AtLeastOneInputKey(String),
+    WeightNotAFloat,
     IncorrectData,
     StorageNotInitialized,
 }
 
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Debug, PartialEq)]
 pub enum ServerValue {
     RESP(RESP),
+    // Settings that HELLO applies to the connection,
+    // which then sends the reply itself
+    Hello {
+        protocol: Option<Protocol>,
+        name: Option<String>,
+    },
 }
 
 #[derive(Debug, PartialEq)]
@@ -22,22 +75,196 @@ pub enum ServerMessage {
     Error(ServerError),
 }
 
+// Printable form of a command and its arguments, used in error messages
+pub fn command_to_string(command: &[Vec<u8>]) -> String {
+    String::from_utf8_lossy(&command.join(&b' ')).into_owned()
+}
+
+// The messages follow the ones of Redis, prefixed
+// by the error code, as clients parse them
 impl fmt::Display for ServerError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             ServerError::CommandInternalError(string) => {
-                write!(f, "Internal Error while processing {}", string)
+                write!(f, "ERR internal error while processing '{}'", string)
             }
-            ServerError::CommandSyntaxError(string) => {
-                write!(f, "Syntax Error while processing {}", string)
+            ServerError::CommandSyntaxError(_) => write!(f, "ERR syntax error"),
+            ServerError::IncorrectData => {
+                write!(f, "ERR Protocol error: expected an array of bulk strings")
+            }
+            ServerError::StorageNotInitialized => {
+                write!(f, "ERR storage has not been initialized")
             }
-            ServerError::IncorrectData => write!(f, "Data received from stream is incorrect!"),
-            ServerError::StorageNotInitialized => write!(f, "Storage has not been initialized!"),
             ServerError::CommandNotAvailable(string) => {
-                write!(f, "command not available {}", string)
+                write!(f, "ERR unknown command '{}'", string)
+            }
+            ServerError::WrongNumberOfArguments(string) => {
+                write!(f, "ERR wrong number of arguments for '{}' command", string)
+            }
+            ServerError::InvalidProtocolVersion => {
+                write!(f, "ERR Protocol version is not an integer or out of range")
+            }
+            ServerError::UnsupportedProtocol => write!(f, "NOPROTO unsupported protocol version"),
+            ServerError::WrongPass => write!(
+                f,
+                "WRONGPASS invalid username-password pair or user is disabled."
+            ),
+            ServerError::NotAnInteger => write!(f, "ERR value is not an integer or out of range"),
+            ServerError::NotAFloat => write!(f, "ERR value is not a valid float"),
+            ServerError::HashValueNotAnInteger => write!(f, "ERR hash value is not an integer"),
+            ServerError::HashValueNotAFloat => write!(f, "ERR hash value is not a float"),
+            ServerError::Overflow => write!(f, "ERR increment or decrement would overflow"),
+            ServerError::DecrementOverflow => write!(f, "ERR decrement would overflow"),
+            ServerError::NaNOrInfinity => {
+                write!(f, "ERR increment would produce NaN or Infinity")
+            }
+            ServerError::ScoreNotANumber => {
+                write!(f, "ERR resulting score is not a number (NaN)")
+            }
+            ServerError::ZAddIncrPairs => write!(
+                f,
+                "ERR INCR option supports a single increment-element pair"
+            ),
+            ServerError::MinOrMaxNotAFloat => write!(f, "ERR min or max is not a float"),
+            ServerError::MinOrMaxNotAStringRange => {
+                write!(f, "ERR min or max not valid string range item")
+            }
+            ServerError::LimitWithoutBy => write!(
+                f,
+                "ERR syntax error, LIMIT is only supported in combination with either BYSCORE or BYLEX"
+            ),
+            ServerError::WithScoresByLex => write!(
+                f,
+                "ERR syntax error, WITHSCORES not supported in combination with BYLEX"
+            ),
+            ServerError::OffsetOutOfRange => write!(f, "ERR offset is out of range"),
+            ServerError::StringTooLong => write!(
+                f,
+                "ERR string exceeds maximum allowed size (proto-max-bulk-len)"
+            ),
+            ServerError::NoSuchKey => write!(f, "ERR no such key"),
+            ServerError::WrongType => write!(
+                f,
+                "WRONGTYPE Operation against a key holding the wrong kind of value"
+            ),
+            ServerError::IndexOutOfRange => write!(f, "ERR index out of range"),
+            ServerError::ValueOutOfRange => write!(f, "ERR value is out of range"),
+            ServerError::ValueMustBePositive => {
+                write!(f, "ERR value is out of range, must be positive")
+            }
+            ServerError::NegativeArgument(string) => write!(f, "ERR {} can't be negative", string),
+            ServerError::ZeroRank => write!(
+                f,
+                "ERR RANK can't be zero: use 1 to start from the first match, \
+                 2 from the second ... or use negative to start from the last match"
+            ),
+            ServerError::SameObject => {
+                write!(f, "ERR source and destination objects are the same")
+            }
+            ServerError::DBIndexOutOfRange => write!(f, "ERR DB index is out of range"),
+            ServerError::InvalidCursor => write!(f, "ERR invalid cursor"),
+            ServerError::TimeoutNotAFloat => {
+                write!(f, "ERR timeout is not a float or out of range")
+            }
+            ServerError::NegativeTimeout => write!(f, "ERR timeout is negative"),
+            ServerError::MustBePositive(string) => {
+                write!(f, "ERR {} should be greater than 0", string)
+            }
+            ServerError::NumKeysTooLarge => {
+                write!(f, "ERR Number of keys can't be greater than number of args")
+            }
+            ServerError::UnknownTypeName(string) => write!(f, "ERR unknown type name '{}'", string),
+            ServerError::InvalidExpireTime(string) => {
+                write!(f, "ERR invalid expire time in '{}' command", string)
+            }
+            ServerError::NegativeExpireTime => write!(f, "ERR invalid expire time, must be >= 0"),
+            ServerError::MissingFieldsArgument => write!(
+                f,
+                "ERR Mandatory argument FIELDS is missing or not at the right position"
+            ),
+            ServerError::NumFieldsMustBePositive => {
+                write!(f, "ERR Parameter `numFields` should be greater than 0")
             }
+            ServerError::NumFieldsMismatch => write!(
+                f,
+                "ERR The `numfields` parameter must match the number of arguments"
+            ),
+            ServerError::IncompatibleOptions(string) => {
+                write!(
+                    f,
+                    "ERR {} options at the same time are not compatible",
+                    string
+                )
+            }
+            ServerError::AtLeastOneInputKey(string) => {
+                write!(f, "ERR at least 1 input key is needed for '{}' command", string)
+            }
+            ServerError::WeightNotAFloat => write!(f, "ERR weight value is not a float"),
+            ServerError::InvalidClientName => write!(
+                f,
+                "ERR Client names cannot contain spaces, newlines or special characters."
+            ),
         }
     }
 }
 
-pub type ServerResult = Result<ServerValue, ServerError>;
+impl From<StorageError> for ServerError {
+    fn from(e: StorageError) -> ServerError {
+        match e {
+            StorageError::CommandSyntaxError(string) => ServerError::CommandSyntaxError(string),
+            StorageError::CommandNotAvailable(string) => ServerError::CommandNotAvailable(string),
+            StorageError::NotAnInteger => ServerError::NotAnInteger,
+            StorageError::NotAFloat => ServerError::NotAFloat,
+            StorageError::HashValueNotAnInteger => ServerError::HashValueNotAnInteger,
+            StorageError::HashValueNotAFloat => ServerError::HashValueNotAFloat,
+            StorageError::Overflow => ServerError::Overflow,
+            StorageError::NaNOrInfinity => ServerError::NaNOrInfinity,
+            StorageError::ScoreNotANumber => ServerError::ScoreNotANumber,
+            StorageError::StringTooLong => ServerError::StringTooLong,
+            StorageError::NoSuchKey => ServerError::NoSuchKey,
+            StorageError::WrongType => ServerError::WrongType,
+            StorageError::IndexOutOfRange => ServerError::IndexOutOfRange,
+            StorageError::InvalidExpireTime(string) => ServerError::InvalidExpireTime(string),
+            StorageError::IncompatibleOptions(string) => ServerError::IncompatibleOptions(string),
+            StorageError::IncorrectRequest => ServerError::IncorrectData,
+            StorageError::CommandInternalError(string) => ServerError::CommandInternalError(string),
+        }
+    }
+}
+
+impl From<ServerError> for RESP {
+    fn from(e: ServerError) -> RESP {
+        RESP::SimpleError(e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_messages() {
+        assert_eq!(
+            ServerError::CommandSyntaxError(String::from("set a b c")).to_string(),
+            "ERR syntax error"
+        );
+        assert_eq!(
+            ServerError::CommandNotAvailable(String::from("foo")).to_string(),
+            "ERR unknown command 'foo'"
+        );
+        assert_eq!(
+            ServerError::WrongNumberOfArguments(String::from("get")).to_string(),
+            "ERR wrong number of arguments for 'get' command"
+        );
+    }
+
+    #[test]
+    fn test_error_to_resp() {
+        assert_eq!(
+            RESP::from(ServerError::WrongNumberOfArguments(String::from("get"))),
+            RESP::SimpleError(String::from(
+                "ERR wrong number of arguments for 'get' command"
+            ))
+        );
+    }
+}
@@ -1,4 +1,8 @@
-use crate::storage_result::{StorageError, StorageResult};
+use crate::{
+    number::parse_i64,
+    server_result::command_to_string,
+    storage_result::{StorageError, StorageResult},
+};
 
 #[derive(Debug, PartialEq)]
 pub enum KeyExistence {
@@ -6,10 +10,14 @@ pub enum KeyExistence {
     XX, // set if exists
 }
 
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Debug, PartialEq)]
 pub enum KeyExipry {
-    EX(u64), // expiry in seconds
-    PX(u64), // expiry in milliseconds
+    EX(u64),   // expiry in seconds
+    PX(u64),   // expiry in milliseconds
+    EXAT(u64), // unix time of the expiry in seconds
+    PXAT(u64), // unix time of the expiry in milliseconds
+    KEEPTTL,   // keep the expiry of the existing key
 }
 
 #[derive(Debug, PartialEq)]
@@ -29,67 +37,166 @@ impl SetArgs {
     }
 }
 
-pub fn parse_set_arguments(arguments: &Vec<String>) -> StorageResult<SetArgs> {
+// Result of a SET on the storage: whether the value
+// was written and the value previously stored at the key
+#[derive(Debug, PartialEq)]
+pub struct SetOutcome {
+    pub written: bool,
+    pub previous: Option<Vec<u8>>,
+}
+
+// Parse the value of an EX, PX, EXAT or PXAT option,
+// which has to be a positive integer
+pub fn parse_expiry(option: &[u8], value: &[u8], command: &str) -> StorageResult<KeyExipry> {
+    let value = parse_i64(value).ok_or(StorageError::NotAnInteger)?;
+    if value <= 0 {
+        return Err(StorageError::InvalidExpireTime(command.to_string()));
+    }
+    let value = value as u64;
+    match option.to_ascii_lowercase().as_slice() {
+        b"ex" => Ok(KeyExipry::EX(value)),
+        b"px" => Ok(KeyExipry::PX(value)),
+        b"exat" => Ok(KeyExipry::EXAT(value)),
+        b"pxat" => Ok(KeyExipry::PXAT(value)),
+        _ => Err(StorageError::CommandSyntaxError(
+            String::from_utf8_lossy(option).into_owned(),
+        )),
+    }
+}
+
+pub fn parse_set_arguments(arguments: &[Vec<u8>]) -> StorageResult<SetArgs> {
     let mut args = SetArgs::new();
     let mut idx: usize = 0;
     loop {
         if idx >= arguments.len() {
             break;
         }
-        match arguments[idx].to_lowercase().as_str() {
-            "nx" => {
+        match arguments[idx].to_ascii_lowercase().as_slice() {
+            b"nx" => {
                 if args.existence == Some(KeyExistence::XX) {
-                    return Err(StorageError::CommandSyntaxError(arguments.join(" ")));
+                    return Err(StorageError::CommandSyntaxError(command_to_string(
+                        arguments,
+                    )));
                 }
                 args.existence = Some(KeyExistence::NX);
                 idx += 1;
             }
-            "xx" => {
+            b"xx" => {
                 if args.existence == Some(KeyExistence::NX) {
-                    return Err(StorageError::CommandSyntaxError(arguments.join(" ")));
+                    return Err(StorageError::CommandSyntaxError(command_to_string(
+                        arguments,
+                    )));
                 }
                 args.existence = Some(KeyExistence::XX);
                 idx += 1;
             }
-            "ex" => {
-                if let Some(KeyExipry::PX(_)) = args.expiry {
-                    // PX is set , we can't set EX
-                    return Err(StorageError::CommandSyntaxError(arguments.join(" ")));
-                }
-                if idx + 1 == arguments.len() {
-                    // expecting a value for EX
-                    return Err(StorageError::CommandSyntaxError(arguments.join(" ")));
+            b"ex" | b"px" | b"exat" | b"pxat" => {
+                if args.expiry.is_some() || idx + 1 == arguments.len() {
+                    // only one expiry can be set, and it needs a value
+                    return Err(StorageError::CommandSyntaxError(command_to_string(
+                        arguments,
+                    )));
                 }
-                let value: u64 = arguments[idx + 1]
-                    .parse()
-                    .map_err(|_| StorageError::CommandSyntaxError(arguments.join(" ")))?;
-                args.expiry = Some(KeyExipry::EX(value));
+                args.expiry = Some(parse_expiry(&arguments[idx], &arguments[idx + 1], "set")?);
                 idx += 2;
             }
-            "px" => {
-                if let Some(KeyExipry::EX(_)) = args.expiry {
-                    // PX is set , we can't set EX
-                    return Err(StorageError::CommandSyntaxError(arguments.join(" ")));
+            b"keepttl" => {
+                if args.expiry.is_some() {
+                    return Err(StorageError::CommandSyntaxError(command_to_string(
+                        arguments,
+                    )));
                 }
-                if idx + 1 == arguments.len() {
-                    // expecting a value for EX
-                    return Err(StorageError::CommandSyntaxError(arguments.join(" ")));
-                }
-
-                let value: u64 = arguments[idx + 1]
-                    .parse()
-                    .map_err(|_| StorageError::CommandSyntaxError(arguments.join(" ")))?;
-                args.expiry = Some(KeyExipry::PX(value));
-                idx += 2;
+                args.expiry = Some(KeyExipry::KEEPTTL);
+                idx += 1;
             }
-            "get" => {
+            b"get" => {
                 args.get = true;
                 idx += 1;
             }
             _ => {
-                return Err(StorageError::CommandSyntaxError(arguments.join(" ")));
+                return Err(StorageError::CommandSyntaxError(command_to_string(
+                    arguments,
+                )));
             }
         }
     }
     Ok(args)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(arguments: &str) -> StorageResult<SetArgs> {
+        let arguments: Vec<Vec<u8>> = arguments
+            .split(' ')
+            .map(|a| a.as_bytes().to_vec())
+            .collect();
+        parse_set_arguments(&arguments)
+    }
+
+    #[test]
+    fn test_parse_relative_expiry() {
+        assert_eq!(parse("EX 10").unwrap().expiry, Some(KeyExipry::EX(10)));
+        assert_eq!(parse("px 1500").unwrap().expiry, Some(KeyExipry::PX(1500)));
+    }
+
+    #[test]
+    fn test_parse_absolute_expiry() {
+        assert_eq!(
+            parse("EXAT 1700000000").unwrap().expiry,
+            Some(KeyExipry::EXAT(1700000000))
+        );
+        assert_eq!(
+            parse("pxat 1700000000000").unwrap().expiry,
+            Some(KeyExipry::PXAT(1700000000000))
+        );
+    }
+
+    #[test]
+    fn test_parse_keepttl() {
+        let args = parse("XX KEEPTTL GET").unwrap();
+        assert_eq!(args.expiry, Some(KeyExipry::KEEPTTL));
+        assert_eq!(args.existence, Some(KeyExistence::XX));
+        assert!(args.get);
+    }
+
+    #[test]
+    fn test_parse_multiple_expiry() {
+        for arguments in [
+            "EX 10 PX 100",
+            "EX 10 EX 10",
+            "EXAT 10 KEEPTTL",
+            "KEEPTTL PXAT 10",
+        ] {
+            assert_eq!(
+                parse(arguments).unwrap_err(),
+                StorageError::CommandSyntaxError(arguments.to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_invalid_expire_time() {
+        for arguments in ["EX 0", "PX -10", "EXAT 0", "PXAT -1"] {
+            assert_eq!(
+                parse(arguments).unwrap_err(),
+                StorageError::InvalidExpireTime(String::from("set"))
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_expiry_not_an_integer() {
+        assert_eq!(parse("EX ten").unwrap_err(), StorageError::NotAnInteger);
+        assert_eq!(parse("EX 1.5").unwrap_err(), StorageError::NotAnInteger);
+    }
+
+    #[test]
+    fn test_parse_missing_expiry_value() {
+        assert_eq!(
+            parse("NX EX").unwrap_err(),
+            StorageError::CommandSyntaxError(String::from("NX EX"))
+        );
+    }
+}
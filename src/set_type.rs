@@ -0,0 +1,317 @@
+use std::{collections::HashSet, fmt};
+
+use crate::{active_expire::Rng, dict::Dict, intset::Intset, number::parse_i64};
+
+// Same default as set-max-intset-entries in Redis: past this
+// limit an intset is converted to a hash table
+const INTSET_MAX_ENTRIES: usize = 512;
+
+// An intset while all the members are integers and the set is small,
+// a hash table otherwise. It never goes back to an intset.
+#[derive(Clone)]
+enum Encoding {
+    Intset(Intset),
+    Dict(Dict<()>),
+}
+
+#[derive(Clone)]
+pub struct Set {
+    encoding: Encoding,
+}
+
+// Operations of SINTER, SUNION and SDIFF
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum SetOperation {
+    Intersection,
+    Union,
+    Difference,
+}
+
+impl Default for Set {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Set {
+    pub fn new() -> Self {
+        Self {
+            encoding: Encoding::Intset(Intset::new()),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match &self.encoding {
+            Encoding::Intset(intset) => intset.len(),
+            Encoding::Dict(dict) => dict.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // Number of allocations holding the set
+    pub fn allocations(&self) -> usize {
+        match &self.encoding {
+            Encoding::Intset(_) => 1,
+            Encoding::Dict(dict) => dict.len(),
+        }
+    }
+
+    pub fn contains(&self, member: &[u8]) -> bool {
+        match &self.encoding {
+            Encoding::Intset(intset) => parse_i64(member).is_some_and(|n| intset.contains(n)),
+            Encoding::Dict(dict) => dict.contains_key(member),
+        }
+    }
+
+    // Returns true if the member is new
+    pub fn insert(&mut self, member: &[u8]) -> bool {
+        if let Encoding::Intset(intset) = &mut self.encoding {
+            if let Some(value) = parse_i64(member) {
+                let new = intset.insert(value);
+                if intset.len() > INTSET_MAX_ENTRIES {
+                    self.convert_to_dict();
+                }
+                return new;
+            }
+            self.convert_to_dict();
+        }
+        match &mut self.encoding {
+            Encoding::Dict(dict) => dict.insert(member.to_vec(), ()).is_none(),
+            Encoding::Intset(_) => unreachable!(),
+        }
+    }
+
+    // Returns true if the member existed
+    pub fn remove(&mut self, member: &[u8]) -> bool {
+        match &mut self.encoding {
+            Encoding::Intset(intset) => parse_i64(member).is_some_and(|n| intset.remove(n)),
+            Encoding::Dict(dict) => dict.remove(member).is_some(),
+        }
+    }
+
+    fn convert_to_dict(&mut self) {
+        if let Encoding::Intset(_) = self.encoding {
+            let mut dict = Dict::new();
+            for member in self.iter() {
+                dict.insert(member, ());
+            }
+            self.encoding = Encoding::Dict(dict);
+        }
+    }
+
+    // Members, in increasing order for an intset
+    pub fn iter(&self) -> Box<dyn Iterator<Item = Vec<u8>> + '_> {
+        match &self.encoding {
+            Encoding::Intset(intset) => Box::new(intset.iter().map(|n| n.to_string().into_bytes())),
+            Encoding::Dict(dict) => Box::new(dict.iter().map(|(member, _)| member.to_vec())),
+        }
+    }
+
+    fn random_member(&self, rng: &mut Rng) -> Option<Vec<u8>> {
+        match &self.encoding {
+            Encoding::Intset(intset) if intset.is_empty() => None,
+            Encoding::Intset(intset) => intset
+                .get(rng.below(intset.len()))
+                .map(|n| n.to_string().into_bytes()),
+            Encoding::Dict(dict) => dict.random_entry(rng).map(|(member, _)| member.to_vec()),
+        }
+    }
+
+    // Random members as SRANDMEMBER picks them, with the same
+    // strategies as Hash::random_fields: `count` distinct ones, or
+    // all of them if there are fewer, unless `unique` is false
+    pub fn random_members(&self, count: usize, unique: bool, rng: &mut Rng) -> Vec<Vec<u8>> {
+        if self.is_empty() {
+            return Vec::new();
+        }
+        if !unique {
+            return (0..count).filter_map(|_| self.random_member(rng)).collect();
+        }
+        if count >= self.len() {
+            return self.iter().collect();
+        }
+        if count * 3 > self.len() {
+            let mut members: Vec<Vec<u8>> = self.iter().collect();
+            while members.len() > count {
+                members.swap_remove(rng.below(members.len()));
+            }
+            return members;
+        }
+        let mut picked = HashSet::new();
+        while picked.len() < count {
+            if let Some(member) = self.random_member(rng) {
+                picked.insert(member);
+            }
+        }
+        picked.into_iter().collect()
+    }
+
+    // Remove `count` random members, or all of them if there are fewer
+    pub fn pop(&mut self, count: usize, rng: &mut Rng) -> Vec<Vec<u8>> {
+        let members = self.random_members(count, true, rng);
+        for member in members.iter() {
+            self.remove(member);
+        }
+        members
+    }
+
+    // Result of an operation between sets, a missing set being empty
+    pub fn combine(sets: &[Option<&Set>], operation: SetOperation) -> Set {
+        let mut result = Set::new();
+        match operation {
+            SetOperation::Intersection => {
+                for member in Self::intersection(sets) {
+                    result.insert(&member);
+                }
+            }
+            SetOperation::Union => {
+                for set in sets.iter().flatten() {
+                    for member in set.iter() {
+                        result.insert(&member);
+                    }
+                }
+            }
+            SetOperation::Difference => {
+                if let Some(Some(first)) = sets.first() {
+                    for member in first.iter() {
+                        if !sets[1..].iter().flatten().any(|set| set.contains(&member)) {
+                            result.insert(&member);
+                        }
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    // Members of all the sets, checked from the smallest set
+    // against the others, so that the fewest lookups are done
+    pub fn intersection<'a>(sets: &[Option<&'a Set>]) -> Box<dyn Iterator<Item = Vec<u8>> + 'a> {
+        let Some(mut sets) = sets.iter().copied().collect::<Option<Vec<&Set>>>() else {
+            return Box::new(std::iter::empty());
+        };
+        sets.sort_by_key(|set| set.len());
+        let Some((smallest, others)) = sets.split_first() else {
+            return Box::new(std::iter::empty());
+        };
+        let others = others.to_vec();
+        Box::new(
+            smallest
+                .iter()
+                .filter(move |member| others.iter().all(|set| set.contains(member))),
+        )
+    }
+}
+
+impl fmt::Debug for Set {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set()
+            .entries(
+                self.iter()
+                    .map(|member| String::from_utf8_lossy(&member).into_owned()),
+            )
+            .finish()
+    }
+}
+
+// Sets are equal when their members are, whatever their encoding
+impl PartialEq for Set {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().all(|member| other.contains(&member))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(members: &[&str]) -> Set {
+        let mut set = Set::new();
+        for member in members {
+            set.insert(member.as_bytes());
+        }
+        set
+    }
+
+    fn sorted(members: impl Iterator<Item = Vec<u8>>) -> Vec<String> {
+        let mut members: Vec<String> = members
+            .map(|member| String::from_utf8(member).unwrap())
+            .collect();
+        members.sort();
+        members
+    }
+
+    #[test]
+    fn test_insert_contains_remove() {
+        let mut set = set(&["3", "1", "2"]);
+        assert!(matches!(set.encoding, Encoding::Intset(_)));
+        assert!(!set.insert(b"2"));
+        assert!(set.contains(b"1"));
+        assert!(!set.contains(b"01"));
+        assert!(set.remove(b"1"));
+        assert!(!set.remove(b"1"));
+        assert_eq!(sorted(set.iter()), vec!["2", "3"]);
+
+        // a member that is not an integer converts the intset
+        assert!(set.insert(b"a"));
+        assert!(matches!(set.encoding, Encoding::Dict(_)));
+        assert!(set.contains(b"3"));
+        assert_eq!(set.len(), 3);
+    }
+
+    #[test]
+    fn test_converts_when_large() {
+        let mut set = Set::new();
+        for i in 0..INTSET_MAX_ENTRIES {
+            set.insert(i.to_string().as_bytes());
+        }
+        assert!(matches!(set.encoding, Encoding::Intset(_)));
+        set.insert(b"-1");
+        assert!(matches!(set.encoding, Encoding::Dict(_)));
+        assert_eq!(set.len(), INTSET_MAX_ENTRIES + 1);
+        assert!(set.contains(b"-1"));
+        assert!(set.contains(b"511"));
+    }
+
+    #[test]
+    fn test_combine() {
+        let a = set(&["1", "2", "3", "x"]);
+        let b = set(&["2", "3", "4"]);
+        let c = set(&["3", "x"]);
+        let sets = [Some(&a), Some(&b), Some(&c)];
+        assert_eq!(Set::combine(&sets, SetOperation::Intersection), set(&["3"]));
+        assert_eq!(
+            Set::combine(&sets, SetOperation::Union),
+            set(&["1", "2", "3", "4", "x"])
+        );
+        assert_eq!(Set::combine(&sets, SetOperation::Difference), set(&["1"]));
+        assert!(Set::combine(&[Some(&a), None], SetOperation::Intersection).is_empty());
+        assert_eq!(Set::combine(&[Some(&a), None], SetOperation::Difference), a);
+        assert!(Set::combine(&[None, Some(&a)], SetOperation::Difference).is_empty());
+    }
+
+    #[test]
+    fn test_random_members_and_pop() {
+        let mut rng = Rng::new(42);
+        let members: Vec<String> = (0..1000).map(|i| format!("m{}", i)).collect();
+        let members: Vec<&str> = members.iter().map(String::as_str).collect();
+        for mut set in [set(&["1", "2", "3", "4", "5"]), set(&members)] {
+            for count in [1, 2, 4, 300, 2000] {
+                let picked = set.random_members(count, true, &mut rng);
+                assert_eq!(picked.len(), count.min(set.len()));
+                let distinct: HashSet<&Vec<u8>> = picked.iter().collect();
+                assert_eq!(distinct.len(), picked.len());
+                assert!(picked.iter().all(|member| set.contains(member)));
+            }
+            assert_eq!(set.random_members(3000, false, &mut rng).len(), 3000);
+
+            let len = set.len();
+            let popped = set.pop(3, &mut rng);
+            assert_eq!(set.len(), len - 3);
+            assert!(popped.iter().all(|member| !set.contains(member)));
+        }
+    }
+}
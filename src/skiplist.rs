@@ -0,0 +1,331 @@
+use crate::active_expire::Rng;
+
+// Skiplist modelled on the one of sorted sets in Redis: members are
+// kept ordered by score then by member, each link knowing how many
+// nodes it skips so that the rank of a node is found while walking
+// down to it, and each node pointing back to the previous one so
+// that ranges can be walked in both directions.
+//
+// Nodes live in a vector and link to each other by index, the head
+// being the first one. Like in Redis, the span of a link that ends
+// the list is not maintained and may be any value.
+
+const MAX_LEVEL: usize = 32;
+const HEAD: usize = 0;
+
+#[derive(Clone, Copy, Default)]
+struct Level {
+    forward: Option<usize>,
+    span: usize,
+}
+
+#[derive(Clone)]
+struct Node {
+    member: Vec<u8>,
+    score: f64,
+    backward: Option<usize>,
+    levels: Vec<Level>,
+}
+
+#[derive(Clone)]
+pub struct Skiplist {
+    nodes: Vec<Node>,
+    free: Vec<usize>,
+    tail: Option<usize>,
+    len: usize,
+    level: usize,
+    rng: Rng,
+}
+
+impl Default for Skiplist {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Skiplist {
+    pub fn new() -> Self {
+        let head = Node {
+            member: Vec::new(),
+            score: 0.0,
+            backward: None,
+            levels: vec![Level::default(); MAX_LEVEL],
+        };
+        Self {
+            nodes: vec![head],
+            free: Vec::new(),
+            tail: None,
+            len: 0,
+            level: 1,
+            rng: Rng::from_time(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    // Whether a node comes before the given score and member
+    fn before(&self, node: usize, score: f64, member: &[u8]) -> bool {
+        let node = &self.nodes[node];
+        node.score < score || (node.score == score && node.member.as_slice() < member)
+    }
+
+    // Level of a new node, each level being 4 times less likely
+    fn random_level(&mut self) -> usize {
+        let mut level = 1;
+        while level < MAX_LEVEL && self.rng.next_u64() & 0xffff < 0xffff / 4 {
+            level += 1;
+        }
+        level
+    }
+
+    // Last node before the given score and member on each level,
+    // with the rank of that node
+    fn find_update(&self, score: f64, member: &[u8]) -> ([usize; MAX_LEVEL], [usize; MAX_LEVEL]) {
+        let mut update = [HEAD; MAX_LEVEL];
+        let mut rank = [0; MAX_LEVEL];
+        let mut x = HEAD;
+        for i in (0..self.level).rev() {
+            rank[i] = if i == self.level - 1 { 0 } else { rank[i + 1] };
+            while let Some(next) = self.nodes[x].levels[i].forward {
+                if !self.before(next, score, member) {
+                    break;
+                }
+                rank[i] += self.nodes[x].levels[i].span;
+                x = next;
+            }
+            update[i] = x;
+        }
+        (update, rank)
+    }
+
+    // Insert a member, which must not be in the list already
+    pub fn insert(&mut self, score: f64, member: &[u8]) {
+        let (mut update, mut rank) = self.find_update(score, member);
+        let level = self.random_level();
+        if level > self.level {
+            for i in self.level..level {
+                rank[i] = 0;
+                update[i] = HEAD;
+                self.nodes[HEAD].levels[i].span = self.len;
+            }
+            self.level = level;
+        }
+
+        let node = Node {
+            member: member.to_vec(),
+            score,
+            backward: None,
+            levels: vec![Level::default(); level],
+        };
+        let x = match self.free.pop() {
+            Some(index) => {
+                self.nodes[index] = node;
+                index
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        };
+        // spans of links ending the list may wrap around, as they
+        // would in Redis, and are never read
+        for i in 0..level {
+            let previous = self.nodes[update[i]].levels[i];
+            self.nodes[x].levels[i] = Level {
+                forward: previous.forward,
+                span: previous.span.wrapping_sub(rank[0] - rank[i]),
+            };
+            self.nodes[update[i]].levels[i] = Level {
+                forward: Some(x),
+                span: rank[0] - rank[i] + 1,
+            };
+        }
+        for (i, &previous) in update.iter().enumerate().take(self.level).skip(level) {
+            let span = &mut self.nodes[previous].levels[i].span;
+            *span = span.wrapping_add(1);
+        }
+
+        self.nodes[x].backward = (update[0] != HEAD).then_some(update[0]);
+        match self.nodes[x].levels[0].forward {
+            Some(next) => self.nodes[next].backward = Some(x),
+            None => self.tail = Some(x),
+        }
+        self.len += 1;
+    }
+
+    // Remove a member with its score, returns true if it was found
+    pub fn remove(&mut self, score: f64, member: &[u8]) -> bool {
+        let (update, _) = self.find_update(score, member);
+        let Some(x) = self.nodes[update[0]].levels[0].forward else {
+            return false;
+        };
+        if self.nodes[x].score != score || self.nodes[x].member != member {
+            return false;
+        }
+
+        for (i, &previous) in update.iter().enumerate().take(self.level) {
+            let removed = self.nodes[x].levels.get(i).copied();
+            let level = &mut self.nodes[previous].levels[i];
+            match removed {
+                Some(removed) if level.forward == Some(x) => {
+                    level.span = level.span.wrapping_add(removed.span).wrapping_sub(1);
+                    level.forward = removed.forward;
+                }
+                _ => level.span = level.span.wrapping_sub(1),
+            }
+        }
+        let backward = self.nodes[x].backward;
+        match self.nodes[x].levels[0].forward {
+            Some(next) => self.nodes[next].backward = backward,
+            None => self.tail = backward,
+        }
+        while self.level > 1 && self.nodes[HEAD].levels[self.level - 1].forward.is_none() {
+            self.level -= 1;
+        }
+        self.nodes[x].member = Vec::new();
+        self.nodes[x].levels = Vec::new();
+        self.free.push(x);
+        self.len -= 1;
+        true
+    }
+
+    // Number of nodes for which the predicate holds, the predicate
+    // holding for the first nodes of the list and not for the others
+    pub fn count_before(&self, before: impl Fn(f64, &[u8]) -> bool) -> usize {
+        let mut rank = 0;
+        let mut x = HEAD;
+        for i in (0..self.level).rev() {
+            while let Some(next) = self.nodes[x].levels[i].forward {
+                if !before(self.nodes[next].score, &self.nodes[next].member) {
+                    break;
+                }
+                rank += self.nodes[x].levels[i].span;
+                x = next;
+            }
+        }
+        rank
+    }
+
+    // Node at a rank, starting from 0
+    fn node_at(&self, rank: usize) -> Option<usize> {
+        if rank >= self.len {
+            return None;
+        }
+        if rank == self.len - 1 {
+            return self.tail;
+        }
+        let mut traversed = 0;
+        let mut x = HEAD;
+        for i in (0..self.level).rev() {
+            while let Some(next) = self.nodes[x].levels[i].forward {
+                if traversed + self.nodes[x].levels[i].span > rank + 1 {
+                    break;
+                }
+                traversed += self.nodes[x].levels[i].span;
+                x = next;
+            }
+            if traversed == rank + 1 {
+                return Some(x);
+            }
+        }
+        None
+    }
+
+    // Members with their scores from a rank on, walking towards
+    // the head of the list if `reverse` is set
+    pub fn iter_from(&self, rank: usize, reverse: bool) -> impl Iterator<Item = (&[u8], f64)> {
+        let mut x = self.node_at(rank);
+        std::iter::from_fn(move || {
+            let node = &self.nodes[x?];
+            x = match reverse {
+                false => node.levels[0].forward,
+                true => node.backward,
+            };
+            Some((node.member.as_slice(), node.score))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn members(list: &Skiplist) -> Vec<(String, f64)> {
+        list.iter_from(0, false)
+            .map(|(member, score)| (String::from_utf8(member.to_vec()).unwrap(), score))
+            .collect()
+    }
+
+    #[test]
+    fn test_ordered_by_score_then_member() {
+        let mut list = Skiplist::new();
+        list.insert(2.0, b"b");
+        list.insert(1.0, b"z");
+        list.insert(2.0, b"a");
+        list.insert(-1.5, b"c");
+        assert_eq!(
+            members(&list),
+            vec![
+                (String::from("c"), -1.5),
+                (String::from("z"), 1.0),
+                (String::from("a"), 2.0),
+                (String::from("b"), 2.0),
+            ]
+        );
+        let reversed: Vec<&[u8]> = list.iter_from(3, true).map(|(member, _)| member).collect();
+        assert_eq!(reversed, vec![b"b", b"a", b"z", b"c"]);
+        assert_eq!(list.iter_from(4, false).count(), 0);
+    }
+
+    #[test]
+    fn test_ranks_after_removals() {
+        let mut list = Skiplist::new();
+        for i in 0..1000 {
+            list.insert(i as f64, format!("m{}", i).as_bytes());
+        }
+        for i in (0..1000).step_by(3) {
+            assert!(list.remove(i as f64, format!("m{}", i).as_bytes()));
+        }
+        assert!(!list.remove(1.0, b"missing"));
+        assert!(!list.remove(2.0, b"m1"));
+        assert_eq!(list.len(), 666);
+
+        let expected: Vec<usize> = (0..1000).filter(|i| i % 3 != 0).collect();
+        for (rank, &i) in expected.iter().enumerate() {
+            assert_eq!(
+                list.count_before(|score, _| score < i as f64),
+                rank,
+                "rank of {}",
+                i
+            );
+            let (member, score) = list.iter_from(rank, false).next().unwrap();
+            assert_eq!((member, score), (format!("m{}", i).as_bytes(), i as f64));
+        }
+
+        // freed nodes are reused
+        let nodes = list.nodes.len();
+        list.insert(0.5, b"new");
+        assert_eq!(list.nodes.len(), nodes);
+        assert_eq!(
+            list.iter_from(0, false).next(),
+            Some((b"new".as_slice(), 0.5))
+        );
+    }
+
+    #[test]
+    fn test_remove_all() {
+        let mut list = Skiplist::new();
+        for i in 0..100 {
+            list.insert(1.0, format!("{:03}", i).as_bytes());
+        }
+        for i in (0..100).rev() {
+            assert!(list.remove(1.0, format!("{:03}", i).as_bytes()));
+        }
+        assert_eq!(list.len(), 0);
+        assert_eq!(list.level, 1);
+        assert_eq!(list.tail, None);
+        assert_eq!(list.iter_from(0, false).count(), 0);
+    }
+}
@@ -1,16 +1,51 @@
-use std::{
-    collections::HashMap,
-    time::{Duration, SystemTime},
-};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use crate::{
-    set::{KeyExipry, KeyExistence, SetArgs},
-    storage_result::StorageResult,
+    active_expire::{ActiveExpireConfig, ExpiryIndex, Rng},
+    clock::{Clock, MonotonicClock},
+    dict::Dict,
+    expire::{conditions_allow, unix_time_ms, ExpireCondition, ExpireTime, KeyTTL},
+    hash::{Fields, Hash, MAX_FIELD_EXPIRY_MS},
+    number::{add_f64, parse_f64, parse_i64},
+    quicklist::Quicklist,
+    resp::MAX_BULK_LENGTH,
+    set::{KeyExipry, KeyExistence, SetArgs, SetOutcome},
+    set_type::{Set, SetOperation},
+    storage_result::{StorageError, StorageResult},
+    zset::{Aggregate, Entries, Scored, SortedSet, ZAddOptions, ZAddOutcome, ZRangeBy},
 };
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum StorageValue {
-    String(String),
+    String(Vec<u8>),
+    List(Quicklist),
+    Hash(Hash),
+    Set(Set),
+    SortedSet(SortedSet),
+}
+
+impl StorageValue {
+    // Name of the type as reported by TYPE
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            StorageValue::String(_) => "string",
+            StorageValue::List(_) => "list",
+            StorageValue::Hash(_) => "hash",
+            StorageValue::Set(_) => "set",
+            StorageValue::SortedSet(_) => "zset",
+        }
+    }
+
+    // Number of allocations released when the value is dropped
+    pub fn free_effort(&self) -> usize {
+        match self {
+            StorageValue::String(_) => 1,
+            StorageValue::List(list) => list.node_count(),
+            StorageValue::Hash(hash) => hash.allocations(),
+            StorageValue::Set(set) => set.allocations(),
+            StorageValue::SortedSet(zset) => zset.allocations(),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -21,9 +56,18 @@ pub struct StorageData {
 }
 
 pub struct Storage {
-    store: HashMap<String, StorageData>,
-    expiry: HashMap<String, SystemTime>,
+    store: Dict<StorageData>,
+    expiry: ExpiryIndex,
+    // hashes having fields with an expiry, by the earliest deadline
+    // of their fields, which may be earlier than the actual one
+    field_expiry: ExpiryIndex,
     active_expiry: bool,
+    active_expire: ActiveExpireConfig,
+    rng: Rng,
+    clock: Box<dyn Clock>,
+    // keys which received values clients may be blocked on,
+    // since the last call to take_ready_keys
+    ready_keys: Vec<Vec<u8>>,
 }
 
 impl StorageData {
@@ -32,8 +76,8 @@ impl StorageData {
     }
 }
 
-impl From<String> for StorageData {
-    fn from(s: String) -> StorageData {
+impl From<Vec<u8>> for StorageData {
+    fn from(s: Vec<u8>) -> StorageData {
         StorageData {
             value: StorageValue::String(s),
             creation_time: SystemTime::now(),
@@ -50,45 +94,189 @@ impl PartialEq for StorageData {
 
 impl Storage {
     pub fn new() -> Self {
-        let store: HashMap<String, StorageData> = HashMap::new();
+        Self::with_clock(MonotonicClock::new())
+    }
+
+    pub fn with_clock(clock: impl Clock + 'static) -> Self {
         Self {
-            store,
-            expiry: HashMap::<String, SystemTime>::new(),
+            store: Dict::new(),
+            expiry: ExpiryIndex::new(),
+            field_expiry: ExpiryIndex::new(),
             active_expiry: true,
+            active_expire: ActiveExpireConfig::default(),
+            rng: Rng::from_time(),
+            clock: Box::new(clock),
+            ready_keys: Vec::new(),
         }
     }
 
+    // Current time according to the clock of the storage
+    pub fn now(&self) -> SystemTime {
+        self.clock.now()
+    }
+
     pub fn set_active_expiry(&mut self, value: bool) {
         self.active_expiry = value;
     }
 
+    pub fn set_active_expire_effort(&mut self, effort: u8) {
+        self.active_expire = ActiveExpireConfig::with_effort(effort);
+    }
+
+    // Active expiry as done by Redis: sample random keys with an
+    // expiry and remove the expired ones, repeating while too many
+    // of the sampled keys were expired, within a time limit. The
+    // hashes with expiring fields are then sampled the same way.
     pub fn expire_keys(&mut self) {
         if !self.active_expiry {
             return;
         }
-        let now = SystemTime::now();
+        let start = Instant::now();
+        self.expire_cycle(start, false);
+        self.expire_cycle(start, true);
+    }
 
-        // iterate over the keys which has expiry set
-        let expired_keys: Vec<String> = self
-            .expiry
-            .iter()
-            .filter_map(|(key, &value)| if value < now { Some(key.clone()) } else { None })
-            .collect();
+    fn expire_cycle(&mut self, start: Instant, fields: bool) {
+        let config = self.active_expire;
+        let mut iterations: usize = 0;
+        while !self.expiry_index(fields).is_empty() {
+            let now = self.clock.now();
+            let mut sampled: usize = 0;
+            let mut expired: usize = 0;
+            while sampled < config.keys_per_loop && !self.expiry_index(fields).is_empty() {
+                let position = self.rng.below(self.expiry_index(fields).len());
+                let (key, deadline) = self.expiry_index(fields).get_index(position);
+                if deadline <= now {
+                    let key = key.to_vec();
+                    if fields {
+                        self.expire_fields(&key, now);
+                    } else {
+                        self.delete(&key);
+                    }
+                    expired += 1;
+                }
+                sampled += 1;
+            }
+
+            // checking the time every iteration would be too costly
+            iterations += 1;
+            if iterations.is_multiple_of(16) && start.elapsed() > config.time_limit {
+                break;
+            }
+            if expired * 100 <= sampled * config.acceptable_stale {
+                break;
+            }
+        }
+    }
+
+    fn expiry_index(&self, fields: bool) -> &ExpiryIndex {
+        if fields {
+            &self.field_expiry
+        } else {
+            &self.expiry
+        }
+    }
+
+    // Remove the key if its expiry time has passed, or the fields
+    // of a hash whose expiry time has passed. Returns true if the
+    // key was expired, or deleted with the last of its fields.
+    fn expire_if_needed(&mut self, key: &[u8]) -> bool {
+        let now = self.clock.now();
+        if self.expiry.get(key).is_some_and(|&expiry| now >= expiry) {
+            self.delete(key);
+            return true;
+        }
+        if self
+            .field_expiry
+            .get(key)
+            .is_some_and(|&expiry| now >= expiry)
+        {
+            return self.expire_fields(key, now);
+        }
+        false
+    }
 
-        // Remove the keys
-        for k in expired_keys {
-            self.store.remove(&k);
-            self.expiry.remove(&k);
+    // Remove the expired fields of a hash and index it by its next
+    // deadline. Returns true if the hash was deleted, being empty.
+    fn expire_fields(&mut self, key: &[u8], now: SystemTime) -> bool {
+        let next = match self.store.get_mut(key).map(|data| &mut data.value) {
+            Some(StorageValue::Hash(hash)) => {
+                hash.remove_expired(now);
+                if hash.is_empty() {
+                    self.delete(key);
+                    return true;
+                }
+                hash.next_deadline()
+            }
+            _ => None,
+        };
+        self.index_fields(key, next);
+        false
+    }
+
+    // Record the earliest deadline of the fields of a hash
+    fn index_fields(&mut self, key: &[u8], deadline: Option<SystemTime>) {
+        match deadline {
+            Some(deadline) => self.field_expiry.insert(key.to_vec(), deadline),
+            None => {
+                self.field_expiry.remove(key);
+            }
         }
     }
 
-    pub fn set(&mut self, key: String, value: String, args: SetArgs) -> StorageResult<String> {
+    // Remove a key from the store and the expiry indexes
+    fn delete(&mut self, key: &[u8]) -> Option<StorageData> {
+        self.expiry.remove(key);
+        self.field_expiry.remove(key);
+        self.store.remove(key)
+    }
+
+    // Absolute time at which a key set at `now` expires. As in Redis
+    // the time in milliseconds since the epoch must fit in an i64.
+    fn deadline(now: SystemTime, expiry: KeyExipry) -> StorageResult<SystemTime> {
+        let milliseconds = |v: u64, unit: i64| i64::try_from(v).ok()?.checked_mul(unit);
+        let relative = |ms: Option<i64>| {
+            ms.filter(|&ms| unix_time_ms(now).checked_add(ms).is_some())
+                .and_then(|ms| now.checked_add(Duration::from_millis(ms as u64)))
+        };
+        let absolute = |ms: Option<i64>| {
+            ms.and_then(|ms| UNIX_EPOCH.checked_add(Duration::from_millis(ms as u64)))
+        };
+        let deadline = match expiry {
+            KeyExipry::EX(v) => relative(milliseconds(v, 1000)),
+            KeyExipry::PX(v) => relative(milliseconds(v, 1)),
+            KeyExipry::EXAT(v) => absolute(milliseconds(v, 1000)),
+            KeyExipry::PXAT(v) => absolute(milliseconds(v, 1)),
+            KeyExipry::KEEPTTL => None,
+        };
+        deadline.ok_or(StorageError::InvalidExpireTime(String::from("set")))
+    }
+
+    pub fn set(
+        &mut self,
+        key: Vec<u8>,
+        value: Vec<u8>,
+        args: SetArgs,
+    ) -> StorageResult<SetOutcome> {
         let mut data = StorageData::from(value);
+        data.creation_time = self.clock.now();
         let mut should_insert = true;
 
-        let key_present = match self.store.get(&key) {
-            None => false,
-            _ => true,
+        self.expire_if_needed(&key);
+        let key_present = self.store.contains_key(&key);
+        // SET overwrites values of any type, but cannot return them
+        let previous = match self.store.get(&key).map(|data| &data.value) {
+            Some(StorageValue::String(v)) => Some(v.clone()),
+            Some(_) if args.get => return Err(StorageError::WrongType),
+            _ => None,
+        };
+
+        // an invalid expiry is an error even if NX or XX block the write,
+        // without an expiry option the key loses its previous TTL
+        let deadline = match args.expiry {
+            None => None,
+            Some(KeyExipry::KEEPTTL) => self.expiry.get(&key).copied(),
+            Some(expiry) => Some(Self::deadline(data.creation_time, expiry)?),
         };
 
         if let Some(value) = args.existence {
@@ -104,110 +292,2321 @@ impl Storage {
             }
         }
 
-        if let Some(value) = args.expiry {
-            let expiry = match value {
-                KeyExipry::EX(v) => Duration::from_secs(v),
-                KeyExipry::PX(v) => Duration::from_millis(v),
-            };
-            data.add_expiry(expiry);
-            self.expiry
-                .insert(key.clone(), data.creation_time.checked_add(expiry).unwrap());
+        // a write blocked by NX or XX leaves the key untouched
+        if !should_insert {
+            return Ok(SetOutcome {
+                written: false,
+                previous,
+            });
         }
-        if should_insert {
-            self.store.insert(key, data);
-            return Ok(String::from("OK"));
+
+        match deadline {
+            Some(deadline) => {
+                data.add_expiry(
+                    deadline
+                        .duration_since(data.creation_time)
+                        .unwrap_or(Duration::ZERO),
+                );
+                self.expiry.insert(key.clone(), deadline);
+            }
+            None => {
+                self.expiry.remove(&key);
+            }
         }
-        Ok(format!("Key is present {}", key_present))
+        self.field_expiry.remove(&key);
+        self.store.insert(key, data);
+        Ok(SetOutcome {
+            written: true,
+            previous,
+        })
     }
 
-    pub fn get(&mut self, key: String) -> StorageResult<Option<String>> {
-        if let Some(&expiry) = self.expiry.get(&key) {
-            if SystemTime::now() >= expiry {
-                self.expiry.remove(&key);
-                self.store.remove(&key);
-                return Ok(None);
+    // Set the expiry of an existing key, returns false if the key
+    // does not exist or the conditions are not met.
+    // A time in the past deletes the key.
+    pub fn expire(
+        &mut self,
+        key: &[u8],
+        time: ExpireTime,
+        conditions: &[ExpireCondition],
+        command: &str,
+    ) -> StorageResult<bool> {
+        self.expire_if_needed(key);
+        if !self.store.contains_key(key) {
+            return Ok(false);
+        }
+
+        let now = unix_time_ms(self.clock.now());
+        let deadline = match time {
+            ExpireTime::Relative(ms) => now.checked_add(ms),
+            ExpireTime::Absolute(ms) => Some(ms),
+        }
+        .ok_or_else(|| StorageError::InvalidExpireTime(command.to_string()))?;
+
+        let current = self.expiry.get(key).map(|&expiry| unix_time_ms(expiry));
+        if !conditions_allow(conditions, current, deadline) {
+            return Ok(false);
+        }
+
+        if deadline <= now {
+            self.delete(key);
+            return Ok(true);
+        }
+
+        let deadline = UNIX_EPOCH + Duration::from_millis(deadline as u64);
+        if let Some(data) = self.store.get_mut(key) {
+            data.add_expiry(
+                deadline
+                    .duration_since(data.creation_time)
+                    .unwrap_or(Duration::ZERO),
+            );
+        }
+        self.expiry.insert(key.to_vec(), deadline);
+        Ok(true)
+    }
+
+    pub fn ttl(&mut self, key: &[u8]) -> StorageResult<KeyTTL> {
+        if self.expire_if_needed(key) || !self.store.contains_key(key) {
+            return Ok(KeyTTL::Missing);
+        }
+        match self.expiry.get(key) {
+            Some(&expiry) => Ok(KeyTTL::Expires(unix_time_ms(expiry))),
+            None => Ok(KeyTTL::Persistent),
+        }
+    }
+
+    // Remove the expiry of a key, returns false if the
+    // key does not exist or has no expiry
+    pub fn persist(&mut self, key: &[u8]) -> StorageResult<bool> {
+        if self.expire_if_needed(key) {
+            return Ok(false);
+        }
+        if self.expiry.remove(key).is_none() {
+            return Ok(false);
+        }
+        if let Some(data) = self.store.get_mut(key) {
+            data.expiry = None;
+        }
+        Ok(true)
+    }
+
+    // String stored at a key, created empty if the key does not
+    // exist. Updating it in place keeps the expiry of the key.
+    fn string_entry(&mut self, key: &[u8]) -> StorageResult<&mut Vec<u8>> {
+        self.expire_if_needed(key);
+        let now = self.clock.now();
+        let data = self.store.get_or_insert_with(key, || {
+            let mut data = StorageData::from(Vec::new());
+            data.creation_time = now;
+            data
+        });
+        match &mut data.value {
+            StorageValue::String(v) => Ok(v),
+            _ => Err(StorageError::WrongType),
+        }
+    }
+
+    // Whether a key exists and has not expired
+    pub fn exists(&mut self, key: &[u8]) -> bool {
+        !self.expire_if_needed(key) && self.store.contains_key(key)
+    }
+
+    // Add to the integer stored at a key, a missing key counts as 0
+    pub fn incr_by(&mut self, key: &[u8], increment: i64) -> StorageResult<i64> {
+        let current = if self.exists(key) {
+            parse_i64(self.string_entry(key)?).ok_or(StorageError::NotAnInteger)?
+        } else {
+            0
+        };
+        let value = current
+            .checked_add(increment)
+            .ok_or(StorageError::Overflow)?;
+        *self.string_entry(key)? = value.to_string().into_bytes();
+        Ok(value)
+    }
+
+    // Add to the float stored at a key, a missing key counts as 0,
+    // returns the new value as stored
+    pub fn incr_by_float(&mut self, key: &[u8], increment: f64) -> StorageResult<Vec<u8>> {
+        let current = if self.exists(key) {
+            parse_f64(self.string_entry(key)?).ok_or(StorageError::NotAFloat)?
+        } else {
+            0.0
+        };
+        let value = add_f64(current, increment)
+            .ok_or(StorageError::NaNOrInfinity)?
+            .into_bytes();
+        *self.string_entry(key)? = value.clone();
+        Ok(value)
+    }
+
+    // Append to the string stored at a key, returns its new length
+    pub fn append(&mut self, key: &[u8], value: &[u8]) -> StorageResult<usize> {
+        let string = self.string_entry(key)?;
+        if string.len() + value.len() > MAX_BULK_LENGTH as usize {
+            return Err(StorageError::StringTooLong);
+        }
+        string.extend_from_slice(value);
+        Ok(string.len())
+    }
+
+    // Overwrite part of the string stored at a key, padding it with
+    // zero bytes if the offset is past its end. Returns the new length.
+    pub fn set_range(&mut self, key: &[u8], offset: usize, value: &[u8]) -> StorageResult<usize> {
+        if value.is_empty() {
+            // nothing to write, the key is not created
+            return Ok(self.get(key)?.map_or(0, |v| v.len()));
+        }
+        let end = offset
+            .checked_add(value.len())
+            .filter(|&end| end <= MAX_BULK_LENGTH as usize)
+            .ok_or(StorageError::StringTooLong)?;
+        let string = self.string_entry(key)?;
+        if string.len() < end {
+            string.resize(end, 0);
+        }
+        string[offset..end].copy_from_slice(value);
+        Ok(string.len())
+    }
+
+    pub fn get_del(&mut self, key: &[u8]) -> StorageResult<Option<Vec<u8>>> {
+        let value = self.get(key)?;
+        self.delete(key);
+        Ok(value)
+    }
+
+    // Remove a key with its expiry, returns its data if it existed
+    pub fn remove(&mut self, key: &[u8]) -> Option<StorageData> {
+        if self.expire_if_needed(key) {
+            return None;
+        }
+        self.delete(key)
+    }
+
+    pub fn key_type(&mut self, key: &[u8]) -> Option<&'static str> {
+        if self.expire_if_needed(key) {
+            return None;
+        }
+        self.store.get(key).map(|data| data.value.type_name())
+    }
+
+    // Move a key to a new name with its expiry, overwriting the
+    // destination unless `nx` is set. Returns false if nothing moved.
+    pub fn rename(&mut self, source: &[u8], destination: &[u8], nx: bool) -> StorageResult<bool> {
+        if !self.exists(source) {
+            return Err(StorageError::NoSuchKey);
+        }
+        if nx && self.exists(destination) {
+            return Ok(false);
+        }
+        if source == destination {
+            return Ok(true);
+        }
+        let deadline = self.expiry.get(source).copied();
+        let fields_deadline = self.field_expiry.get(source).copied();
+        let data = self.delete(source).ok_or(StorageError::NoSuchKey)?;
+        self.remove(destination);
+        if let Some(deadline) = deadline {
+            self.expiry.insert(destination.to_vec(), deadline);
+        }
+        self.index_fields(destination, fields_deadline);
+        if matches!(
+            data.value,
+            StorageValue::List(_) | StorageValue::SortedSet(_)
+        ) {
+            self.signal_ready(destination);
+        }
+        self.store.insert(destination.to_vec(), data);
+        Ok(true)
+    }
+
+    // Copy a key with its expiry, overwriting the destination only
+    // with `replace`. Returns false if nothing was copied.
+    pub fn copy(&mut self, source: &[u8], destination: &[u8], replace: bool) -> bool {
+        if !self.exists(source) || (!replace && self.exists(destination)) {
+            return false;
+        }
+        let now = self.clock.now();
+        let deadline = self.expiry.get(source).copied();
+        let fields_deadline = self.field_expiry.get(source).copied();
+        let value = match self.store.get(source) {
+            Some(data) => data.value.clone(),
+            None => return false,
+        };
+        self.remove(destination);
+        let data = StorageData {
+            value,
+            creation_time: now,
+            expiry: deadline.map(|deadline| deadline.duration_since(now).unwrap_or(Duration::ZERO)),
+        };
+        if let Some(deadline) = deadline {
+            self.expiry.insert(destination.to_vec(), deadline);
+        }
+        self.index_fields(destination, fields_deadline);
+        if matches!(
+            data.value,
+            StorageValue::List(_) | StorageValue::SortedSet(_)
+        ) {
+            self.signal_ready(destination);
+        }
+        self.store.insert(destination.to_vec(), data);
+        true
+    }
+
+    fn is_expired(&self, key: &[u8], now: SystemTime) -> bool {
+        self.expiry.get(key).is_some_and(|&expiry| now >= expiry)
+    }
+
+    // Keys accepted by the filter, skipping the expired ones
+    pub fn keys(&self, filter: impl Fn(&[u8]) -> bool) -> Vec<Vec<u8>> {
+        let now = self.clock.now();
+        self.store
+            .iter()
+            .filter(|(key, _)| !self.is_expired(key, now) && filter(key))
+            .map(|(key, _)| key.to_vec())
+            .collect()
+    }
+
+    // Visit the buckets of the store from the cursor until `count`
+    // keys were collected or 10 times as many buckets were visited,
+    // as SCAN does in Redis. Expired keys are removed on the way.
+    // Returns the next cursor, 0 once the scan is complete.
+    pub fn scan(&mut self, cursor: u64, count: usize) -> (u64, Vec<Vec<u8>>) {
+        let mut keys = Vec::new();
+        let mut cursor = cursor;
+        let mut buckets = count.saturating_mul(10);
+        loop {
+            cursor = self.store.scan(cursor, |key, _| keys.push(key.to_vec()));
+            buckets -= 1;
+            if cursor == 0 || buckets == 0 || keys.len() >= count {
+                break;
             }
         }
-        match self.store.get(&key) {
-            Some(StorageData {
-                value: StorageValue::String(v),
-                creation_time: _,
-                expiry: _,
-            }) => return Ok(Some(v.to_owned())),
-            None => return Ok(None),
+        keys.retain(|key| !self.expire_if_needed(key));
+        (cursor, keys)
+    }
+
+    // Move buckets of the store to its resized table for up to
+    // a millisecond, on top of the steps done on each access
+    pub fn rehash(&mut self) {
+        let start = Instant::now();
+        while self.store.rehash(100) && start.elapsed() < Duration::from_millis(1) {}
+    }
+
+    // List stored at a key, None if the key does not exist
+    fn list(&mut self, key: &[u8]) -> StorageResult<Option<&mut Quicklist>> {
+        self.expire_if_needed(key);
+        match self.store.get_mut(key).map(|data| &mut data.value) {
+            Some(StorageValue::List(list)) => Ok(Some(list)),
+            Some(_) => Err(StorageError::WrongType),
+            None => Ok(None),
         }
     }
-}
-#[cfg(test)]
-mod tests {
-    use super::*;
-    #[test]
-    fn test_create_new() {
-        let storage: Storage = Storage::new();
-        assert_eq!(storage.store.len(), 0);
-        assert_eq!(storage.expiry.len(), 0);
-        assert_eq!(storage.expiry, HashMap::<String, SystemTime>::new());
-        assert!(storage.active_expiry);
+
+    // List stored at a key, created empty if the key does not exist
+    fn list_entry(&mut self, key: &[u8]) -> StorageResult<&mut Quicklist> {
+        self.expire_if_needed(key);
+        let now = self.clock.now();
+        let data = self.store.get_or_insert_with(key, || StorageData {
+            value: StorageValue::List(Quicklist::new()),
+            creation_time: now,
+            expiry: None,
+        });
+        match &mut data.value {
+            StorageValue::List(list) => Ok(list),
+            _ => Err(StorageError::WrongType),
+        }
     }
 
-    #[test]
-    fn test_set_value() {
-        let mut storage: Storage = Storage::new();
-        let avalue = StorageData::from(String::from("avalue"));
-        let output = storage
-            .set(String::from("akey"), String::from("avalue"), SetArgs::new())
-            .unwrap();
-        assert_eq!(output, String::from("OK"));
-        assert_eq!(storage.store.len(), 1);
-        match storage.store.get(&String::from("akey")) {
-            Some(value) => assert_eq!(value, &avalue),
-            None => panic!(),
+    // Record that a key received values, so that the clients
+    // blocked on it can be served
+    fn signal_ready(&mut self, key: &[u8]) {
+        self.ready_keys.push(key.to_vec());
+    }
+
+    pub fn take_ready_keys(&mut self) -> Vec<Vec<u8>> {
+        std::mem::take(&mut self.ready_keys)
+    }
+
+    // Lists and hashes are deleted once empty, as in Redis
+    fn remove_if_empty(&mut self, key: &[u8]) {
+        let empty = match self.store.get(key).map(|data| &data.value) {
+            Some(StorageValue::List(list)) => list.is_empty(),
+            Some(StorageValue::Hash(hash)) => hash.is_empty(),
+            Some(StorageValue::Set(set)) => set.is_empty(),
+            Some(StorageValue::SortedSet(zset)) => zset.is_empty(),
+            _ => false,
+        };
+        if empty {
+            self.delete(key);
         }
     }
-    #[test]
-    fn test_get_value() {
-        let mut storage: Storage = Storage::new();
-        storage.store.insert(
-            String::from("akey"),
-            StorageData::from(String::from("avalue")),
-        );
-        let result = storage.get(String::from("akey")).unwrap();
-        assert_eq!(storage.store.len(), 1);
-        assert_eq!(result, Some(String::from("avalue")));
+
+    // Push values one after the other at the head or the tail of a
+    // list, which is created unless `existing` is set. Returns the
+    // length of the list.
+    pub fn push(
+        &mut self,
+        key: &[u8],
+        values: &[Vec<u8>],
+        front: bool,
+        existing: bool,
+    ) -> StorageResult<usize> {
+        if existing && self.list(key)?.is_none() {
+            return Ok(0);
+        }
+        let list = self.list_entry(key)?;
+        for value in values {
+            if front {
+                list.push_front(value);
+            } else {
+                list.push_back(value);
+            }
+        }
+        let length = list.len();
+        self.signal_ready(key);
+        Ok(length)
     }
-    #[test]
-    fn test_get_value_key_does_not_exist() {
-        let mut storage: Storage = Storage::new();
-        let result = storage.get(String::from("akey")).unwrap();
-        assert_eq!(storage.store.len(), 0);
-        assert_eq!(result, None);
+
+    // Pop up to `count` values from the head or the tail of a list,
+    // returns None if the key does not exist
+    pub fn pop(
+        &mut self,
+        key: &[u8],
+        count: usize,
+        front: bool,
+    ) -> StorageResult<Option<Vec<Vec<u8>>>> {
+        let Some(list) = self.list(key)? else {
+            return Ok(None);
+        };
+        let count = count.min(list.len());
+        let mut values = Vec::with_capacity(count);
+        for _ in 0..count {
+            values.extend(if front {
+                list.pop_front()
+            } else {
+                list.pop_back()
+            });
+        }
+        self.remove_if_empty(key);
+        Ok(Some(values))
     }
 
-    #[test]
-    fn test_expire_keys() {
-        let mut storage: Storage = Storage::new();
-        storage
-            .set(String::from("akey"), String::from("avalue"), SetArgs::new())
-            .unwrap();
-        storage.expiry.insert(
-            String::from("akey"),
-            SystemTime::now() - Duration::from_secs(5),
-        );
-        storage.expire_keys();
-        assert_eq!(storage.store.len(), 0);
+    pub fn list_len(&mut self, key: &[u8]) -> StorageResult<usize> {
+        Ok(self.list(key)?.map_or(0, |list| list.len()))
     }
-    #[test]
-    fn test_expire_keys_deactivated() {
-        let mut storage: Storage = Storage::new();
-        storage.set_active_expiry(false);
-        storage
-            .set(String::from("akey"), String::from("avalue"), SetArgs::new())
-            .unwrap();
-        storage.expiry.insert(
-            String::from("akey"),
-            SystemTime::now() - Duration::from_secs(5),
-        );
-        storage.expire_keys();
-        assert_eq!(storage.store.len(), 1);
+
+    // Values from start to end included, negative indexes
+    // being counted from the end of the list
+    pub fn list_range(&mut self, key: &[u8], start: i64, end: i64) -> StorageResult<Vec<Vec<u8>>> {
+        let Some(list) = self.list(key)? else {
+            return Ok(Vec::new());
+        };
+        Ok(match resolve_range(list.len(), start, end) {
+            Some((start, end)) => list.range(start, end),
+            None => Vec::new(),
+        })
+    }
+
+    pub fn list_index(&mut self, key: &[u8], index: i64) -> StorageResult<Option<Vec<u8>>> {
+        let Some(list) = self.list(key)? else {
+            return Ok(None);
+        };
+        Ok(resolve_index(list.len(), index).and_then(|index| list.get(index).map(<[u8]>::to_vec)))
+    }
+
+    pub fn list_set(&mut self, key: &[u8], index: i64, value: &[u8]) -> StorageResult<()> {
+        let list = self.list(key)?.ok_or(StorageError::NoSuchKey)?;
+        let index = resolve_index(list.len(), index).ok_or(StorageError::IndexOutOfRange)?;
+        list.replace(index, value);
+        Ok(())
+    }
+
+    // Remove occurrences of a value as LREM does: `count` of them from
+    // the head, from the tail if negative, all of them if 0.
+    // Returns the number of values removed.
+    pub fn list_remove(&mut self, key: &[u8], count: i64, value: &[u8]) -> StorageResult<usize> {
+        let Some(list) = self.list(key)? else {
+            return Ok(0);
+        };
+        let limit = match count {
+            0 => usize::MAX,
+            count => count.unsigned_abs() as usize,
+        };
+        let removed = list.remove_value(value, limit, count < 0);
+        self.remove_if_empty(key);
+        Ok(removed)
+    }
+
+    // Keep the values from start to end included, negative indexes
+    // being counted from the end of the list
+    pub fn list_trim(&mut self, key: &[u8], start: i64, end: i64) -> StorageResult<()> {
+        let Some(list) = self.list(key)? else {
+            return Ok(());
+        };
+        match resolve_range(list.len(), start, end) {
+            Some((start, end)) => list.trim(start, end),
+            None => {
+                self.remove(key);
+            }
+        }
+        Ok(())
+    }
+
+    // Insert a value before or after the first occurrence of a pivot,
+    // returns the length of the list, 0 if the key does not exist
+    // and -1 if the pivot was not found, as LINSERT replies
+    pub fn list_insert(
+        &mut self,
+        key: &[u8],
+        pivot: &[u8],
+        value: &[u8],
+        before: bool,
+    ) -> StorageResult<i64> {
+        let Some(list) = self.list(key)? else {
+            return Ok(0);
+        };
+        let Some(index) = list.iter().position(|v| v == pivot) else {
+            return Ok(-1);
+        };
+        list.insert(if before { index } else { index + 1 }, value);
+        Ok(list.len() as i64)
+    }
+
+    // Indexes of a value as LPOS finds them: skipping the first
+    // `rank - 1` matches, searching from the tail if `rank` is
+    // negative, returning up to `count` of them and comparing up
+    // to `maxlen` values. A `count` or `maxlen` of 0 means no limit.
+    pub fn list_positions(
+        &mut self,
+        key: &[u8],
+        value: &[u8],
+        rank: i64,
+        count: usize,
+        maxlen: usize,
+    ) -> StorageResult<Vec<usize>> {
+        let Some(list) = self.list(key)? else {
+            return Ok(Vec::new());
+        };
+        let len = list.len();
+        let maxlen = if maxlen == 0 { len } else { maxlen };
+        let count = if count == 0 { usize::MAX } else { count };
+        let skip = (rank.unsigned_abs() - 1) as usize;
+        let values: Box<dyn Iterator<Item = (usize, &[u8])>> = if rank > 0 {
+            Box::new(list.iter().enumerate())
+        } else {
+            Box::new(list.iter().rev().enumerate().map(|(i, v)| (len - 1 - i, v)))
+        };
+        Ok(values
+            .take(maxlen)
+            .filter(|(_, v)| *v == value)
+            .skip(skip)
+            .take(count)
+            .map(|(i, _)| i)
+            .collect())
+    }
+
+    // Pop a value from the head or the tail of a list and push it
+    // to another one, returns None if the source does not exist.
+    // Both keys are checked to be lists before anything changes.
+    pub fn list_move(
+        &mut self,
+        source: &[u8],
+        destination: &[u8],
+        from_front: bool,
+        to_front: bool,
+    ) -> StorageResult<Option<Vec<u8>>> {
+        if self.list(source)?.is_none() {
+            return Ok(None);
+        }
+        self.list(destination)?;
+        let Some(list) = self.list(source)? else {
+            return Ok(None);
+        };
+        let Some(value) = (if from_front {
+            list.pop_front()
+        } else {
+            list.pop_back()
+        }) else {
+            return Ok(None);
+        };
+        let list = self.list_entry(destination)?;
+        if to_front {
+            list.push_front(&value);
+        } else {
+            list.push_back(&value);
+        }
+        self.signal_ready(destination);
+        self.remove_if_empty(source);
+        Ok(Some(value))
+    }
+
+    // Hash stored at a key, None if the key does not exist
+    fn hash(&mut self, key: &[u8]) -> StorageResult<Option<&mut Hash>> {
+        self.expire_if_needed(key);
+        match self.store.get_mut(key).map(|data| &mut data.value) {
+            Some(StorageValue::Hash(hash)) => Ok(Some(hash)),
+            Some(_) => Err(StorageError::WrongType),
+            None => Ok(None),
+        }
+    }
+
+    // Hash stored at a key, created empty if the key does not exist
+    fn hash_entry(&mut self, key: &[u8]) -> StorageResult<&mut Hash> {
+        self.expire_if_needed(key);
+        let now = self.clock.now();
+        let data = self.store.get_or_insert_with(key, || StorageData {
+            value: StorageValue::Hash(Hash::new()),
+            creation_time: now,
+            expiry: None,
+        });
+        match &mut data.value {
+            StorageValue::Hash(hash) => Ok(hash),
+            _ => Err(StorageError::WrongType),
+        }
+    }
+
+    // Set fields of a hash, `pairs` holding each field followed by
+    // its value. The fields lose their expiry, as in Redis.
+    // Returns the number of fields that were added.
+    pub fn hash_set(&mut self, key: &[u8], pairs: &[Vec<u8>]) -> StorageResult<usize> {
+        let hash = self.hash_entry(key)?;
+        let mut added = 0;
+        for pair in pairs.chunks_exact(2) {
+            hash.persist(&pair[0]);
+            if hash.insert(&pair[0], &pair[1]) {
+                added += 1;
+            }
+        }
+        Ok(added)
+    }
+
+    // Set a field of a hash unless it exists, returns true if it was set
+    pub fn hash_set_nx(&mut self, key: &[u8], field: &[u8], value: &[u8]) -> StorageResult<bool> {
+        let hash = self.hash_entry(key)?;
+        if hash.contains(field) {
+            return Ok(false);
+        }
+        Ok(hash.insert(field, value))
+    }
+
+    pub fn hash_get(&mut self, key: &[u8], field: &[u8]) -> StorageResult<Option<Vec<u8>>> {
+        Ok(self
+            .hash(key)?
+            .and_then(|hash| hash.get(field).map(<[u8]>::to_vec)))
+    }
+
+    pub fn hash_get_many(
+        &mut self,
+        key: &[u8],
+        fields: &[Vec<u8>],
+    ) -> StorageResult<Vec<Option<Vec<u8>>>> {
+        let hash = self.hash(key)?;
+        Ok(fields
+            .iter()
+            .map(|field| {
+                hash.as_ref()
+                    .and_then(|hash| hash.get(field).map(<[u8]>::to_vec))
+            })
+            .collect())
+    }
+
+    // Remove fields of a hash, returns the number of fields removed
+    pub fn hash_delete(&mut self, key: &[u8], fields: &[Vec<u8>]) -> StorageResult<usize> {
+        let Some(hash) = self.hash(key)? else {
+            return Ok(0);
+        };
+        let removed = fields.iter().filter(|field| hash.remove(field)).count();
+        self.remove_if_empty(key);
+        Ok(removed)
+    }
+
+    pub fn hash_len(&mut self, key: &[u8]) -> StorageResult<usize> {
+        Ok(self.hash(key)?.map_or(0, |hash| hash.len()))
+    }
+
+    pub fn hash_exists(&mut self, key: &[u8], field: &[u8]) -> StorageResult<bool> {
+        Ok(self.hash(key)?.is_some_and(|hash| hash.contains(field)))
+    }
+
+    // Length of the value of a field, 0 if it does not exist
+    pub fn hash_strlen(&mut self, key: &[u8], field: &[u8]) -> StorageResult<usize> {
+        Ok(self
+            .hash(key)?
+            .and_then(|hash| hash.get(field).map(<[u8]>::len))
+            .unwrap_or(0))
+    }
+
+    // Fields of a hash with their values
+    pub fn hash_entries(&mut self, key: &[u8]) -> StorageResult<Fields> {
+        Ok(self.hash(key)?.map_or_else(Vec::new, |hash| {
+            hash.iter()
+                .map(|(field, value)| (field.to_vec(), value.to_vec()))
+                .collect()
+        }))
+    }
+
+    // Add to the integer stored in a field, a missing field counts as 0
+    pub fn hash_incr_by(&mut self, key: &[u8], field: &[u8], increment: i64) -> StorageResult<i64> {
+        let hash = self.hash_entry(key)?;
+        let current = match hash.get(field) {
+            Some(value) => parse_i64(value).ok_or(StorageError::HashValueNotAnInteger)?,
+            None => 0,
+        };
+        let value = current
+            .checked_add(increment)
+            .ok_or(StorageError::Overflow)?;
+        hash.insert(field, value.to_string().as_bytes());
+        Ok(value)
+    }
+
+    // Add to the float stored in a field, a missing field counts as 0,
+    // returns the new value as stored
+    pub fn hash_incr_by_float(
+        &mut self,
+        key: &[u8],
+        field: &[u8],
+        increment: f64,
+    ) -> StorageResult<Vec<u8>> {
+        // the value is checked before the hash is created, so
+        // that an error doesn't leave an empty hash behind
+        let current = match self.hash(key)?.and_then(|hash| hash.get(field)) {
+            Some(value) => parse_f64(value).ok_or(StorageError::HashValueNotAFloat)?,
+            None => 0.0,
+        };
+        let value = add_f64(current, increment)
+            .ok_or(StorageError::NaNOrInfinity)?
+            .into_bytes();
+        self.hash_entry(key)?.insert(field, &value);
+        Ok(value)
+    }
+
+    // Visit the buckets of a hash from the cursor until `count` fields
+    // were collected or 10 times as many buckets were visited, as
+    // scan does for keys. Returns the next cursor, 0 once complete.
+    pub fn hash_scan(
+        &mut self,
+        key: &[u8],
+        cursor: u64,
+        count: usize,
+    ) -> StorageResult<(u64, Fields)> {
+        let Some(hash) = self.hash(key)? else {
+            return Ok((0, Vec::new()));
+        };
+        let mut fields = Vec::new();
+        let mut cursor = cursor;
+        let mut buckets = count.saturating_mul(10);
+        loop {
+            cursor = hash.scan(cursor, |field, value| {
+                fields.push((field.to_vec(), value.to_vec()))
+            });
+            buckets -= 1;
+            if cursor == 0 || buckets == 0 || fields.len() >= count {
+                break;
+            }
+        }
+        Ok((cursor, fields))
+    }
+
+    // Set the expiry of fields of a hash, as HEXPIRE does. Replies
+    // for each field as HEXPIRE: -2 if it does not exist, 0 if the
+    // conditions are not met, 1 if the expiry was set and 2 if the
+    // field was deleted, the time being in the past.
+    pub fn hash_expire(
+        &mut self,
+        key: &[u8],
+        fields: &[Vec<u8>],
+        time: ExpireTime,
+        conditions: &[ExpireCondition],
+        command: &str,
+    ) -> StorageResult<Vec<i64>> {
+        let now = self.clock.now();
+        let now_ms = unix_time_ms(now);
+        let deadline = match time {
+            ExpireTime::Relative(ms) => now_ms.checked_add(ms),
+            ExpireTime::Absolute(ms) => Some(ms),
+        }
+        .filter(|&deadline| deadline <= MAX_FIELD_EXPIRY_MS)
+        .ok_or_else(|| StorageError::InvalidExpireTime(command.to_string()))?;
+        let Some(hash) = self.hash(key)? else {
+            return Ok(vec![-2; fields.len()]);
+        };
+
+        let mut replies = Vec::with_capacity(fields.len());
+        for field in fields {
+            if !hash.contains(field) {
+                replies.push(-2);
+                continue;
+            }
+            let current = hash.expiry(field).map(unix_time_ms);
+            if !conditions_allow(conditions, current, deadline) {
+                replies.push(0);
+            } else if deadline <= now_ms {
+                hash.remove(field);
+                replies.push(2);
+            } else {
+                hash.set_expiry(field, UNIX_EPOCH + Duration::from_millis(deadline as u64));
+                replies.push(1);
+            }
+        }
+        let next = hash.next_deadline();
+        if let Some(next) = next {
+            // an earlier entry of the index is fine, the fields
+            // are checked again when it is reached
+            if self
+                .field_expiry
+                .get(key)
+                .is_none_or(|&indexed| next < indexed)
+            {
+                self.field_expiry.insert(key.to_vec(), next);
+            }
+        }
+        self.remove_if_empty(key);
+        Ok(replies)
+    }
+
+    // Expiry of fields of a hash, as HTTL reports them
+    pub fn hash_ttl(&mut self, key: &[u8], fields: &[Vec<u8>]) -> StorageResult<Vec<KeyTTL>> {
+        let hash = self.hash(key)?;
+        Ok(fields
+            .iter()
+            .map(|field| match hash.as_ref() {
+                Some(hash) if hash.contains(field) => match hash.expiry(field) {
+                    Some(deadline) => KeyTTL::Expires(unix_time_ms(deadline)),
+                    None => KeyTTL::Persistent,
+                },
+                _ => KeyTTL::Missing,
+            })
+            .collect())
+    }
+
+    // Remove the expiry of fields of a hash. Replies for each field
+    // as HPERSIST: -2 if it does not exist, -1 if it has no expiry
+    // and 1 if its expiry was removed.
+    pub fn hash_persist(&mut self, key: &[u8], fields: &[Vec<u8>]) -> StorageResult<Vec<i64>> {
+        let Some(hash) = self.hash(key)? else {
+            return Ok(vec![-2; fields.len()]);
+        };
+        Ok(fields
+            .iter()
+            .map(|field| {
+                if !hash.contains(field) {
+                    -2
+                } else if hash.persist(field) {
+                    1
+                } else {
+                    -1
+                }
+            })
+            .collect())
+    }
+
+    // Random fields of a hash with their values, see Hash::random_fields
+    pub fn hash_random_fields(
+        &mut self,
+        key: &[u8],
+        count: usize,
+        unique: bool,
+    ) -> StorageResult<Fields> {
+        self.expire_if_needed(key);
+        match self.store.get(key).map(|data| &data.value) {
+            Some(StorageValue::Hash(hash)) => Ok(hash.random_fields(count, unique, &mut self.rng)),
+            Some(_) => Err(StorageError::WrongType),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    // Set stored at a key, if any
+    fn get_set(&mut self, key: &[u8]) -> StorageResult<Option<&mut Set>> {
+        self.expire_if_needed(key);
+        match self.store.get_mut(key).map(|data| &mut data.value) {
+            Some(StorageValue::Set(set)) => Ok(Some(set)),
+            Some(_) => Err(StorageError::WrongType),
+            None => Ok(None),
+        }
+    }
+
+    // Set stored at a key, created empty if the key does not exist
+    fn set_entry(&mut self, key: &[u8]) -> StorageResult<&mut Set> {
+        self.expire_if_needed(key);
+        let now = self.clock.now();
+        let data = self.store.get_or_insert_with(key, || StorageData {
+            value: StorageValue::Set(Set::new()),
+            creation_time: now,
+            expiry: None,
+        });
+        match &mut data.value {
+            StorageValue::Set(set) => Ok(set),
+            _ => Err(StorageError::WrongType),
+        }
+    }
+
+    // Sets stored at the keys, None for the missing ones
+    fn sets(&mut self, keys: &[Vec<u8>]) -> StorageResult<Vec<Option<&Set>>> {
+        for key in keys {
+            self.expire_if_needed(key);
+        }
+        keys.iter()
+            .map(|key| match self.store.get(key).map(|data| &data.value) {
+                Some(StorageValue::Set(set)) => Ok(Some(set)),
+                Some(_) => Err(StorageError::WrongType),
+                None => Ok(None),
+            })
+            .collect()
+    }
+
+    // Add members to a set, returns the number of new members
+    pub fn set_add(&mut self, key: &[u8], members: &[Vec<u8>]) -> StorageResult<usize> {
+        let set = self.set_entry(key)?;
+        Ok(members.iter().filter(|member| set.insert(member)).count())
+    }
+
+    // Remove members from a set, deleting it once empty.
+    // Returns the number of members removed.
+    pub fn set_remove(&mut self, key: &[u8], members: &[Vec<u8>]) -> StorageResult<usize> {
+        let Some(set) = self.get_set(key)? else {
+            return Ok(0);
+        };
+        let removed = members.iter().filter(|member| set.remove(member)).count();
+        self.remove_if_empty(key);
+        Ok(removed)
+    }
+
+    pub fn set_is_member(&mut self, key: &[u8], member: &[u8]) -> StorageResult<bool> {
+        Ok(self.get_set(key)?.is_some_and(|set| set.contains(member)))
+    }
+
+    pub fn set_members(&mut self, key: &[u8]) -> StorageResult<Vec<Vec<u8>>> {
+        Ok(self
+            .get_set(key)?
+            .map_or_else(Vec::new, |set| set.iter().collect()))
+    }
+
+    pub fn set_len(&mut self, key: &[u8]) -> StorageResult<usize> {
+        Ok(self.get_set(key)?.map_or(0, |set| set.len()))
+    }
+
+    // Members resulting of an operation between sets, as SINTER,
+    // SUNION and SDIFF reply them
+    pub fn set_combine(
+        &mut self,
+        keys: &[Vec<u8>],
+        operation: SetOperation,
+    ) -> StorageResult<Vec<Vec<u8>>> {
+        let sets = self.sets(keys)?;
+        Ok(Set::combine(&sets, operation).iter().collect())
+    }
+
+    // Store the result of an operation between sets at the
+    // destination, which is deleted if the result is empty.
+    // Returns the number of members stored.
+    pub fn set_combine_store(
+        &mut self,
+        destination: &[u8],
+        keys: &[Vec<u8>],
+        operation: SetOperation,
+    ) -> StorageResult<usize> {
+        let result = Set::combine(&self.sets(keys)?, operation);
+        let len = result.len();
+        self.delete(destination);
+        if len > 0 {
+            let data = StorageData {
+                value: StorageValue::Set(result),
+                creation_time: self.clock.now(),
+                expiry: None,
+            };
+            self.store.insert(destination.to_vec(), data);
+        }
+        Ok(len)
+    }
+
+    // Number of members of the intersection of sets, counting
+    // no further than `limit` unless it is 0, as SINTERCARD
+    pub fn set_intersection_len(&mut self, keys: &[Vec<u8>], limit: usize) -> StorageResult<usize> {
+        let sets = self.sets(keys)?;
+        let members = Set::intersection(&sets);
+        Ok(match limit {
+            0 => members.count(),
+            limit => members.take(limit).count(),
+        })
+    }
+
+    // Move a member from a set to another, returns false
+    // if it is not a member of the source
+    pub fn set_move(
+        &mut self,
+        source: &[u8],
+        destination: &[u8],
+        member: &[u8],
+    ) -> StorageResult<bool> {
+        match self.get_set(source)? {
+            None => return Ok(false),
+            Some(set) if source == destination => return Ok(set.contains(member)),
+            Some(_) => {}
+        }
+        // the destination is checked before the source is changed
+        self.get_set(destination)?;
+        if !self.get_set(source)?.is_some_and(|set| set.remove(member)) {
+            return Ok(false);
+        }
+        self.remove_if_empty(source);
+        self.set_entry(destination)?.insert(member);
+        Ok(true)
+    }
+
+    // Remove and return random members of a set, see Set::pop
+    pub fn set_pop(&mut self, key: &[u8], count: usize) -> StorageResult<Vec<Vec<u8>>> {
+        self.expire_if_needed(key);
+        let members = match self.store.get_mut(key).map(|data| &mut data.value) {
+            Some(StorageValue::Set(set)) => set.pop(count, &mut self.rng),
+            Some(_) => return Err(StorageError::WrongType),
+            None => return Ok(Vec::new()),
+        };
+        self.remove_if_empty(key);
+        Ok(members)
+    }
+
+    // Random members of a set, see Set::random_members
+    pub fn set_random_members(
+        &mut self,
+        key: &[u8],
+        count: usize,
+        unique: bool,
+    ) -> StorageResult<Vec<Vec<u8>>> {
+        self.expire_if_needed(key);
+        match self.store.get(key).map(|data| &data.value) {
+            Some(StorageValue::Set(set)) => Ok(set.random_members(count, unique, &mut self.rng)),
+            Some(_) => Err(StorageError::WrongType),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    // Sorted set stored at a key, if any
+    fn zset(&mut self, key: &[u8]) -> StorageResult<Option<&mut SortedSet>> {
+        self.expire_if_needed(key);
+        match self.store.get_mut(key).map(|data| &mut data.value) {
+            Some(StorageValue::SortedSet(zset)) => Ok(Some(zset)),
+            Some(_) => Err(StorageError::WrongType),
+            None => Ok(None),
+        }
+    }
+
+    // Sorted set stored at a key, created empty if the key does not exist
+    fn zset_entry(&mut self, key: &[u8]) -> StorageResult<&mut SortedSet> {
+        self.expire_if_needed(key);
+        let now = self.clock.now();
+        let data = self.store.get_or_insert_with(key, || StorageData {
+            value: StorageValue::SortedSet(SortedSet::new()),
+            creation_time: now,
+            expiry: None,
+        });
+        match &mut data.value {
+            StorageValue::SortedSet(zset) => Ok(zset),
+            _ => Err(StorageError::WrongType),
+        }
+    }
+
+    // Add members with their scores as ZADD does. Returns the number
+    // of new members, along with the updated ones if CH is set.
+    pub fn zset_add(
+        &mut self,
+        key: &[u8],
+        entries: &[(f64, Vec<u8>)],
+        options: &ZAddOptions,
+    ) -> StorageResult<usize> {
+        let zset = self.zset_entry(key)?;
+        let mut count = 0;
+        for (score, member) in entries {
+            match zset.add(member, *score, options, false) {
+                ZAddOutcome::Added(_) => count += 1,
+                ZAddOutcome::Updated(_) if options.ch => count += 1,
+                _ => {}
+            }
+        }
+        // options such as XX may leave a new set empty
+        self.remove_if_empty(key);
+        self.signal_ready(key);
+        Ok(count)
+    }
+
+    // Increment the score of a member as ZINCRBY and ZADD INCR do,
+    // returns the new score, or None if the options prevented it
+    pub fn zset_incr_by(
+        &mut self,
+        key: &[u8],
+        member: &[u8],
+        increment: f64,
+        options: &ZAddOptions,
+    ) -> StorageResult<Option<f64>> {
+        let outcome = self.zset_entry(key)?.add(member, increment, options, true);
+        self.remove_if_empty(key);
+        self.signal_ready(key);
+        match outcome {
+            ZAddOutcome::Added(score)
+            | ZAddOutcome::Updated(score)
+            | ZAddOutcome::Unchanged(score) => Ok(Some(score)),
+            ZAddOutcome::Skipped => Ok(None),
+            ZAddOutcome::NotANumber => Err(StorageError::ScoreNotANumber),
+        }
+    }
+
+    // Remove members from a sorted set, deleting it once empty.
+    // Returns the number of members removed.
+    pub fn zset_remove(&mut self, key: &[u8], members: &[Vec<u8>]) -> StorageResult<usize> {
+        let Some(zset) = self.zset(key)? else {
+            return Ok(0);
+        };
+        let removed = members.iter().filter(|member| zset.remove(member)).count();
+        self.remove_if_empty(key);
+        Ok(removed)
+    }
+
+    pub fn zset_score(&mut self, key: &[u8], member: &[u8]) -> StorageResult<Option<f64>> {
+        Ok(self.zset(key)?.and_then(|zset| zset.score(member)))
+    }
+
+    pub fn zset_len(&mut self, key: &[u8]) -> StorageResult<usize> {
+        Ok(self.zset(key)?.map_or(0, |zset| zset.len()))
+    }
+
+    // Rank of a member with its score, the highest score
+    // having rank 0 if `reverse` is set
+    pub fn zset_rank(
+        &mut self,
+        key: &[u8],
+        member: &[u8],
+        reverse: bool,
+    ) -> StorageResult<Option<(usize, f64)>> {
+        let Some(zset) = self.zset(key)? else {
+            return Ok(None);
+        };
+        let (Some(rank), Some(score)) = (zset.rank(member), zset.score(member)) else {
+            return Ok(None);
+        };
+        let rank = if reverse { zset.len() - 1 - rank } else { rank };
+        Ok(Some((rank, score)))
+    }
+
+    // Members with their scores as ZRANGE selects them, from the
+    // highest score if `reverse` is set. `limit` holds the offset and
+    // count of LIMIT, a negative count selecting all the members.
+    pub fn zset_range(
+        &mut self,
+        key: &[u8],
+        by: &ZRangeBy,
+        reverse: bool,
+        limit: Option<(i64, i64)>,
+    ) -> StorageResult<Entries> {
+        let Some(zset) = self.zset(key)? else {
+            return Ok(Vec::new());
+        };
+        let len = zset.len();
+        let (start, end) = match by {
+            ZRangeBy::Rank(start, stop) => match resolve_range(len, *start, *stop) {
+                // ranks counted from the highest score
+                Some((start, stop)) if reverse => (len - 1 - stop, len - start),
+                Some((start, stop)) => (start, stop + 1),
+                None => return Ok(Vec::new()),
+            },
+            ZRangeBy::Score(min, max) => zset.score_window(min, max),
+            ZRangeBy::Lex(min, max) => zset.lex_window(min, max),
+        };
+        let (start, end) = match limit {
+            None => (start, end),
+            Some((offset, _)) if offset < 0 => return Ok(Vec::new()),
+            Some((offset, count)) => {
+                let offset = offset as usize;
+                let count = usize::try_from(count).unwrap_or(usize::MAX);
+                if reverse {
+                    let end = end.saturating_sub(offset).max(start);
+                    (end.saturating_sub(count).max(start), end)
+                } else {
+                    let start = start.saturating_add(offset).min(end);
+                    (start, start.saturating_add(count).min(end))
+                }
+            }
+        };
+        Ok(zset.entries(start, end, reverse))
+    }
+
+    // Store the members of a range of a sorted set as ZRANGESTORE
+    // does, see zset_range. Returns the number of members stored.
+    pub fn zset_range_store(
+        &mut self,
+        destination: &[u8],
+        source: &[u8],
+        by: &ZRangeBy,
+        reverse: bool,
+        limit: Option<(i64, i64)>,
+    ) -> StorageResult<usize> {
+        let mut result = SortedSet::new();
+        for (member, score) in self.zset_range(source, by, reverse, limit)? {
+            result.insert(&member, score);
+        }
+        Ok(self.store_zset(destination, result))
+    }
+
+    // Sets and sorted sets stored at keys, as inputs of ZUNIONSTORE
+    fn scored(&mut self, keys: &[Vec<u8>]) -> StorageResult<Vec<Option<Scored<'_>>>> {
+        for key in keys {
+            self.expire_if_needed(key);
+        }
+        keys.iter()
+            .map(|key| match self.store.get(key).map(|data| &data.value) {
+                Some(StorageValue::Set(set)) => Ok(Some(Scored::Set(set))),
+                Some(StorageValue::SortedSet(zset)) => Ok(Some(Scored::SortedSet(zset))),
+                Some(_) => Err(StorageError::WrongType),
+                None => Ok(None),
+            })
+            .collect()
+    }
+
+    // Store the result of an operation between sorted sets or sets,
+    // see SortedSet::combine. Returns the number of members stored.
+    pub fn zset_combine_store(
+        &mut self,
+        destination: &[u8],
+        keys: &[Vec<u8>],
+        weights: &[f64],
+        aggregate: Aggregate,
+        operation: SetOperation,
+    ) -> StorageResult<usize> {
+        let result = SortedSet::combine(&self.scored(keys)?, weights, aggregate, operation);
+        Ok(self.store_zset(destination, result))
+    }
+
+    // Replace a key by a sorted set, only deleting it if the
+    // sorted set is empty. Returns the length of the sorted set.
+    fn store_zset(&mut self, key: &[u8], zset: SortedSet) -> usize {
+        let len = zset.len();
+        self.delete(key);
+        if len > 0 {
+            let data = StorageData {
+                value: StorageValue::SortedSet(zset),
+                creation_time: self.clock.now(),
+                expiry: None,
+            };
+            self.store.insert(key.to_vec(), data);
+            self.signal_ready(key);
+        }
+        len
+    }
+
+    // Remove members from the lowest score, or from the highest if
+    // `max` is set, as ZMPOP does. None if the key does not exist.
+    pub fn zset_pop(
+        &mut self,
+        key: &[u8],
+        count: usize,
+        max: bool,
+    ) -> StorageResult<Option<Entries>> {
+        let Some(zset) = self.zset(key)? else {
+            return Ok(None);
+        };
+        let entries = zset.pop(count, max);
+        self.remove_if_empty(key);
+        Ok(Some(entries))
+    }
+
+    pub fn get(&mut self, key: &[u8]) -> StorageResult<Option<Vec<u8>>> {
+        if self.expire_if_needed(key) {
+            return Ok(None);
+        }
+        match self.store.get(key) {
+            Some(StorageData {
+                value: StorageValue::String(v),
+                creation_time: _,
+                expiry: _,
+            }) => Ok(Some(v.to_owned())),
+            Some(_) => Err(StorageError::WrongType),
+            None => Ok(None),
+        }
+    }
+}
+
+// Index counted from the end of a list when negative,
+// None if it falls out of the list
+fn resolve_index(len: usize, index: i64) -> Option<usize> {
+    let index = if index < 0 { index + len as i64 } else { index };
+    (0..len as i64).contains(&index).then_some(index as usize)
+}
+
+// Range from start to end included, as LRANGE and LTRIM take it,
+// None if it selects nothing
+fn resolve_range(len: usize, start: i64, end: i64) -> Option<(usize, usize)> {
+    let len = len as i64;
+    let start = if start < 0 {
+        (len + start).max(0)
+    } else {
+        start
+    };
+    let end = if end < 0 { len + end } else { end.min(len - 1) };
+    if start > end || start >= len {
+        return None;
+    }
+    Some((start as usize, end as usize))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use crate::zset::ScoreBound;
+
+    // Storage whose time only moves when the returned clock is advanced
+    fn storage_with_manual_clock() -> (Storage, ManualClock) {
+        let clock = ManualClock::new(UNIX_EPOCH + Duration::from_secs(1700000000));
+        (Storage::with_clock(clock.clone()), clock)
+    }
+
+    fn expiring(ms: u64) -> SetArgs {
+        let mut args = SetArgs::new();
+        args.expiry = Some(KeyExipry::PX(ms));
+        args
+    }
+    #[test]
+    fn test_create_new() {
+        let storage: Storage = Storage::new();
+        assert_eq!(storage.store.len(), 0);
+        assert!(storage.expiry.is_empty());
+        assert!(storage.active_expiry);
+        assert_eq!(storage.active_expire, ActiveExpireConfig::with_effort(1));
+    }
+
+    #[test]
+    fn test_set_value() {
+        let mut storage: Storage = Storage::new();
+        let avalue = StorageData::from(b"avalue".to_vec());
+        let output = storage
+            .set(b"akey".to_vec(), b"avalue".to_vec(), SetArgs::new())
+            .unwrap();
+        assert_eq!(
+            output,
+            SetOutcome {
+                written: true,
+                previous: None
+            }
+        );
+        assert_eq!(storage.store.len(), 1);
+        match storage.store.get(b"akey".as_slice()) {
+            Some(value) => assert_eq!(value, &avalue),
+            None => panic!(),
+        }
+    }
+    #[test]
+    fn test_get_value() {
+        let mut storage: Storage = Storage::new();
+        storage
+            .store
+            .insert(b"akey".to_vec(), StorageData::from(b"avalue".to_vec()));
+        let result = storage.get(b"akey").unwrap();
+        assert_eq!(storage.store.len(), 1);
+        assert_eq!(result, Some(b"avalue".to_vec()));
+    }
+    #[test]
+    fn test_get_value_key_does_not_exist() {
+        let mut storage: Storage = Storage::new();
+        let result = storage.get(b"akey").unwrap();
+        assert_eq!(storage.store.len(), 0);
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn test_expire_keys() {
+        let (mut storage, clock) = storage_with_manual_clock();
+        storage
+            .set(b"akey".to_vec(), b"avalue".to_vec(), expiring(1000))
+            .unwrap();
+        clock.advance(Duration::from_secs(5));
+        storage.expire_keys();
+        assert_eq!(storage.store.len(), 0);
+    }
+    #[test]
+    fn test_expire_keys_deactivated() {
+        let (mut storage, clock) = storage_with_manual_clock();
+        storage.set_active_expiry(false);
+        storage
+            .set(b"akey".to_vec(), b"avalue".to_vec(), expiring(1000))
+            .unwrap();
+        clock.advance(Duration::from_secs(5));
+        storage.expire_keys();
+        assert_eq!(storage.store.len(), 1);
+    }
+
+    #[test]
+    fn test_set_get_binary_value() {
+        let mut storage: Storage = Storage::new();
+        let key = vec![0x00, 0xc3, 0x28];
+        let value = vec![0xff, 0x00, 0x80, b'\r', b'\n'];
+        storage
+            .set(key.clone(), value.clone(), SetArgs::new())
+            .unwrap();
+        assert_eq!(storage.get(&key).unwrap(), Some(value));
+    }
+
+    #[test]
+    fn test_set_nx_blocked_keeps_expiry() {
+        let mut storage: Storage = Storage::new();
+        let mut args = SetArgs::new();
+        args.existence = Some(KeyExistence::NX);
+        args.expiry = Some(KeyExipry::EX(100));
+        storage
+            .set(b"akey".to_vec(), b"avalue".to_vec(), SetArgs::new())
+            .unwrap();
+        let output = storage
+            .set(b"akey".to_vec(), b"other".to_vec(), args)
+            .unwrap();
+        assert_eq!(
+            output,
+            SetOutcome {
+                written: false,
+                previous: Some(b"avalue".to_vec())
+            }
+        );
+        assert_eq!(storage.expiry.len(), 0);
+        assert_eq!(storage.get(b"akey").unwrap(), Some(b"avalue".to_vec()));
+    }
+
+    #[test]
+    fn test_set_nx_on_expired_key() {
+        let (mut storage, clock) = storage_with_manual_clock();
+        storage.set_active_expiry(false);
+        storage
+            .set(b"akey".to_vec(), b"avalue".to_vec(), expiring(1000))
+            .unwrap();
+        clock.advance(Duration::from_secs(5));
+        let mut args = SetArgs::new();
+        args.existence = Some(KeyExistence::NX);
+        let output = storage
+            .set(b"akey".to_vec(), b"other".to_vec(), args)
+            .unwrap();
+        assert_eq!(
+            output,
+            SetOutcome {
+                written: true,
+                previous: None
+            }
+        );
+        assert_eq!(storage.get(b"akey").unwrap(), Some(b"other".to_vec()));
+    }
+
+    #[test]
+    fn test_set_clears_expiry() {
+        let mut storage: Storage = Storage::new();
+        let mut args = SetArgs::new();
+        args.expiry = Some(KeyExipry::EX(100));
+        storage
+            .set(b"akey".to_vec(), b"avalue".to_vec(), args)
+            .unwrap();
+        assert_eq!(storage.expiry.len(), 1);
+        storage
+            .set(b"akey".to_vec(), b"other".to_vec(), SetArgs::new())
+            .unwrap();
+        assert_eq!(storage.expiry.len(), 0);
+        assert_eq!(storage.store.get(b"akey".as_slice()).unwrap().expiry, None);
+    }
+
+    #[test]
+    fn test_set_keepttl() {
+        let mut storage: Storage = Storage::new();
+        let mut args = SetArgs::new();
+        args.expiry = Some(KeyExipry::EX(100));
+        storage
+            .set(b"akey".to_vec(), b"avalue".to_vec(), args)
+            .unwrap();
+        let deadline = *storage.expiry.get(b"akey".as_slice()).unwrap();
+
+        let mut args = SetArgs::new();
+        args.expiry = Some(KeyExipry::KEEPTTL);
+        storage
+            .set(b"akey".to_vec(), b"other".to_vec(), args)
+            .unwrap();
+        assert_eq!(storage.expiry.get(b"akey".as_slice()), Some(&deadline));
+        assert_eq!(storage.get(b"akey").unwrap(), Some(b"other".to_vec()));
+    }
+
+    #[test]
+    fn test_set_keepttl_without_expiry() {
+        let mut storage: Storage = Storage::new();
+        let mut args = SetArgs::new();
+        args.expiry = Some(KeyExipry::KEEPTTL);
+        storage
+            .set(b"akey".to_vec(), b"avalue".to_vec(), args)
+            .unwrap();
+        assert_eq!(storage.expiry.len(), 0);
+    }
+
+    #[test]
+    fn test_set_absolute_expiry() {
+        let mut storage: Storage = Storage::new();
+        let mut args = SetArgs::new();
+        args.expiry = Some(KeyExipry::EXAT(4102444800));
+        storage
+            .set(b"akey".to_vec(), b"avalue".to_vec(), args)
+            .unwrap();
+        assert_eq!(
+            storage.expiry.get(b"akey".as_slice()),
+            Some(&(UNIX_EPOCH + Duration::from_secs(4102444800)))
+        );
+
+        let mut args = SetArgs::new();
+        args.expiry = Some(KeyExipry::PXAT(4102444800123));
+        storage
+            .set(b"bkey".to_vec(), b"bvalue".to_vec(), args)
+            .unwrap();
+        assert_eq!(
+            storage.expiry.get(b"bkey".as_slice()),
+            Some(&(UNIX_EPOCH + Duration::from_millis(4102444800123)))
+        );
+    }
+
+    #[test]
+    fn test_set_absolute_expiry_in_the_past() {
+        let mut storage: Storage = Storage::new();
+        let mut args = SetArgs::new();
+        args.expiry = Some(KeyExipry::PXAT(1000));
+        storage
+            .set(b"akey".to_vec(), b"avalue".to_vec(), args)
+            .unwrap();
+        assert_eq!(storage.get(b"akey").unwrap(), None);
+    }
+
+    #[test]
+    fn test_set_expiry_overflow() {
+        let mut storage: Storage = Storage::new();
+        let mut args = SetArgs::new();
+        args.expiry = Some(KeyExipry::EX(u64::MAX / 10));
+        assert_eq!(
+            storage
+                .set(b"akey".to_vec(), b"avalue".to_vec(), args)
+                .unwrap_err(),
+            StorageError::InvalidExpireTime(String::from("set"))
+        );
+        assert_eq!(storage.store.len(), 0);
+    }
+
+    #[test]
+    fn test_set_expiry_overflows_unix_time() {
+        let mut storage: Storage = Storage::new();
+        for expiry in [
+            KeyExipry::PX(i64::MAX as u64),
+            KeyExipry::EX(i64::MAX as u64 / 1000),
+            KeyExipry::PXAT(i64::MAX as u64 + 1),
+            KeyExipry::EXAT(i64::MAX as u64 / 1000 + 1),
+        ] {
+            let mut args = SetArgs::new();
+            args.expiry = Some(expiry);
+            assert_eq!(
+                storage
+                    .set(b"akey".to_vec(), b"avalue".to_vec(), args)
+                    .unwrap_err(),
+                StorageError::InvalidExpireTime(String::from("set"))
+            );
+        }
+        assert_eq!(storage.store.len(), 0);
+    }
+
+    #[test]
+    fn test_set_expiry_checked_before_nx() {
+        let mut storage: Storage = Storage::new();
+        storage
+            .set(b"akey".to_vec(), b"avalue".to_vec(), SetArgs::new())
+            .unwrap();
+        let mut args = SetArgs::new();
+        args.existence = Some(KeyExistence::NX);
+        args.expiry = Some(KeyExipry::EX(u64::MAX / 10));
+        assert_eq!(
+            storage
+                .set(b"akey".to_vec(), b"other".to_vec(), args)
+                .unwrap_err(),
+            StorageError::InvalidExpireTime(String::from("set"))
+        );
+        assert_eq!(storage.get(b"akey").unwrap(), Some(b"avalue".to_vec()));
+    }
+
+    #[test]
+    fn test_expire_missing_key() {
+        let mut storage: Storage = Storage::new();
+        let output = storage
+            .expire(b"akey", ExpireTime::Relative(10000), &[], "expire")
+            .unwrap();
+        assert!(!output);
+        assert_eq!(storage.ttl(b"akey").unwrap(), KeyTTL::Missing);
+    }
+
+    #[test]
+    fn test_expire_and_ttl() {
+        let mut storage: Storage = Storage::new();
+        storage
+            .set(b"akey".to_vec(), b"avalue".to_vec(), SetArgs::new())
+            .unwrap();
+        assert_eq!(storage.ttl(b"akey").unwrap(), KeyTTL::Persistent);
+
+        let output = storage
+            .expire(
+                b"akey",
+                ExpireTime::Absolute(4102444800000),
+                &[],
+                "expireat",
+            )
+            .unwrap();
+        assert!(output);
+        assert_eq!(
+            storage.ttl(b"akey").unwrap(),
+            KeyTTL::Expires(4102444800000)
+        );
+        assert!(storage
+            .store
+            .get(b"akey".as_slice())
+            .unwrap()
+            .expiry
+            .is_some());
+    }
+
+    #[test]
+    fn test_expire_conditions() {
+        let mut storage: Storage = Storage::new();
+        storage
+            .set(b"akey".to_vec(), b"avalue".to_vec(), SetArgs::new())
+            .unwrap();
+        let time = ExpireTime::Absolute(4102444800000);
+        assert!(!storage
+            .expire(b"akey", time, &[ExpireCondition::XX], "expire")
+            .unwrap());
+        assert!(storage
+            .expire(b"akey", time, &[ExpireCondition::NX], "expire")
+            .unwrap());
+        let later = ExpireTime::Absolute(4102444900000);
+        assert!(!storage
+            .expire(b"akey", later, &[ExpireCondition::LT], "expire")
+            .unwrap());
+        assert!(storage
+            .expire(b"akey", later, &[ExpireCondition::GT], "expire")
+            .unwrap());
+        assert_eq!(
+            storage.ttl(b"akey").unwrap(),
+            KeyTTL::Expires(4102444900000)
+        );
+    }
+
+    #[test]
+    fn test_expire_in_the_past_deletes_key() {
+        let mut storage: Storage = Storage::new();
+        storage
+            .set(b"akey".to_vec(), b"avalue".to_vec(), SetArgs::new())
+            .unwrap();
+        assert!(storage
+            .expire(b"akey", ExpireTime::Relative(-1000), &[], "expire")
+            .unwrap());
+        assert_eq!(storage.store.len(), 0);
+        assert_eq!(storage.expiry.len(), 0);
+    }
+
+    #[test]
+    fn test_expire_overflow() {
+        let mut storage: Storage = Storage::new();
+        storage
+            .set(b"akey".to_vec(), b"avalue".to_vec(), SetArgs::new())
+            .unwrap();
+        assert_eq!(
+            storage
+                .expire(b"akey", ExpireTime::Relative(i64::MAX), &[], "pexpire")
+                .unwrap_err(),
+            StorageError::InvalidExpireTime(String::from("pexpire"))
+        );
+    }
+
+    #[test]
+    fn test_ttl_expired_key() {
+        let (mut storage, clock) = storage_with_manual_clock();
+        storage.set_active_expiry(false);
+        storage
+            .set(b"akey".to_vec(), b"avalue".to_vec(), expiring(1000))
+            .unwrap();
+        clock.advance(Duration::from_secs(5));
+        assert_eq!(storage.ttl(b"akey").unwrap(), KeyTTL::Missing);
+        assert_eq!(storage.store.len(), 0);
+    }
+
+    #[test]
+    fn test_persist() {
+        let mut storage: Storage = Storage::new();
+        assert!(!storage.persist(b"akey").unwrap());
+        let mut args = SetArgs::new();
+        args.expiry = Some(KeyExipry::EX(100));
+        storage
+            .set(b"akey".to_vec(), b"avalue".to_vec(), args)
+            .unwrap();
+        assert!(storage.persist(b"akey").unwrap());
+        assert!(!storage.persist(b"akey").unwrap());
+        assert_eq!(storage.ttl(b"akey").unwrap(), KeyTTL::Persistent);
+        assert_eq!(storage.store.get(b"akey".as_slice()).unwrap().expiry, None);
+    }
+
+    // Storage with `live` keys expiring in the future and `expired`
+    // keys whose expiry has passed
+    fn storage_with_expiry(live: usize, expired: usize) -> Storage {
+        let (mut storage, clock) = storage_with_manual_clock();
+        storage.rng = Rng::new(7);
+        for i in 0..live + expired {
+            let ms = if i < live { 100000 } else { 1000 };
+            storage
+                .set(
+                    format!("key:{}", i).into_bytes(),
+                    b"v".to_vec(),
+                    expiring(ms),
+                )
+                .unwrap();
+        }
+        clock.advance(Duration::from_secs(5));
+        storage
+    }
+
+    fn stale_percentage(storage: &Storage) -> f64 {
+        let now = storage.now();
+        let stale = (0..storage.expiry.len())
+            .filter(|&i| storage.expiry.get_index(i).1 <= now)
+            .count();
+        stale as f64 * 100.0 / storage.expiry.len() as f64
+    }
+
+    #[test]
+    fn test_expire_keys_keeps_live_keys() {
+        let mut storage = storage_with_expiry(1000, 1000);
+        for _ in 0..100 {
+            storage.expire_keys();
+        }
+        assert_eq!(storage.expiry.len(), storage.store.len());
+        assert!(storage.store.len() >= 1000);
+        for i in 0..1000 {
+            assert!(storage.store.contains_key(format!("key:{}", i).as_bytes()));
+        }
+    }
+
+    #[test]
+    fn test_expire_keys_under_acceptable_stale() {
+        for effort in [1, 5, 10] {
+            let mut storage = storage_with_expiry(10000, 10000);
+            storage.set_active_expire_effort(effort);
+            let target = storage.active_expire.acceptable_stale as f64;
+            for _ in 0..1000 {
+                storage.expire_keys();
+            }
+            let stale = stale_percentage(&storage);
+            assert!(
+                stale < target,
+                "effort {}: {}% of the keys are expired",
+                effort,
+                stale
+            );
+        }
+    }
+
+    #[test]
+    fn test_expire_keys_bounded_per_cycle() {
+        let mut storage = storage_with_expiry(0, 200000);
+        storage.expire_keys();
+        // a single cycle stops at its time limit
+        assert!(!storage.expiry.is_empty());
+        assert!(storage.expiry.len() < 200000);
+    }
+
+    #[test]
+    fn test_expiry_follows_the_clock() {
+        let (mut storage, clock) = storage_with_manual_clock();
+        storage
+            .set(b"akey".to_vec(), b"avalue".to_vec(), expiring(1000))
+            .unwrap();
+        clock.advance(Duration::from_millis(999));
+        assert_eq!(storage.get(b"akey").unwrap(), Some(b"avalue".to_vec()));
+        assert_eq!(
+            storage.ttl(b"akey").unwrap(),
+            KeyTTL::Expires(1700000001000)
+        );
+        clock.advance(Duration::from_millis(1));
+        assert_eq!(storage.get(b"akey").unwrap(), None);
+    }
+
+    #[test]
+    fn test_incr_by() {
+        let mut storage: Storage = Storage::new();
+        assert_eq!(storage.incr_by(b"counter", 1).unwrap(), 1);
+        assert_eq!(storage.incr_by(b"counter", -5).unwrap(), -4);
+        assert_eq!(storage.get(b"counter").unwrap(), Some(b"-4".to_vec()));
+    }
+
+    #[test]
+    fn test_incr_by_errors() {
+        let mut storage: Storage = Storage::new();
+        for value in ["abc", "1.5", " 1", "007"] {
+            storage
+                .set(b"akey".to_vec(), value.as_bytes().to_vec(), SetArgs::new())
+                .unwrap();
+            assert_eq!(
+                storage.incr_by(b"akey", 1).unwrap_err(),
+                StorageError::NotAnInteger
+            );
+        }
+        storage
+            .set(
+                b"akey".to_vec(),
+                i64::MAX.to_string().into_bytes(),
+                SetArgs::new(),
+            )
+            .unwrap();
+        assert_eq!(
+            storage.incr_by(b"akey", 1).unwrap_err(),
+            StorageError::Overflow
+        );
+        assert_eq!(
+            storage.get(b"akey").unwrap(),
+            Some(i64::MAX.to_string().into_bytes())
+        );
+        assert_eq!(storage.incr_by(b"missing", 0).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_incr_by_keeps_expiry() {
+        let (mut storage, clock) = storage_with_manual_clock();
+        storage
+            .set(b"akey".to_vec(), b"10".to_vec(), expiring(1000))
+            .unwrap();
+        assert_eq!(storage.incr_by(b"akey", 1).unwrap(), 11);
+        assert_eq!(
+            storage.ttl(b"akey").unwrap(),
+            KeyTTL::Expires(1700000001000)
+        );
+        clock.advance(Duration::from_secs(2));
+        assert_eq!(storage.incr_by(b"akey", 1).unwrap(), 1);
+        assert_eq!(storage.ttl(b"akey").unwrap(), KeyTTL::Persistent);
+    }
+
+    #[test]
+    fn test_incr_by_float() {
+        let mut storage: Storage = Storage::new();
+        storage
+            .set(b"akey".to_vec(), b"10.50".to_vec(), SetArgs::new())
+            .unwrap();
+        assert_eq!(storage.incr_by_float(b"akey", 0.1).unwrap(), b"10.6");
+        assert_eq!(storage.incr_by_float(b"akey", -5.0).unwrap(), b"5.6");
+        storage
+            .set(b"bkey".to_vec(), b"5.0e3".to_vec(), SetArgs::new())
+            .unwrap();
+        assert_eq!(storage.incr_by_float(b"bkey", 2.0e2).unwrap(), b"5200");
+        assert_eq!(storage.incr_by_float(b"ckey", 3.0).unwrap(), b"3");
+        assert_eq!(storage.incr_by_float(b"dkey", 0.1).unwrap(), b"0.1");
+        assert_eq!(storage.incr_by_float(b"dkey", 0.2).unwrap(), b"0.3");
+    }
+
+    #[test]
+    fn test_incr_by_float_errors() {
+        let mut storage: Storage = Storage::new();
+        storage
+            .set(b"akey".to_vec(), b"abc".to_vec(), SetArgs::new())
+            .unwrap();
+        assert_eq!(
+            storage.incr_by_float(b"akey", 1.0).unwrap_err(),
+            StorageError::NotAFloat
+        );
+        storage
+            .set(b"akey".to_vec(), b"1".to_vec(), SetArgs::new())
+            .unwrap();
+        assert_eq!(
+            storage.incr_by_float(b"akey", f64::INFINITY).unwrap_err(),
+            StorageError::NaNOrInfinity
+        );
+        assert_eq!(storage.get(b"akey").unwrap(), Some(b"1".to_vec()));
+    }
+
+    #[test]
+    fn test_append() {
+        let (mut storage, _) = storage_with_manual_clock();
+        assert_eq!(storage.append(b"akey", b"hello").unwrap(), 5);
+        storage
+            .expire(b"akey", ExpireTime::Relative(1000), &[], "expire")
+            .unwrap();
+        assert_eq!(storage.append(b"akey", b" world").unwrap(), 11);
+        assert_eq!(storage.get(b"akey").unwrap(), Some(b"hello world".to_vec()));
+        assert_eq!(
+            storage.ttl(b"akey").unwrap(),
+            KeyTTL::Expires(1700000001000)
+        );
+    }
+
+    #[test]
+    fn test_set_range() {
+        let mut storage: Storage = Storage::new();
+        storage
+            .set(b"akey".to_vec(), b"Hello World".to_vec(), SetArgs::new())
+            .unwrap();
+        assert_eq!(storage.set_range(b"akey", 6, b"Redis").unwrap(), 11);
+        assert_eq!(storage.get(b"akey").unwrap(), Some(b"Hello Redis".to_vec()));
+        assert_eq!(storage.set_range(b"bkey", 3, b"ab").unwrap(), 5);
+        assert_eq!(storage.get(b"bkey").unwrap(), Some(b"\0\0\0ab".to_vec()));
+    }
+
+    #[test]
+    fn test_set_range_empty_value() {
+        let mut storage: Storage = Storage::new();
+        assert_eq!(storage.set_range(b"akey", 10, b"").unwrap(), 0);
+        assert_eq!(storage.store.len(), 0);
+    }
+
+    #[test]
+    fn test_set_range_too_long() {
+        let mut storage: Storage = Storage::new();
+        assert_eq!(
+            storage
+                .set_range(b"akey", MAX_BULK_LENGTH as usize, b"a")
+                .unwrap_err(),
+            StorageError::StringTooLong
+        );
+        assert_eq!(storage.store.len(), 0);
+    }
+
+    #[test]
+    fn test_get_del() {
+        let mut storage: Storage = Storage::new();
+        storage
+            .set(b"akey".to_vec(), b"avalue".to_vec(), expiring(1000))
+            .unwrap();
+        assert_eq!(storage.get_del(b"akey").unwrap(), Some(b"avalue".to_vec()));
+        assert_eq!(storage.get_del(b"akey").unwrap(), None);
+        assert!(storage.expiry.is_empty());
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut storage: Storage = Storage::new();
+        storage
+            .set(b"akey".to_vec(), b"avalue".to_vec(), expiring(1000))
+            .unwrap();
+        assert_eq!(
+            storage.remove(b"akey").map(|data| data.value),
+            Some(StorageValue::String(b"avalue".to_vec()))
+        );
+        assert!(storage.remove(b"akey").is_none());
+        assert!(storage.expiry.is_empty());
+    }
+
+    #[test]
+    fn test_key_type() {
+        let mut storage: Storage = Storage::new();
+        storage
+            .set(b"akey".to_vec(), b"avalue".to_vec(), SetArgs::new())
+            .unwrap();
+        assert_eq!(storage.key_type(b"akey"), Some("string"));
+        assert_eq!(storage.key_type(b"missing"), None);
+    }
+
+    #[test]
+    fn test_rename_moves_expiry() {
+        let (mut storage, _) = storage_with_manual_clock();
+        storage
+            .set(b"akey".to_vec(), b"avalue".to_vec(), expiring(1000))
+            .unwrap();
+        storage
+            .set(b"bkey".to_vec(), b"bvalue".to_vec(), SetArgs::new())
+            .unwrap();
+        assert!(storage.rename(b"akey", b"bkey", false).unwrap());
+        assert_eq!(storage.get(b"akey").unwrap(), None);
+        assert_eq!(storage.get(b"bkey").unwrap(), Some(b"avalue".to_vec()));
+        assert_eq!(
+            storage.ttl(b"bkey").unwrap(),
+            KeyTTL::Expires(1700000001000)
+        );
+        assert_eq!(storage.expiry.len(), 1);
+    }
+
+    #[test]
+    fn test_rename_clears_destination_expiry() {
+        let mut storage: Storage = Storage::new();
+        storage
+            .set(b"akey".to_vec(), b"avalue".to_vec(), SetArgs::new())
+            .unwrap();
+        storage
+            .set(b"bkey".to_vec(), b"bvalue".to_vec(), expiring(1000))
+            .unwrap();
+        assert!(storage.rename(b"akey", b"bkey", false).unwrap());
+        assert_eq!(storage.ttl(b"bkey").unwrap(), KeyTTL::Persistent);
+        assert!(storage.expiry.is_empty());
+    }
+
+    #[test]
+    fn test_rename_nx_and_missing() {
+        let mut storage: Storage = Storage::new();
+        assert_eq!(
+            storage.rename(b"akey", b"bkey", false).unwrap_err(),
+            StorageError::NoSuchKey
+        );
+        storage
+            .set(b"akey".to_vec(), b"avalue".to_vec(), SetArgs::new())
+            .unwrap();
+        storage
+            .set(b"bkey".to_vec(), b"bvalue".to_vec(), SetArgs::new())
+            .unwrap();
+        assert!(!storage.rename(b"akey", b"bkey", true).unwrap());
+        assert!(!storage.rename(b"akey", b"akey", true).unwrap());
+        assert!(storage.rename(b"akey", b"akey", false).unwrap());
+        assert_eq!(storage.get(b"akey").unwrap(), Some(b"avalue".to_vec()));
+    }
+
+    #[test]
+    fn test_copy() {
+        let (mut storage, _) = storage_with_manual_clock();
+        storage
+            .set(b"akey".to_vec(), b"avalue".to_vec(), expiring(1000))
+            .unwrap();
+        storage
+            .set(b"bkey".to_vec(), b"bvalue".to_vec(), SetArgs::new())
+            .unwrap();
+        assert!(!storage.copy(b"missing", b"ckey", false));
+        assert!(!storage.copy(b"akey", b"bkey", false));
+        assert!(storage.copy(b"akey", b"bkey", true));
+        assert_eq!(storage.get(b"akey").unwrap(), Some(b"avalue".to_vec()));
+        assert_eq!(storage.get(b"bkey").unwrap(), Some(b"avalue".to_vec()));
+        assert_eq!(
+            storage.ttl(b"bkey").unwrap(),
+            KeyTTL::Expires(1700000001000)
+        );
+    }
+
+    #[test]
+    fn test_keys() {
+        let (mut storage, clock) = storage_with_manual_clock();
+        for key in ["one", "two", "three"] {
+            storage
+                .set(key.as_bytes().to_vec(), b"v".to_vec(), SetArgs::new())
+                .unwrap();
+        }
+        storage
+            .set(b"expired".to_vec(), b"v".to_vec(), expiring(1000))
+            .unwrap();
+        clock.advance(Duration::from_secs(2));
+        let mut keys = storage.keys(|key| key.starts_with(b"t") || key == b"expired");
+        keys.sort();
+        assert_eq!(keys, vec![b"three".to_vec(), b"two".to_vec()]);
+    }
+
+    #[test]
+    fn test_scan() {
+        let (mut storage, clock) = storage_with_manual_clock();
+        for i in 0..100 {
+            let args = if i < 10 {
+                expiring(1000)
+            } else {
+                SetArgs::new()
+            };
+            storage
+                .set(format!("key:{}", i).into_bytes(), b"v".to_vec(), args)
+                .unwrap();
+        }
+        clock.advance(Duration::from_secs(2));
+        let mut keys = Vec::new();
+        let mut cursor = 0;
+        loop {
+            let (next, found) = storage.scan(cursor, 10);
+            keys.extend(found);
+            cursor = next;
+            if cursor == 0 {
+                break;
+            }
+        }
+        keys.sort();
+        keys.dedup();
+        assert_eq!(keys.len(), 90);
+        assert!(!keys.contains(&b"key:0".to_vec()));
+        // the expired keys found by the scan were removed
+        assert_eq!(storage.store.len(), 90);
+    }
+
+    #[test]
+    fn test_string_commands_on_list() {
+        let mut storage = Storage::new();
+        storage
+            .push(b"list", &[b"a".to_vec()], true, false)
+            .unwrap();
+        assert_eq!(storage.get(b"list"), Err(StorageError::WrongType));
+        assert_eq!(storage.append(b"list", b"b"), Err(StorageError::WrongType));
+        assert_eq!(storage.incr_by(b"list", 1), Err(StorageError::WrongType));
+        let mut args = SetArgs::new();
+        args.get = true;
+        assert_eq!(
+            storage.set(b"list".to_vec(), b"v".to_vec(), args),
+            Err(StorageError::WrongType)
+        );
+        assert_eq!(storage.key_type(b"list"), Some("list"));
+
+        // without GET, SET replaces a value of any type
+        storage
+            .set(b"list".to_vec(), b"v".to_vec(), SetArgs::new())
+            .unwrap();
+        assert_eq!(storage.get(b"list").unwrap(), Some(b"v".to_vec()));
+        assert_eq!(
+            storage.push(b"list", &[b"a".to_vec()], true, false),
+            Err(StorageError::WrongType)
+        );
+    }
+
+    #[test]
+    fn test_list_keeps_expiry() {
+        let (mut storage, clock) = storage_with_manual_clock();
+        storage.set_active_expiry(false);
+        storage
+            .push(b"list", &[b"a".to_vec()], true, false)
+            .unwrap();
+        storage
+            .expire(b"list", ExpireTime::Relative(1000), &[], "expire")
+            .unwrap();
+        storage
+            .push(b"list", &[b"b".to_vec()], true, false)
+            .unwrap();
+        assert!(matches!(storage.ttl(b"list").unwrap(), KeyTTL::Expires(_)));
+        clock.advance(Duration::from_secs(2));
+        assert_eq!(storage.list_len(b"list").unwrap(), 0);
+        assert!(!storage.exists(b"list"));
+    }
+
+    #[test]
+    fn test_hash_field_expiry() {
+        let (mut storage, clock) = storage_with_manual_clock();
+        let fields = |names: &[&str]| -> Vec<Vec<u8>> {
+            names.iter().map(|name| name.as_bytes().to_vec()).collect()
+        };
+        storage
+            .hash_set(b"hash", &fields(&["a", "1", "b", "2", "c", "3"]))
+            .unwrap();
+        assert_eq!(
+            storage
+                .hash_expire(
+                    b"hash",
+                    &fields(&["a", "b", "d"]),
+                    ExpireTime::Relative(1000),
+                    &[],
+                    "hpexpire"
+                )
+                .unwrap(),
+            vec![1, 1, -2]
+        );
+        // HSET clears the expiry of the fields it sets
+        storage.hash_set(b"hash", &fields(&["b", "4"])).unwrap();
+        assert_eq!(
+            storage.hash_ttl(b"hash", &fields(&["a", "b"])).unwrap(),
+            vec![KeyTTL::Expires(1700000001000), KeyTTL::Persistent]
+        );
+
+        // expired lazily when the hash is accessed
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(storage.hash_get(b"hash", b"a").unwrap(), None);
+        assert_eq!(storage.hash_len(b"hash").unwrap(), 2);
+        assert!(storage.field_expiry.is_empty());
+
+        // and actively, deleting the hash along with its last field
+        storage
+            .hash_expire(
+                b"hash",
+                &fields(&["b", "c"]),
+                ExpireTime::Relative(1000),
+                &[],
+                "hpexpire",
+            )
+            .unwrap();
+        storage.rename(b"hash", b"other", false).unwrap();
+        clock.advance(Duration::from_secs(1));
+        storage.expire_keys();
+        assert_eq!(storage.store.len(), 0);
+        assert!(storage.field_expiry.is_empty());
+    }
+
+    #[test]
+    fn test_hash_type() {
+        let mut storage = Storage::new();
+        storage
+            .hash_set(b"hash", &[b"field".to_vec(), b"value".to_vec()])
+            .unwrap();
+        assert_eq!(storage.key_type(b"hash"), Some("hash"));
+        assert_eq!(storage.get(b"hash"), Err(StorageError::WrongType));
+        assert_eq!(storage.list_len(b"hash"), Err(StorageError::WrongType));
+        storage
+            .push(b"list", &[b"a".to_vec()], true, false)
+            .unwrap();
+        assert_eq!(storage.hash_len(b"list"), Err(StorageError::WrongType));
+        assert_eq!(
+            storage.hash_incr_by(b"list", b"field", 1),
+            Err(StorageError::WrongType)
+        );
+
+        // the copy of a hash is independent from it
+        assert!(storage.copy(b"hash", b"other", false));
+        storage.hash_delete(b"hash", &[b"field".to_vec()]).unwrap();
+        assert!(!storage.exists(b"hash"));
+        assert_eq!(
+            storage.hash_get(b"other", b"field").unwrap(),
+            Some(b"value".to_vec())
+        );
+    }
+
+    #[test]
+    fn test_set_type() {
+        let mut storage = Storage::new();
+        let members = |names: &[&str]| -> Vec<Vec<u8>> {
+            names.iter().map(|name| name.as_bytes().to_vec()).collect()
+        };
+        storage.set_add(b"a", &members(&["1", "2", "3"])).unwrap();
+        storage.set_add(b"b", &members(&["2", "3", "x"])).unwrap();
+        assert_eq!(storage.key_type(b"a"), Some("set"));
+        assert_eq!(storage.hash_len(b"a"), Err(StorageError::WrongType));
+        storage
+            .push(b"list", &[b"a".to_vec()], true, false)
+            .unwrap();
+        assert_eq!(
+            storage.set_combine(&members(&["a", "list"]), SetOperation::Union),
+            Err(StorageError::WrongType)
+        );
+
+        // storing an empty result deletes the destination
+        assert_eq!(
+            storage
+                .set_combine_store(b"list", &members(&["a", "b"]), SetOperation::Intersection)
+                .unwrap(),
+            2
+        );
+        assert_eq!(storage.key_type(b"list"), Some("set"));
+        assert_eq!(
+            storage
+                .set_combine_store(
+                    b"list",
+                    &members(&["a", "missing"]),
+                    SetOperation::Intersection
+                )
+                .unwrap(),
+            0
+        );
+        assert!(!storage.exists(b"list"));
+        assert_eq!(
+            storage
+                .set_intersection_len(&members(&["a", "b"]), 1)
+                .unwrap(),
+            1
+        );
+
+        // moving the last member deletes the source
+        storage.set_add(b"c", &members(&["y"])).unwrap();
+        assert!(storage.set_move(b"c", b"a", b"y").unwrap());
+        assert!(!storage.exists(b"c"));
+        assert!(storage.set_is_member(b"a", b"y").unwrap());
+        assert!(!storage.set_move(b"c", b"a", b"y").unwrap());
+        storage
+            .set(b"s".to_vec(), b"v".to_vec(), SetArgs::new())
+            .unwrap();
+        assert_eq!(
+            storage.set_move(b"a", b"s", b"1"),
+            Err(StorageError::WrongType)
+        );
+        assert!(storage.set_is_member(b"a", b"1").unwrap());
+
+        assert_eq!(storage.set_pop(b"b", 5).unwrap().len(), 3);
+        assert!(!storage.exists(b"b"));
+    }
+
+    #[test]
+    fn test_zset_type() {
+        let mut storage = Storage::new();
+        let options = ZAddOptions::default();
+        let entries: Vec<(f64, Vec<u8>)> = (0..200)
+            .map(|i| (i as f64, format!("m{:03}", i).into_bytes()))
+            .collect();
+        assert_eq!(storage.zset_add(b"z", &entries, &options).unwrap(), 200);
+        assert_eq!(storage.key_type(b"z"), Some("zset"));
+        assert_eq!(storage.hash_len(b"z"), Err(StorageError::WrongType));
+        assert_eq!(
+            storage.zset_rank(b"z", b"m010", true).unwrap(),
+            Some((189, 10.0))
+        );
+
+        let range = |storage: &mut Storage, by, reverse, limit| -> Vec<f64> {
+            storage
+                .zset_range(b"z", &by, reverse, limit)
+                .unwrap()
+                .into_iter()
+                .map(|(_, score)| score)
+                .collect()
+        };
+        assert_eq!(
+            range(&mut storage, ZRangeBy::Rank(0, 2), true, None),
+            vec![199.0, 198.0, 197.0]
+        );
+        assert_eq!(
+            range(&mut storage, ZRangeBy::Rank(-2, -1), false, None),
+            vec![198.0, 199.0]
+        );
+        let scores = ZRangeBy::Score(
+            ScoreBound {
+                value: 10.0,
+                exclusive: true,
+            },
+            ScoreBound {
+                value: 20.0,
+                exclusive: false,
+            },
+        );
+        assert_eq!(
+            range(&mut storage, scores.clone(), false, Some((8, 5))),
+            vec![19.0, 20.0]
+        );
+        assert_eq!(
+            range(&mut storage, scores.clone(), true, Some((1, 2))),
+            vec![19.0, 18.0]
+        );
+        assert!(range(&mut storage, scores, false, Some((-1, 5))).is_empty());
+
+        // a NaN increment leaves the sorted set untouched
+        storage
+            .zset_add(b"inf", &[(f64::INFINITY, b"a".to_vec())], &options)
+            .unwrap();
+        assert_eq!(
+            storage.zset_incr_by(b"inf", b"a", f64::NEG_INFINITY, &options),
+            Err(StorageError::ScoreNotANumber)
+        );
+        assert_eq!(
+            storage.zset_score(b"inf", b"a").unwrap(),
+            Some(f64::INFINITY)
+        );
+        assert_eq!(storage.zset_remove(b"inf", &[b"a".to_vec()]).unwrap(), 1);
+        assert!(!storage.exists(b"inf"));
+
+        // storing a sorted set signals the blocked clients
+        storage.take_ready_keys();
+        let keys = vec![b"z".to_vec()];
+        let weights = [1.0];
+        assert_eq!(
+            storage
+                .zset_combine_store(b"top", &keys, &weights, Aggregate::Max, SetOperation::Union)
+                .unwrap(),
+            200
+        );
+        assert_eq!(storage.take_ready_keys(), vec![b"top".to_vec()]);
+
+        // popping the last members deletes the sorted set
+        assert_eq!(storage.zset_pop(b"top", 2, true).unwrap().unwrap().len(), 2);
+        assert_eq!(
+            storage.zset_pop(b"top", 500, false).unwrap().unwrap().len(),
+            198
+        );
+        assert!(!storage.exists(b"top"));
+        assert_eq!(storage.zset_pop(b"top", 1, false).unwrap(), None);
+    }
+
+    #[test]
+    fn test_resolve_range() {
+        assert_eq!(resolve_range(4, 0, -1), Some((0, 3)));
+        assert_eq!(resolve_range(4, -2, 10), Some((2, 3)));
+        assert_eq!(resolve_range(4, i64::MIN, i64::MAX), Some((0, 3)));
+        assert_eq!(resolve_range(4, 2, 1), None);
+        assert_eq!(resolve_range(4, 4, 5), None);
+        assert_eq!(resolve_range(0, 0, -1), None);
+        assert_eq!(resolve_index(4, -4), Some(0));
+        assert_eq!(resolve_index(4, -5), None);
+        assert_eq!(resolve_index(4, 4), None);
     }
 }
@@ -1,11 +1,25 @@
 use std::fmt;
 
+#[allow(dead_code)]
 #[derive(Debug, PartialEq)]
 pub enum StorageError {
     IncorrectRequest,
     CommandSyntaxError(String),
     CommandInternalError(String),
     CommandNotAvailable(String),
+    NotAnInteger,
+    NotAFloat,
+    HashValueNotAnInteger,
+    HashValueNotAFloat,
+    Overflow,
+    NaNOrInfinity,
+    ScoreNotANumber,
+    StringTooLong,
+    NoSuchKey,
+    WrongType,
+    IndexOutOfRange,
+    InvalidExpireTime(String),
+    IncompatibleOptions(String),
 }
 
 impl fmt::Display for StorageError {
@@ -23,6 +37,29 @@ impl fmt::Display for StorageError {
             StorageError::CommandInternalError(c) => {
                 write!(f, "internal error while processing {}", c)
             }
+            StorageError::NotAnInteger => {
+                write!(f, "value is not an integer or out of range")
+            }
+            StorageError::NotAFloat => write!(f, "value is not a valid float"),
+            StorageError::HashValueNotAnInteger => write!(f, "hash value is not an integer"),
+            StorageError::HashValueNotAFloat => write!(f, "hash value is not a float"),
+            StorageError::Overflow => write!(f, "increment or decrement would overflow"),
+            StorageError::NaNOrInfinity => {
+                write!(f, "increment would produce NaN or Infinity")
+            }
+            StorageError::ScoreNotANumber => write!(f, "resulting score is not a number (NaN)"),
+            StorageError::StringTooLong => write!(f, "string exceeds maximum allowed size"),
+            StorageError::NoSuchKey => write!(f, "no such key"),
+            StorageError::WrongType => {
+                write!(f, "operation against a key holding the wrong kind of value")
+            }
+            StorageError::IndexOutOfRange => write!(f, "index out of range"),
+            StorageError::InvalidExpireTime(c) => {
+                write!(f, "invalid expire time in '{}' command", c)
+            }
+            StorageError::IncompatibleOptions(c) => {
+                write!(f, "{} options at the same time are not compatible", c)
+            }
         }
     }
 }
@@ -0,0 +1,741 @@
+use std::{collections::HashMap, fmt};
+
+use crate::{
+    dict::Dict,
+    listpack::Listpack,
+    number::parse_f64,
+    set_type::{Set, SetOperation},
+    skiplist::Skiplist,
+};
+
+// Same defaults as zset-max-listpack-entries and zset-max-listpack-value
+// in Redis: past either limit a sorted set is converted to a skiplist
+const LISTPACK_MAX_ENTRIES: usize = 128;
+const LISTPACK_MAX_VALUE: usize = 64;
+
+// A listpack of members followed by their scores, in order, while the
+// set is small, where every operation is a cheap scan of a single
+// buffer. Once large, a skiplist keeps the order and a hash table the
+// score of each member. It never goes back to a listpack.
+#[derive(Clone)]
+enum Encoding {
+    Listpack(Listpack),
+    Skiplist { scores: Dict<f64>, list: Skiplist },
+}
+
+#[derive(Clone)]
+pub struct SortedSet {
+    encoding: Encoding,
+}
+
+// Members of a sorted set with their scores
+pub type Entries = Vec<(Vec<u8>, f64)>;
+
+// Options of ZADD
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub struct ZAddOptions {
+    pub nx: bool, // only add new members
+    pub xx: bool, // only update existing members
+    pub gt: bool, // only update to a greater score
+    pub lt: bool, // only update to a lower score
+    pub ch: bool, // count the updated members along with the new ones
+}
+
+// Outcome of SortedSet::add, with the score of the member
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ZAddOutcome {
+    Added(f64),
+    Updated(f64),
+    Unchanged(f64),
+    Skipped, // because of the options
+    NotANumber,
+}
+
+// Bound of a range of scores, `(` making it exclusive
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct ScoreBound {
+    pub value: f64,
+    pub exclusive: bool,
+}
+
+impl ScoreBound {
+    pub fn parse(bound: &[u8]) -> Option<Self> {
+        let (value, exclusive) = match bound.strip_prefix(b"(") {
+            Some(value) => (value, true),
+            None => (bound, false),
+        };
+        Some(Self {
+            value: parse_f64(value)?,
+            exclusive,
+        })
+    }
+}
+
+// Bound of a lexicographical range: `-` and `+` for the lowest and
+// greatest members, `[` and `(` prefixing inclusive and exclusive ones
+#[derive(Debug, PartialEq, Clone)]
+pub enum LexBound {
+    Min,
+    Max,
+    Inclusive(Vec<u8>),
+    Exclusive(Vec<u8>),
+}
+
+impl LexBound {
+    pub fn parse(bound: &[u8]) -> Option<Self> {
+        match bound {
+            b"-" => Some(LexBound::Min),
+            b"+" => Some(LexBound::Max),
+            [b'[', member @ ..] => Some(LexBound::Inclusive(member.to_vec())),
+            [b'(', member @ ..] => Some(LexBound::Exclusive(member.to_vec())),
+            _ => None,
+        }
+    }
+}
+
+// Members selected by ZRANGE: between two ranks, or between bounds of
+// scores or members, which are then given lowest first
+#[derive(Debug, PartialEq, Clone)]
+pub enum ZRangeBy {
+    Rank(i64, i64),
+    Score(ScoreBound, ScoreBound),
+    Lex(LexBound, LexBound),
+}
+
+// How ZUNIONSTORE and ZINTERSTORE combine the scores of a member
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub enum Aggregate {
+    #[default]
+    Sum,
+    Min,
+    Max,
+}
+
+impl Aggregate {
+    // Like Redis, a sum of opposite infinities is 0 rather than NaN
+    fn apply(self, a: f64, b: f64) -> f64 {
+        match self {
+            Aggregate::Sum => zero_if_nan(a + b),
+            Aggregate::Min => a.min(b),
+            Aggregate::Max => a.max(b),
+        }
+    }
+}
+
+fn zero_if_nan(score: f64) -> f64 {
+    if score.is_nan() {
+        0.0
+    } else {
+        score
+    }
+}
+
+// Input of ZUNIONSTORE, ZINTERSTORE and ZDIFFSTORE, which
+// take sets as well, their members having a score of 1
+#[derive(Clone, Copy)]
+pub enum Scored<'a> {
+    Set(&'a Set),
+    SortedSet(&'a SortedSet),
+}
+
+impl<'a> Scored<'a> {
+    fn len(&self) -> usize {
+        match self {
+            Scored::Set(set) => set.len(),
+            Scored::SortedSet(zset) => zset.len(),
+        }
+    }
+
+    fn score(&self, member: &[u8]) -> Option<f64> {
+        match self {
+            Scored::Set(set) => set.contains(member).then_some(1.0),
+            Scored::SortedSet(zset) => zset.score(member),
+        }
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = (Vec<u8>, f64)> + 'a> {
+        match *self {
+            Scored::Set(set) => Box::new(set.iter().map(|member| (member, 1.0))),
+            Scored::SortedSet(zset) => {
+                Box::new(zset.iter().map(|(member, score)| (member.to_vec(), score)))
+            }
+        }
+    }
+}
+
+// Scores are stored in the listpack as their 8 bytes, which read
+// back exactly without any formatting
+fn decode_score(bytes: &[u8]) -> f64 {
+    f64::from_le_bytes(bytes.try_into().unwrap_or_default())
+}
+
+fn listpack_entries(listpack: &Listpack) -> impl Iterator<Item = (&[u8], f64)> {
+    let mut entries = listpack.iter();
+    std::iter::from_fn(move || Some((entries.next()?, decode_score(entries.next()?))))
+}
+
+impl Default for SortedSet {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SortedSet {
+    pub fn new() -> Self {
+        Self {
+            encoding: Encoding::Listpack(Listpack::new()),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match &self.encoding {
+            Encoding::Listpack(listpack) => listpack.len() / 2,
+            Encoding::Skiplist { list, .. } => list.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // Number of allocations holding the set, a member being
+    // held by both the hash table and the skiplist
+    pub fn allocations(&self) -> usize {
+        match &self.encoding {
+            Encoding::Listpack(_) => 1,
+            Encoding::Skiplist { list, .. } => list.len() * 2,
+        }
+    }
+
+    pub fn score(&self, member: &[u8]) -> Option<f64> {
+        match &self.encoding {
+            Encoding::Listpack(listpack) => listpack_entries(listpack)
+                .find(|(m, _)| *m == member)
+                .map(|(_, score)| score),
+            Encoding::Skiplist { scores, .. } => scores.get(member).copied(),
+        }
+    }
+
+    // Set the score of a member, returns true if it is new
+    pub fn insert(&mut self, member: &[u8], score: f64) -> bool {
+        let new = match self.score(member) {
+            Some(current) if current == score => return false,
+            Some(current) => {
+                self.remove_entry(member, current);
+                false
+            }
+            None => true,
+        };
+        match &mut self.encoding {
+            Encoding::Listpack(listpack) => {
+                let rank = listpack_entries(listpack)
+                    .take_while(|&(m, s)| s < score || (s == score && m < member))
+                    .count();
+                listpack.insert(rank * 2, member);
+                listpack.insert(rank * 2 + 1, &score.to_le_bytes());
+            }
+            Encoding::Skiplist { scores, list } => {
+                scores.insert(member.to_vec(), score);
+                list.insert(score, member);
+            }
+        }
+        if member.len() > LISTPACK_MAX_VALUE || self.len() > LISTPACK_MAX_ENTRIES {
+            self.convert_to_skiplist();
+        }
+        new
+    }
+
+    // Returns true if the member existed
+    pub fn remove(&mut self, member: &[u8]) -> bool {
+        match self.score(member) {
+            Some(score) => {
+                self.remove_entry(member, score);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn remove_entry(&mut self, member: &[u8], score: f64) {
+        match &mut self.encoding {
+            Encoding::Listpack(listpack) => {
+                if let Some(position) = listpack.iter().step_by(2).position(|m| m == member) {
+                    listpack.remove(position * 2 + 1);
+                    listpack.remove(position * 2);
+                }
+            }
+            Encoding::Skiplist { scores, list } => {
+                scores.remove(member);
+                list.remove(score, member);
+            }
+        }
+    }
+
+    fn convert_to_skiplist(&mut self) {
+        if let Encoding::Listpack(listpack) = &self.encoding {
+            let mut scores = Dict::new();
+            let mut list = Skiplist::new();
+            for (member, score) in listpack_entries(listpack) {
+                scores.insert(member.to_vec(), score);
+                list.insert(score, member);
+            }
+            self.encoding = Encoding::Skiplist { scores, list };
+        }
+    }
+
+    // Add a member or update its score as ZADD does with its options,
+    // `score` being added to the current one if `increment` is set
+    pub fn add(
+        &mut self,
+        member: &[u8],
+        score: f64,
+        options: &ZAddOptions,
+        increment: bool,
+    ) -> ZAddOutcome {
+        let Some(current) = self.score(member) else {
+            if options.xx {
+                return ZAddOutcome::Skipped;
+            }
+            self.insert(member, score);
+            return ZAddOutcome::Added(score);
+        };
+        if options.nx {
+            return ZAddOutcome::Skipped;
+        }
+        let score = if increment { current + score } else { score };
+        if score.is_nan() {
+            return ZAddOutcome::NotANumber;
+        }
+        if (options.gt && score <= current) || (options.lt && score >= current) {
+            return ZAddOutcome::Skipped;
+        }
+        if score == current {
+            return ZAddOutcome::Unchanged(current);
+        }
+        self.insert(member, score);
+        ZAddOutcome::Updated(score)
+    }
+
+    // Number of members for which the predicate holds, which
+    // has to hold for the first members and not for the others
+    fn count_before(&self, before: impl Fn(f64, &[u8]) -> bool) -> usize {
+        match &self.encoding {
+            Encoding::Listpack(listpack) => listpack_entries(listpack)
+                .take_while(|&(member, score)| before(score, member))
+                .count(),
+            Encoding::Skiplist { list, .. } => list.count_before(before),
+        }
+    }
+
+    // Rank of a member, the member with the lowest score being 0
+    pub fn rank(&self, member: &[u8]) -> Option<usize> {
+        let score = self.score(member)?;
+        Some(self.count_before(|s, m| s < score || (s == score && m < member)))
+    }
+
+    // Ranks of the first member within the bounds and of
+    // the one following the last, as a range
+    pub fn score_window(&self, min: &ScoreBound, max: &ScoreBound) -> (usize, usize) {
+        let start = self
+            .count_before(|score, _| score < min.value || (min.exclusive && score == min.value));
+        let end = self
+            .count_before(|score, _| score < max.value || (!max.exclusive && score == max.value));
+        (start, end.max(start))
+    }
+
+    // Same as score_window for members, assuming that all the
+    // members have the same score, as ZRANGE BYLEX does
+    pub fn lex_window(&self, min: &LexBound, max: &LexBound) -> (usize, usize) {
+        let start = self.count_before(|_, member| match min {
+            LexBound::Min => false,
+            LexBound::Max => true,
+            LexBound::Inclusive(bound) => member < bound.as_slice(),
+            LexBound::Exclusive(bound) => member <= bound.as_slice(),
+        });
+        let end = self.count_before(|_, member| match max {
+            LexBound::Min => false,
+            LexBound::Max => true,
+            LexBound::Inclusive(bound) => member <= bound.as_slice(),
+            LexBound::Exclusive(bound) => member < bound.as_slice(),
+        });
+        (start, end.max(start))
+    }
+
+    // Members with their scores between two ranks, the end being
+    // excluded, from the last one if `reverse` is set
+    pub fn entries(&self, start: usize, end: usize, reverse: bool) -> Entries {
+        let end = end.min(self.len());
+        if start >= end {
+            return Vec::new();
+        }
+        let to_owned = |(member, score): (&[u8], f64)| (member.to_vec(), score);
+        match &self.encoding {
+            Encoding::Listpack(listpack) => {
+                let entries = listpack_entries(listpack).skip(start).take(end - start);
+                let mut entries: Entries = entries.map(to_owned).collect();
+                if reverse {
+                    entries.reverse();
+                }
+                entries
+            }
+            Encoding::Skiplist { list, .. } => {
+                let first = if reverse { end - 1 } else { start };
+                list.iter_from(first, reverse)
+                    .take(end - start)
+                    .map(to_owned)
+                    .collect()
+            }
+        }
+    }
+
+    // Result of an operation between sorted sets, a missing one
+    // being empty. The scores of each input are multiplied by its
+    // weight, then combined with `aggregate` for a union or an
+    // intersection, while a difference keeps the scores of the first.
+    pub fn combine(
+        inputs: &[Option<Scored>],
+        weights: &[f64],
+        aggregate: Aggregate,
+        operation: SetOperation,
+    ) -> SortedSet {
+        let weighted = |index: usize, score: f64| zero_if_nan(score * weights[index]);
+        let mut scores: HashMap<Vec<u8>, f64> = HashMap::new();
+        match operation {
+            SetOperation::Union => {
+                for (index, input) in inputs.iter().enumerate() {
+                    let Some(input) = input else { continue };
+                    for (member, score) in input.iter() {
+                        let score = weighted(index, score);
+                        scores
+                            .entry(member)
+                            .and_modify(|current| *current = aggregate.apply(*current, score))
+                            .or_insert(score);
+                    }
+                }
+            }
+            // members of the smallest input are looked up in the others
+            SetOperation::Intersection => {
+                let Some(mut inputs) = inputs
+                    .iter()
+                    .enumerate()
+                    .map(|(index, input)| input.map(|input| (index, input)))
+                    .collect::<Option<Vec<_>>>()
+                else {
+                    return SortedSet::new();
+                };
+                inputs.sort_by_key(|(_, input)| input.len());
+                if let Some(((first, smallest), others)) = inputs.split_first() {
+                    'members: for (member, score) in smallest.iter() {
+                        let mut score = weighted(*first, score);
+                        for (index, input) in others {
+                            let Some(other) = input.score(&member) else {
+                                continue 'members;
+                            };
+                            score = aggregate.apply(score, weighted(*index, other));
+                        }
+                        scores.insert(member, score);
+                    }
+                }
+            }
+            SetOperation::Difference => {
+                if let Some((Some(first), others)) = inputs.split_first() {
+                    for (member, score) in first.iter() {
+                        if !others
+                            .iter()
+                            .flatten()
+                            .any(|other| other.score(&member).is_some())
+                        {
+                            scores.insert(member, score);
+                        }
+                    }
+                }
+            }
+        }
+        let mut result = SortedSet::new();
+        for (member, score) in scores {
+            result.insert(&member, score);
+        }
+        result
+    }
+
+    // Remove `count` members from the lowest score, or from the
+    // highest if `max` is set, returning them in that order
+    pub fn pop(&mut self, count: usize, max: bool) -> Entries {
+        let len = self.len();
+        let count = count.min(len);
+        let entries = match max {
+            true => self.entries(len - count, len, true),
+            false => self.entries(0, count, false),
+        };
+        for (member, score) in entries.iter() {
+            self.remove_entry(member, *score);
+        }
+        entries
+    }
+
+    // Members with their scores, lowest first
+    pub fn iter(&self) -> Box<dyn Iterator<Item = (&[u8], f64)> + '_> {
+        match &self.encoding {
+            Encoding::Listpack(listpack) => Box::new(listpack_entries(listpack)),
+            Encoding::Skiplist { list, .. } => Box::new(list.iter_from(0, false)),
+        }
+    }
+}
+
+impl fmt::Debug for SortedSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(
+                self.iter()
+                    .map(|(member, score)| (String::from_utf8_lossy(member), score)),
+            )
+            .finish()
+    }
+}
+
+// Sorted sets are equal when their members and scores
+// are, whatever their encoding
+impl PartialEq for SortedSet {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && self
+                .iter()
+                .all(|(member, score)| other.score(member) == Some(score))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn zset(count: usize) -> SortedSet {
+        let mut zset = SortedSet::new();
+        for i in 0..count {
+            zset.insert(format!("m{:04}", i).as_bytes(), i as f64);
+        }
+        zset
+    }
+
+    fn members(entries: Entries) -> Vec<String> {
+        entries
+            .into_iter()
+            .map(|(member, _)| String::from_utf8(member).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_insert_score_remove() {
+        let mut zset = SortedSet::new();
+        assert!(zset.insert(b"b", 2.0));
+        assert!(zset.insert(b"a", 2.0));
+        assert!(zset.insert(b"c", 1.0));
+        assert!(!zset.insert(b"c", 3.0));
+        assert_eq!(zset.score(b"c"), Some(3.0));
+        assert_eq!(zset.rank(b"a"), Some(0));
+        assert_eq!(zset.rank(b"c"), Some(2));
+        assert!(zset.remove(b"a"));
+        assert!(!zset.remove(b"a"));
+        assert_eq!(members(zset.entries(0, 10, false)), vec!["b", "c"]);
+        assert!(matches!(zset.encoding, Encoding::Listpack(_)));
+    }
+
+    #[test]
+    fn test_converts_when_large() {
+        let mut zset = zset(LISTPACK_MAX_ENTRIES);
+        assert!(matches!(zset.encoding, Encoding::Listpack(_)));
+        let listpack = zset.clone();
+        zset.insert(b"one more", -1.0);
+        assert!(matches!(zset.encoding, Encoding::Skiplist { .. }));
+        assert_eq!(zset.rank(b"one more"), Some(0));
+        assert_eq!(zset.rank(b"m0007"), Some(8));
+        zset.remove(b"one more");
+        assert_eq!(zset, listpack);
+
+        let mut zset = SortedSet::new();
+        zset.insert(&[b'x'; LISTPACK_MAX_VALUE + 1], 1.0);
+        assert!(matches!(zset.encoding, Encoding::Skiplist { .. }));
+    }
+
+    #[test]
+    fn test_add_options() {
+        let mut zset = SortedSet::new();
+        let options = |nx, xx, gt, lt| ZAddOptions {
+            nx,
+            xx,
+            gt,
+            lt,
+            ch: false,
+        };
+        let none = options(false, false, false, false);
+        assert_eq!(zset.add(b"a", 1.0, &none, false), ZAddOutcome::Added(1.0));
+        assert_eq!(
+            zset.add(b"b", 1.0, &options(false, true, false, false), false),
+            ZAddOutcome::Skipped
+        );
+        assert_eq!(
+            zset.add(b"a", 2.0, &options(true, false, false, false), false),
+            ZAddOutcome::Skipped
+        );
+        assert_eq!(
+            zset.add(b"a", 0.5, &options(false, false, true, false), false),
+            ZAddOutcome::Skipped
+        );
+        assert_eq!(
+            zset.add(b"a", 0.5, &options(false, false, false, true), false),
+            ZAddOutcome::Updated(0.5)
+        );
+        assert_eq!(
+            zset.add(b"a", 0.5, &none, false),
+            ZAddOutcome::Unchanged(0.5)
+        );
+        assert_eq!(zset.add(b"a", 2.0, &none, true), ZAddOutcome::Updated(2.5));
+        zset.insert(b"inf", f64::INFINITY);
+        assert_eq!(
+            zset.add(b"inf", f64::NEG_INFINITY, &none, true),
+            ZAddOutcome::NotANumber
+        );
+    }
+
+    #[test]
+    fn test_windows() {
+        for zset in [zset(10), zset(1000)] {
+            let bound = |value, exclusive| ScoreBound { value, exclusive };
+            assert_eq!(
+                zset.score_window(&bound(2.0, false), &bound(5.0, false)),
+                (2, 6)
+            );
+            assert_eq!(
+                zset.score_window(&bound(2.0, true), &bound(5.0, true)),
+                (3, 5)
+            );
+            assert_eq!(
+                zset.score_window(&bound(f64::NEG_INFINITY, false), &bound(1.5, false)),
+                (0, 2)
+            );
+            assert_eq!(
+                zset.score_window(&bound(5.0, false), &bound(2.0, false)),
+                (5, 5)
+            );
+
+            let min = LexBound::Inclusive(b"m0003".to_vec());
+            let max = LexBound::Exclusive(b"m0005".to_vec());
+            assert_eq!(zset.lex_window(&min, &max), (3, 5));
+            assert_eq!(
+                zset.lex_window(&LexBound::Min, &LexBound::Max),
+                (0, zset.len())
+            );
+            assert_eq!(
+                zset.lex_window(&LexBound::Max, &LexBound::Min),
+                (zset.len(), zset.len())
+            );
+
+            assert_eq!(members(zset.entries(3, 5, false)), vec!["m0003", "m0004"]);
+            assert_eq!(members(zset.entries(3, 5, true)), vec!["m0004", "m0003"]);
+            assert!(zset.entries(5, 5, true).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_parse_bounds() {
+        assert_eq!(
+            ScoreBound::parse(b"(1.5"),
+            Some(ScoreBound {
+                value: 1.5,
+                exclusive: true
+            })
+        );
+        assert_eq!(
+            ScoreBound::parse(b"-inf").map(|bound| bound.value),
+            Some(f64::NEG_INFINITY)
+        );
+        assert_eq!(ScoreBound::parse(b"(nan"), None);
+        assert_eq!(ScoreBound::parse(b"x"), None);
+        assert_eq!(
+            LexBound::parse(b"[a"),
+            Some(LexBound::Inclusive(b"a".to_vec()))
+        );
+        assert_eq!(LexBound::parse(b"("), Some(LexBound::Exclusive(Vec::new())));
+        assert_eq!(LexBound::parse(b"+"), Some(LexBound::Max));
+        assert_eq!(LexBound::parse(b"a"), None);
+    }
+
+    #[test]
+    fn test_combine() {
+        let mut a = SortedSet::new();
+        a.insert(b"x", 1.0);
+        a.insert(b"y", 2.0);
+        a.insert(b"inf", f64::INFINITY);
+        let mut b = SortedSet::new();
+        b.insert(b"y", 10.0);
+        b.insert(b"inf", f64::NEG_INFINITY);
+        let mut set = Set::new();
+        set.insert(b"y");
+        set.insert(b"z");
+        let inputs = [
+            Some(Scored::SortedSet(&a)),
+            Some(Scored::SortedSet(&b)),
+            Some(Scored::Set(&set)),
+        ];
+        let scores = |zset: SortedSet| -> Vec<(String, f64)> {
+            zset.iter()
+                .map(|(member, score)| (String::from_utf8(member.to_vec()).unwrap(), score))
+                .collect()
+        };
+
+        let union = SortedSet::combine(
+            &inputs,
+            &[1.0, 2.0, 3.0],
+            Aggregate::Sum,
+            SetOperation::Union,
+        );
+        assert_eq!(
+            scores(union),
+            vec![
+                (String::from("inf"), 0.0),
+                (String::from("x"), 1.0),
+                (String::from("z"), 3.0),
+                (String::from("y"), 25.0),
+            ]
+        );
+        let intersection = SortedSet::combine(
+            &inputs,
+            &[1.0, 1.0, 1.0],
+            Aggregate::Max,
+            SetOperation::Intersection,
+        );
+        assert_eq!(scores(intersection), vec![(String::from("y"), 10.0)]);
+        let intersection = SortedSet::combine(
+            &[Some(Scored::SortedSet(&a)), None],
+            &[1.0, 1.0],
+            Aggregate::Sum,
+            SetOperation::Intersection,
+        );
+        assert!(intersection.is_empty());
+        let difference = SortedSet::combine(
+            &[Some(Scored::SortedSet(&a)), None, Some(Scored::Set(&set))],
+            &[1.0, 1.0, 1.0],
+            Aggregate::Sum,
+            SetOperation::Difference,
+        );
+        assert_eq!(
+            scores(difference),
+            vec![
+                (String::from("x"), 1.0),
+                (String::from("inf"), f64::INFINITY)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pop() {
+        for size in [10, LISTPACK_MAX_ENTRIES + 10] {
+            let mut zset = zset(size);
+            assert_eq!(members(zset.pop(2, false)), vec!["m0000", "m0001"]);
+            let last = format!("m{:04}", size - 1);
+            assert_eq!(members(zset.pop(1, true)), vec![last]);
+            assert_eq!(zset.len(), size - 3);
+            assert_eq!(zset.pop(size, true).len(), size - 3);
+            assert!(zset.is_empty());
+        }
+    }
+}